@@ -4,19 +4,152 @@
 /* auto-generated by NAPI-RS */
 
 export interface ConnectionOptions {
-  resourceArn: string
-  secretArn: string
-  database: string
-  region: string
+  /** `dataApi` (default) or `postgres`. */
+  driver?: string
+  resourceArn?: string
+  secretArn?: string
+  database?: string
+  region?: string
+  /** Overrides the Data API endpoint, e.g. `http://localhost:8080` for a local emulator. */
+  endpoint?: string
+  /** Static credentials used instead of the default AWS credentials chain, both or neither must be set. */
+  accessKeyId?: string
+  secretAccessKey?: string
+  sessionToken?: string
+  /** Connection string used by the `postgres` driver. */
+  url?: string
+  /**
+   * TLS of the `postgres` driver like libpq's `sslmode`: `disable`, `prefer` or `require` encrypt
+   * without checking the server's certificate, `verify-full` checks it was issued to the host by a
+   * trusted authority. Falls back to the `sslmode` of `url`, `prefer` by default.
+   */
+  sslMode?: string
+  /**
+   * PEM file of the certificate authorities `verify-full` trusts instead of the system's, e.g. the
+   * RDS certificate bundle.
+   */
+  sslRootCert?: string
+  /** Schema used for introspection, falls back to the `SCHEMA` variable when missing. */
+  schema?: string
+}
+/** Tables, views and enums of the introspected schemas. */
+export interface DatabaseSchema {
+  schemas: Array<string>
+  tables: Array<TableSchema>
+  views: Array<ViewSchema>
+  enums: Array<EnumSchema>
+}
+export interface TableSchema {
+  schema: string
+  name: string
+  comment?: string
+  columns: Array<ColumnSchema>
+  primaryKey?: KeyConstraint
+  uniqueConstraints: Array<KeyConstraint>
+  checkConstraints: Array<CheckConstraint>
+  indexes: Array<IndexSchema>
+  foreignKeys: Array<ForeignKeySchema>
+}
+/** A view, or a materialized view when `materialized` is set. */
+export interface ViewSchema {
+  schema: string
+  name: string
+  materialized: boolean
+  comment?: string
+  definition?: string
+  columns: Array<ColumnSchema>
+}
+export interface ColumnSchema {
+  name: string
+  /** Postgres type including modifiers, e.g. `character varying(255)` or `integer[]`. */
+  dataType: string
+  /** Name of the underlying type, e.g. `varchar`, `_int4` for arrays or the name of an enum. */
+  udtName: string
+  udtSchema: string
+  nullable: boolean
+  /** Default expression as Postgres prints it, e.g. `now()`. */
+  default?: string
+  /** `ALWAYS` or `BY DEFAULT` for identity columns. */
+  identity?: string
+  /** Set for columns defaulting to the next value of a sequence, as `serial` columns do. */
+  serial: boolean
+  comment?: string
+}
+/** A primary key or unique constraint. */
+export interface KeyConstraint {
+  name: string
+  columns: Array<string>
+}
+export interface CheckConstraint {
+  name: string
+  /** The constraint as Postgres prints it, e.g. `CHECK (price > 0)`. */
+  definition: string
+}
+export interface IndexSchema {
+  name: string
+  /** Key columns, or the expressions of expression indexes. */
+  columns: Array<string>
+  unique: boolean
+  primary: boolean
+  /** Access method, e.g. `btree` or `gin`. */
+  method: string
+  /** Condition of a partial index. */
+  predicate?: string
+  definition: string
+}
+export interface ForeignKeySchema {
+  name: string
+  columns: Array<string>
+  foreignSchema: string
+  foreignTable: string
+  foreignColumns: Array<string>
+  /** `NO ACTION`, `RESTRICT`, `CASCADE`, `SET NULL` or `SET DEFAULT`. */
+  onUpdate: string
+  onDelete: string
+}
+export interface EnumSchema {
+  schema: string
+  name: string
+  values: Array<string>
 }
 export interface IncludeField {
   joins: Array<Join>
+  /** Related rows returned as nested arrays or objects next to each row's columns. */
+  relations?: Array<Relation>
 }
 export interface Join {
   table: string
   key: string
   joiningTable: string
   joiningKey: string
+  /** `inner` (default), `left`, `right` or `full`. */
+  kind?: string
+  /** Name the joined table is referenced by, e.g. `manager` when joining `employees` to itself. */
+  alias?: string
+  /** Further columns of `table` for composite keys, matched in order with `joiningKeys`. */
+  keys?: Array<string>
+  /** Further columns of `joiningTable` for composite keys, matched in order with `keys`. */
+  joiningKeys?: Array<string>
+}
+export interface Relation {
+  /** Key the related rows are returned under. */
+  name: string
+  table: string
+  /** Column of the related table pointing at the parent row. */
+  key: string
+  /** Column of the parent table the key points at, `id` by default. */
+  references?: string
+  /** Returns the related row as an object instead of an array, e.g. for a post's author. */
+  one?: boolean
+  /** Relations included for every related row. */
+  include?: Array<Relation>
+  /** Columns of the related rows to return, all of them by default. */
+  select?: Array<string>
+  /** Conditions the related rows must match, written like a `where` field's arguments. */
+  where?: Array<Argument>
+  orderBy?: Array<OrderBy>
+  /** Maximum number of related rows returned for each parent row. */
+  take?: number
 }
 export interface Field {
   name: string
@@ -24,12 +157,60 @@ export interface Field {
 }
 export interface Argument {
   name: string
+  value?: string
+  /**
+   * `string`, `number`, `float`, `decimal`, `boolean`, `date`, `datetime`, `timestamptz`, `uuid`,
+   * `json`, `jsonb`, `bytes` (base64), `enum`, `custom`, `null`, or `array` for the elements in
+   * `values`. Values are cast to their column's type once models are registered.
+   */
+  valueType?: string
+  /**
+   * Filter applied in a `where` field: `equals`, `not`, `gt`, `gte`, `lt`, `lte`, `in`, `notIn`,
+   * `contains`, `startsWith`, `endsWith`, `between`, `isNull`, `isNotNull`, or `some`, `every` and
+   * `none` to filter by the related rows of the relation named by `name`. In a `set` field:
+   * `increment`, `decrement`, `multiply`, `divide`, `push`, `remove` or `append`. In an `aggs`
+   * field: `_sum`, `_min`, `_max`, `_avg`, `_count` or `_countDistinct`.
+   */
+  operator?: string
+  /** Values for the `in`, `notIn` and `between` operators, or the elements of an `array` value. */
+  values?: Array<ArgumentValue>
+  /**
+   * Nested conditions of an `AND`, `OR` or `NOT` group, of an aggregate group such as `_sum`
+   * in a `having` field, or of a `some`, `every` or `none` relation filter.
+   */
+  arguments?: Array<Argument>
+}
+/**
+ * One element of a list argument. Elements without their own `value_type` use the type of the
+ * argument they belong to.
+ */
+export interface ArgumentValue {
   value?: string
   valueType?: string
 }
 export interface OrderBy {
   field: string
   order: Order
+  /** `first` or `last`, defaults to the database's placement for the direction. */
+  nulls?: string
+  /** Joined table the field belongs to. */
+  table?: string
+  /** Orders by an aggregate of a related table instead of a column. */
+  relation?: RelationOrder
+  /**
+   * Orders groups by an aggregate of the field: `_sum`, `_min`, `_max`, `_avg`, `_count` or
+   * `_countDistinct`.
+   */
+  aggregate?: string
+}
+export interface RelationOrder {
+  table: string
+  /** Column of the related table pointing at this table. */
+  key: string
+  /** Column of this table the key points at, `id` by default. */
+  references?: string
+  /** `count` (default), `sum`, `avg`, `min` or `max` of `field`. */
+  function?: string
 }
 export const enum Order {
   Asc = 0,
@@ -42,6 +223,32 @@ export interface Options {
   numOfRows?: number
   include?: IncludeField
   groupBy?: Array<string>
+  /** Unique columns and values of the row pagination starts from. */
+  cursor?: Array<Argument>
+  /** Number of rows to return, counting backwards from the cursor when negative. */
+  take?: number
+  /** Number of rows to skip, e.g. `1` to leave out the cursor row itself. */
+  skip?: number
+  /** Returns `{ data, nextCursor, prevCursor, hasMore }` instead of the bare rows. */
+  paginate?: boolean
+  /** `nextCursor` or `prevCursor` of a previously returned page. */
+  pageCursor?: string
+  /** Groups a `groupBy` by time intervals of a timestamp column, returned as `bucket`. */
+  timeBucket?: TimeBucket
+}
+export interface TimeBucket {
+  field: string
+  /** `minute`, `hour`, `day`, `week`, `month`, `quarter` or `year`. */
+  unit: string
+  /**
+   * Time zone the buckets are aligned to, e.g. `Europe/Berlin`. The column's own zone by default.
+   * Columns without a time zone hold UTC times once models are registered, and session times before.
+   */
+  timezone?: string
+  /** First bucket of a series without gaps. Buckets without rows are returned with null aggregates. */
+  from?: string
+  /** Last bucket of a series without gaps, required together with `from`. */
+  to?: string
 }
 export interface Metadata {
   command: string
@@ -53,8 +260,41 @@ export interface Statement {
   fields: Array<Field>
   options: Options
 }
-export function connect(): Promise<void>
-export function introspect(): Promise<string>
-export function getForeignKeysData(table: string): Promise<string>
-export function query(fields: Array<Field>, options: Options, metadata: Metadata): Promise<string | null>
-export function queryRaw(queryString: string): Promise<string | null>
+/**
+ * Connects the client `name`, `default` when missing. Fails when a client with the same name is
+ * already connected.
+ */
+export function connect(options?: ConnectionOptions | undefined | null, name?: string | undefined | null): Promise<void>
+/** Closes the client so its name can be connected again. */
+export function disconnect(name?: string | undefined | null): Promise<void>
+export function introspect(client?: string | undefined | null): Promise<string>
+/** Introspects tables, views and enums of `schemas`, the client's schema by default. */
+export function introspectDatabaseSchema(schemas?: Array<string> | undefined | null, client?: string | undefined | null): Promise<DatabaseSchema>
+/**
+ * Validates the statements of the client against `schema` from now on, e.g. a schema returned
+ * by `introspectDatabaseSchema` earlier.
+ */
+export function setModels(schema: DatabaseSchema, client?: string | undefined | null): Promise<void>
+/** Introspects `schemas` and validates the statements of the client against them from now on. */
+export function loadModels(schemas?: Array<string> | undefined | null, client?: string | undefined | null): Promise<DatabaseSchema>
+export function getForeignKeysData(table: string, client?: string | undefined | null): Promise<string>
+export function beginTransaction(client?: string | undefined | null): Promise<string>
+export function commitTransaction(transactionId: string, client?: string | undefined | null): Promise<void>
+export function rollbackTransaction(transactionId: string, client?: string | undefined | null): Promise<void>
+export function query(fields: Array<Field>, options: Options, metadata: Metadata, transactionId?: string | undefined | null, client?: string | undefined | null): Promise<string | null>
+export function queryRaw(queryString: string, transactionId?: string | undefined | null, client?: string | undefined | null): Promise<string | null>
+/**
+ * Runs a statement like `query`, but resolves with the rows decoded by the types of their
+ * columns: `BigInt` for `int8`, `Date` for dates and timestamps, `Buffer` for `bytea`, arrays and
+ * parsed JSON. `numeric` values are returned as strings to keep their precision.
+ */
+export function queryTyped(fields: Array<Field>, options: Options, metadata: Metadata, transactionId?: string | undefined | null, client?: string | undefined | null): Promise<Array<Record<string, unknown>> | Record<string, unknown> | null>
+/** Runs raw SQL like `queryRaw`, resolving with rows decoded like the rows of `queryTyped`. */
+export function queryRawTyped(queryString: string, transactionId?: string | undefined | null, client?: string | undefined | null): Promise<Array<Record<string, unknown>> | null>
+export function transaction(statements: Array<Statement>, client?: string | undefined | null): Promise<Array<string | undefined | null>>
+/**
+ * Opens a transaction and hands its id to `callback`. The transaction is committed when the
+ * returned promise resolves and rolled back when it rejects. Resolves with the callback's result
+ * when it is JSON, `null` otherwise.
+ */
+export function interactiveTransaction(callback: (transactionId: string) => Promise<unknown>, client?: string | undefined | null): Promise<unknown>
@@ -252,11 +252,22 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { Order, connect, introspect, getForeignKeysData, query, queryRaw } = nativeBinding
+const { Order, connect, disconnect, introspect, introspectDatabaseSchema, setModels, loadModels, getForeignKeysData, beginTransaction, commitTransaction, rollbackTransaction, query, queryRaw, queryTyped, queryRawTyped, transaction, interactiveTransaction } = nativeBinding
 
 module.exports.Order = Order
 module.exports.connect = connect
+module.exports.disconnect = disconnect
 module.exports.introspect = introspect
+module.exports.introspectDatabaseSchema = introspectDatabaseSchema
+module.exports.setModels = setModels
+module.exports.loadModels = loadModels
 module.exports.getForeignKeysData = getForeignKeysData
+module.exports.beginTransaction = beginTransaction
+module.exports.commitTransaction = commitTransaction
+module.exports.rollbackTransaction = rollbackTransaction
 module.exports.query = query
 module.exports.queryRaw = queryRaw
+module.exports.queryTyped = queryTyped
+module.exports.queryRawTyped = queryRawTyped
+module.exports.transaction = transaction
+module.exports.interactiveTransaction = interactiveTransaction
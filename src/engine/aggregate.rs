@@ -0,0 +1,90 @@
+use super::executor::{error::RuntimeError, records::TypedValue};
+use crate::parser;
+use serde_json::{Map, Value};
+
+/// Reshapes the single row of an `aggregate` query into one object keyed by aggregate and then
+/// by column. Columns come back labelled `_sum.amount`, so `{ "_sum.amount": 10 }` becomes
+/// `{ "_sum": { "amount": 10 } }`. Only column names are camelcased, the aggregate keys keep their
+/// leading underscore.
+pub(crate) fn nest(formatted_records: &str) -> Result<String, RuntimeError> {
+  let rows = parse_rows(formatted_records)?;
+
+  let row = match rows.into_iter().next() {
+    Some(row) => nest_row(row),
+    None => Map::new(),
+  };
+
+  Ok(Value::Object(row).to_string())
+}
+
+/// Nests the aggregates of every `groupBy` row the same way [`nest`] does, next to the camelcased
+/// grouping columns.
+pub(crate) fn nest_groups(formatted_records: &str) -> Result<String, RuntimeError> {
+  let rows = parse_rows(formatted_records)?
+    .into_iter()
+    .map(|row| Value::Object(nest_row(row)))
+    .collect();
+
+  Ok(Value::Array(rows).to_string())
+}
+
+fn parse_rows(formatted_records: &str) -> Result<Vec<Map<String, Value>>, RuntimeError> {
+  let rows: Vec<Value> = match serde_json::from_str(formatted_records) {
+    Ok(rows) => rows,
+    Err(err) => return Err(RuntimeError::Error(err.to_string())),
+  };
+
+  Ok(
+    rows
+      .into_iter()
+      .filter_map(|row| match row {
+        Value::Object(row) => Some(row),
+        _ => None,
+      })
+      .collect(),
+  )
+}
+
+fn nest_row(row: Map<String, Value>) -> Map<String, Value> {
+  let mut result = Map::new();
+
+  for (label, value) in row {
+    let (aggregate, column) = match label.split_once('.') {
+      Some(parts) => parts,
+      None => {
+        result.insert(parser::utils::snake_to_camel_case(&label), value);
+        continue;
+      }
+    };
+
+    let column = match column.starts_with('_') {
+      true => column.to_string(),
+      false => parser::utils::snake_to_camel_case(column),
+    };
+
+    if let Value::Object(columns) = result.entry(aggregate).or_insert_with(|| Value::Object(Map::new())) {
+      columns.insert(column, value);
+    }
+  }
+
+  result
+}
+
+/// Adds a typed column to a row, nesting aggregate columns the way [`nest_row`] does.
+pub(crate) fn nest_typed(row: &mut Vec<(String, TypedValue)>, label: &str, value: TypedValue) {
+  let (aggregate, column) = match label.split_once('.') {
+    Some(parts) => parts,
+    None => return row.push((parser::utils::snake_to_camel_case(label), value)),
+  };
+
+  let column = match column.starts_with('_') {
+    true => column.to_string(),
+    false => parser::utils::snake_to_camel_case(column),
+  };
+
+  match row.iter_mut().find(|(key, _)| key == aggregate) {
+    Some((_, TypedValue::Object(columns))) => columns.push((column, value)),
+    Some(_) => {}
+    None => row.push((aggregate.to_string(), TypedValue::Object(vec![(column, value)]))),
+  }
+}
@@ -1,20 +1,123 @@
 use crate::{
-  engine::builder::{error::BuilderError, builder::QueryBuilder},
+  engine::builder::{
+    builder::QueryBuilder,
+    error::BuilderError,
+    options::{bucket_expression, bucket_interval},
+    parameters::BuiltQuery,
+  },
   parser::parsed::{FieldName, ParsedQuery},
 };
 
-pub fn count(query_schema: &ParsedQuery) -> Result<String, BuilderError> {
-  let builder = QueryBuilder::new(&query_schema)
+fn aggs_field_len(query_schema: &ParsedQuery) -> Result<usize, BuilderError> {
+  let arguments = query_schema
+    .fields
+    .iter()
+    .filter(|field| field.name == FieldName::Aggs)
+    .flat_map(|field| field.arguments.iter())
+    .collect::<Vec<_>>();
+
+  if arguments.iter().any(|argument| argument.aggregate.is_none()) {
+    return Err(BuilderError::InputError("Every aggregate column needs an aggregate operator."));
+  }
+
+  Ok(arguments.len())
+}
+
+pub fn count(query_schema: &ParsedQuery) -> Result<BuiltQuery, BuilderError> {
+  let mut builder = QueryBuilder::new(query_schema);
+  let query = builder
+    .order_fields(vec![FieldName::Aggs, FieldName::From, FieldName::Where])
+    .build_ordered()?;
+
+  Ok(builder.finish(format!("SELECT COUNT (*) FROM {}", query)))
+}
+
+pub fn average(query_schema: &ParsedQuery) -> Result<BuiltQuery, BuilderError> {
+  let mut builder = QueryBuilder::new(query_schema);
+  let query = builder
     .order_fields(vec![FieldName::Aggs, FieldName::From, FieldName::Where])
     .build_ordered()?;
 
-  Ok(format!("SELECT COUNT (*) FROM {}", builder))
+  Ok(builder.finish(format!("SELECT {}", query)))
 }
 
-pub fn average(query_schema: &ParsedQuery) -> Result<String, BuilderError> {
-  let builder = QueryBuilder::new(&query_schema)
+/// Computes every aggregate listed in `aggs` in a single pass. Each column is labelled with its
+/// aggregate key, e.g. `SUM(amount) AS "_sum.amount"`, so the row can be nested by aggregate.
+pub fn aggregate(query_schema: &ParsedQuery) -> Result<BuiltQuery, BuilderError> {
+  aggs_field_len(query_schema)?;
+
+  let mut builder = QueryBuilder::new(query_schema);
+  let query = builder
     .order_fields(vec![FieldName::Aggs, FieldName::From, FieldName::Where])
     .build_ordered()?;
 
-  Ok(format!("SELECT {}", builder))
+  Ok(builder.finish(format!("SELECT {}", query)))
+}
+
+/// Selects the `groupBy` columns together with the aggregates listed in `aggs` for every group.
+/// Groups can be filtered on their aggregates with a `having` field, and ordered and paged like rows.
+///
+/// A time bucket adds a `bucket` column grouping rows by interval. When the bucket has a `from` and
+/// `to`, the groups are joined onto a `generate_series` of every bucket in that range so charts
+/// get a continuous series, with null aggregates for buckets without rows. That series is always
+/// ordered by bucket, so it can't be ordered or paged otherwise.
+pub fn group_by(query_schema: &ParsedQuery) -> Result<BuiltQuery, BuilderError> {
+  let bucket = query_schema.options.time_bucket.as_ref();
+
+  let options = &query_schema.options;
+  let paged = options.order_by.is_some()
+    || options.cursor.is_some()
+    || options.take.is_some()
+    || options.skip.is_some()
+    || options.limit.is_some()
+    || options.offset.is_some();
+
+  if paged && bucket.is_some_and(|bucket| bucket.fill.is_some()) {
+    return Err(BuilderError::InputError(
+      "Filled time buckets are ordered by bucket and can't be combined with orderBy, cursor, take, skip, limit or offset.",
+    ));
+  }
+
+  let mut columns = Vec::new();
+  if let Some(bucket) = bucket {
+    columns.push(format!("{} AS bucket", bucket_expression(bucket)));
+  }
+  columns.extend(query_schema.options.group_by.iter().flatten().cloned());
+
+  if columns.is_empty() {
+    return Err(BuilderError::InputError("groupBy needs at least one column to group by."));
+  }
+
+  let columns = columns.join(", ");
+  let mut builder = QueryBuilder::new(query_schema);
+
+  let query = match aggs_field_len(query_schema)? {
+    0 => {
+      let query = builder.order_fields(vec![FieldName::From, FieldName::Where]).build_ordered()?;
+      format!("SELECT {} FROM {}", columns, query)
+    }
+    _ => {
+      let query = builder
+        .order_fields(vec![FieldName::Aggs, FieldName::From, FieldName::Where])
+        .build_ordered()?;
+      format!("SELECT {}, {}", columns, query)
+    }
+  };
+
+  let (bucket, (from, to)) = match bucket.and_then(|bucket| bucket.fill.as_ref().map(|fill| (bucket, fill))) {
+    Some(filled) => filled,
+    None => return Ok(builder.finish(query)),
+  };
+
+  let from = builder.bind(from.clone());
+  let to = builder.bind(to.clone());
+  Ok(builder.finish(format!(
+    "SELECT * FROM generate_series(date_trunc('{}', CAST({} AS timestamp)), CAST({} AS timestamp), {}) AS auro_series(bucket) \
+     LEFT JOIN ({}) AS auro_buckets USING (bucket) ORDER BY bucket",
+    bucket.unit,
+    from,
+    to,
+    bucket_interval(bucket),
+    query
+  )))
 }
@@ -1,8 +1,12 @@
 #[cfg(test)]
 mod tests {
   use crate::{
-    engine::builder::aggregations::aggregations::{average, count},
-    parser::parsed::{FieldName, ParsedArgument, ParsedField, ParsedQuery, ParsedValue, QueryOptions},
+    engine::builder::aggregations::aggregations::{aggregate, average, count, group_by},
+    engine::builder::error::BuilderError,
+    parser::parsed::{
+      FieldName, ParsedAggregate, ParsedArgument, ParsedField, ParsedFilter, ParsedOrderBy, ParsedQuery, ParsedTimeBucket,
+      ParsedValue, QueryOptions,
+    },
   };
 
   #[test]
@@ -14,6 +18,7 @@ mod tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -25,6 +30,7 @@ mod tests {
         arguments: vec![ParsedArgument {
           name: "arg1".to_string(),
           value: Some(ParsedValue::String("value1".to_string())),
+          ..Default::default()
         }],
       }],
     };
@@ -32,10 +38,10 @@ mod tests {
     let result = count(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "SELECT COUNT (*) FROM  my_schema.my_table WHERE arg1 = 'value1' "
+      "SELECT COUNT (*) FROM  my_schema.my_table WHERE arg1 = :p1 "
     );
   }
 
@@ -48,6 +54,7 @@ mod tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -60,7 +67,7 @@ mod tests {
     let result = count(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(generated_sql, "SELECT COUNT (*) FROM  my_schema.my_table  ");
   }
 
@@ -73,6 +80,7 @@ mod tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -85,6 +93,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -92,6 +101,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -100,8 +110,8 @@ mod tests {
     let result = average(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
-    assert_eq!(generated_sql, "SELECT AVG(arg1) FROM my_schema.my_table WHERE arg1 = 'value1' ");
+    let generated_sql = result.unwrap().sql;
+    assert_eq!(generated_sql, "SELECT AVG(arg1) FROM my_schema.my_table WHERE arg1 = :p1 ");
   }
 
   #[test]
@@ -113,6 +123,7 @@ mod tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -124,6 +135,7 @@ mod tests {
         arguments: vec![ParsedArgument {
           name: "arg1".to_string(),
           value: Some(ParsedValue::String("value1".to_string())),
+          ..Default::default()
         }],
       }],
     };
@@ -131,7 +143,222 @@ mod tests {
     let result = average(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(generated_sql, "SELECT AVG(arg1) FROM my_schema.my_table  ");
   }
+
+  fn aggregate_query(arguments: Vec<(&str, Option<ParsedAggregate>)>) -> ParsedQuery {
+    ParsedQuery {
+      table: "orders".to_string(),
+      schema: "my_schema".to_string(),
+      options: QueryOptions {
+        order_by: None,
+        limit: None,
+        offset: None,
+        num_of_rows: None,
+        include: None,
+        group_by: None,
+        ..Default::default()
+      },
+      fields: vec![
+        ParsedField {
+          name: FieldName::Where,
+          arguments: vec![ParsedArgument {
+            name: "status".to_string(),
+            value: Some(ParsedValue::String("paid".to_string())),
+            ..Default::default()
+          }],
+        },
+        ParsedField {
+          name: FieldName::Aggs,
+          arguments: arguments
+            .into_iter()
+            .map(|(name, aggregate)| ParsedArgument {
+              name: name.to_string(),
+              value: None,
+              aggregate,
+              ..Default::default()
+            })
+            .collect(),
+        },
+      ],
+    }
+  }
+
+  #[test]
+  fn test_aggregate() {
+    let parsed_query = aggregate_query(vec![
+      ("amount", Some(ParsedAggregate::Sum)),
+      ("amount", Some(ParsedAggregate::Avg)),
+      ("created_at", Some(ParsedAggregate::Min)),
+      ("created_at", Some(ParsedAggregate::Max)),
+      ("_all", Some(ParsedAggregate::Count)),
+      ("customer_id", Some(ParsedAggregate::CountDistinct)),
+    ]);
+
+    let result = aggregate(&parsed_query);
+    assert!(result.is_ok());
+
+    let generated_sql = result.unwrap().sql;
+    assert_eq!(
+      generated_sql,
+      "SELECT SUM(amount) AS \"_sum.amount\", AVG(amount) AS \"_avg.amount\", MIN(created_at) AS \"_min.created_at\", \
+       MAX(created_at) AS \"_max.created_at\", COUNT(*) AS \"_count._all\", \
+       COUNT(DISTINCT customer_id) AS \"_countDistinct.customer_id\" FROM my_schema.orders WHERE status = :p1 "
+    );
+  }
+
+  #[test]
+  fn test_aggregate_without_operator() {
+    let parsed_query = aggregate_query(vec![("amount", Some(ParsedAggregate::Sum)), ("amount", None)]);
+
+    assert_eq!(
+      aggregate(&parsed_query).map(|query| query.sql),
+      Err(BuilderError::InputError("Every aggregate column needs an aggregate operator."))
+    );
+  }
+
+  #[test]
+  fn test_aggregate_all_without_count() {
+    let parsed_query = aggregate_query(vec![("_all", Some(ParsedAggregate::Sum))]);
+
+    assert_eq!(
+      aggregate(&parsed_query).map(|query| query.sql),
+      Err(BuilderError::InputError("Only _count can aggregate _all."))
+    );
+  }
+
+  #[test]
+  fn test_group_by() {
+    let mut parsed_query = aggregate_query(vec![("amount", Some(ParsedAggregate::Sum)), ("_all", Some(ParsedAggregate::Count))]);
+    parsed_query.options.group_by = Some(vec!["customer_id".to_string(), "currency".to_string()]);
+    parsed_query.options.order_by = Some(vec![ParsedOrderBy {
+      field: "amount".to_string(),
+      order: "desc".to_string(),
+      aggregate: Some(ParsedAggregate::Sum),
+      ..Default::default()
+    }]);
+    parsed_query.options.take = Some(10);
+    parsed_query.options.skip = Some(20);
+    parsed_query.fields.push(ParsedField {
+      name: FieldName::Having,
+      arguments: vec![ParsedArgument {
+        name: "_sum".to_string(),
+        value: None,
+        filter: Some(ParsedFilter::And(vec![ParsedArgument {
+          name: "amount".to_string(),
+          value: None,
+          filter: Some(ParsedFilter::Gt(ParsedValue::Number("100".to_string()))),
+          update: None,
+          aggregate: Some(ParsedAggregate::Sum),
+        }])),
+        aggregate: Some(ParsedAggregate::Sum),
+        ..Default::default()
+      }],
+    });
+
+    let result = group_by(&parsed_query);
+    assert!(result.is_ok());
+
+    let built_query = result.unwrap();
+    assert_eq!(
+      built_query.sql,
+      "SELECT customer_id, currency, SUM(amount) AS \"_sum.amount\", COUNT(*) AS \"_count._all\" FROM my_schema.orders \
+       WHERE status = :p1 GROUP BY customer_id, currency HAVING (SUM(amount) > :p2) ORDER BY SUM(amount) DESC LIMIT 10 OFFSET 20"
+    );
+    assert_eq!(built_query.parameters.len(), 2);
+  }
+
+  #[test]
+  fn test_group_by_without_aggregates() {
+    let mut parsed_query = aggregate_query(vec![]);
+    parsed_query.options.group_by = Some(vec!["customer_id".to_string()]);
+
+    let result = group_by(&parsed_query);
+    assert!(result.is_ok());
+
+    assert_eq!(
+      result.unwrap().sql,
+      "SELECT customer_id FROM my_schema.orders WHERE status = :p1 GROUP BY customer_id"
+    );
+  }
+
+  #[test]
+  fn test_group_by_without_columns() {
+    let parsed_query = aggregate_query(vec![("amount", Some(ParsedAggregate::Sum))]);
+
+    assert_eq!(
+      group_by(&parsed_query).map(|query| query.sql),
+      Err(BuilderError::InputError("groupBy needs at least one column to group by."))
+    );
+  }
+
+  #[test]
+  fn test_group_by_time_bucket() {
+    let mut parsed_query = aggregate_query(vec![("_all", Some(ParsedAggregate::Count))]);
+    parsed_query.options.time_bucket = Some(ParsedTimeBucket {
+      column: "created_at".to_string(),
+      unit: "day".to_string(),
+      timezone: Some("Europe/Berlin".to_string()),
+      column_type: None,
+      fill: None,
+    });
+    parsed_query.options.group_by = Some(vec!["channel".to_string()]);
+
+    let result = group_by(&parsed_query);
+    assert!(result.is_ok());
+
+    assert_eq!(
+      result.unwrap().sql,
+      "SELECT CAST(date_trunc('day', created_at AT TIME ZONE 'Europe/Berlin') AS timestamp) AS bucket, channel, \
+       COUNT(*) AS \"_count._all\" FROM my_schema.orders WHERE status = :p1 \
+       GROUP BY CAST(date_trunc('day', created_at AT TIME ZONE 'Europe/Berlin') AS timestamp), channel"
+    );
+  }
+
+  #[test]
+  fn test_group_by_time_bucket_with_fill() {
+    let mut parsed_query = aggregate_query(vec![("amount", Some(ParsedAggregate::Sum))]);
+    parsed_query.options.time_bucket = Some(ParsedTimeBucket {
+      column: "created_at".to_string(),
+      unit: "quarter".to_string(),
+      timezone: None,
+      column_type: None,
+      fill: Some((
+        ParsedValue::DateTime("2023-01-01 00:00:00".to_string()),
+        ParsedValue::DateTime("2023-12-31 00:00:00".to_string()),
+      )),
+    });
+
+    let result = group_by(&parsed_query);
+    assert!(result.is_ok());
+
+    let built_query = result.unwrap();
+    assert_eq!(
+      built_query.sql,
+      "SELECT * FROM generate_series(date_trunc('quarter', CAST(:p2 AS timestamp)), CAST(:p3 AS timestamp), interval '3 months') \
+       AS auro_series(bucket) LEFT JOIN (SELECT CAST(date_trunc('quarter', created_at) AS timestamp) AS bucket, \
+       SUM(amount) AS \"_sum.amount\" FROM my_schema.orders WHERE status = :p1 \
+       GROUP BY CAST(date_trunc('quarter', created_at) AS timestamp)) AS auro_buckets USING (bucket) ORDER BY bucket"
+    );
+    assert_eq!(built_query.parameters.len(), 3);
+  }
+
+  #[test]
+  fn test_group_by_time_bucket_with_fill_and_take() {
+    let mut parsed_query = aggregate_query(vec![("amount", Some(ParsedAggregate::Sum))]);
+    parsed_query.options.take = Some(10);
+    parsed_query.options.time_bucket = Some(ParsedTimeBucket {
+      column: "created_at".to_string(),
+      unit: "day".to_string(),
+      timezone: None,
+      column_type: None,
+      fill: Some((
+        ParsedValue::DateTime("2023-01-01 00:00:00".to_string()),
+        ParsedValue::DateTime("2023-01-31 00:00:00".to_string()),
+      )),
+    });
+
+    assert!(matches!(group_by(&parsed_query), Err(BuilderError::InputError(_))));
+  }
 }
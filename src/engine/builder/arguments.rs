@@ -1,19 +1,182 @@
-use super::error::BuilderError;
-use crate::parser::parsed::{ParsedArgument, ParsedValue};
+use super::{error::BuilderError, parameters::Parameters};
+use crate::{
+  constants::constants,
+  parser::parsed::{
+    ParsedAggregate, ParsedArgument, ParsedFilter, ParsedQuantifier, ParsedRelationFilter, ParsedUpdate, ParsedValue,
+  },
+};
+
+/// Renders a condition on a column, or on an aggregate of it when used in a `having` field.
+pub(crate) fn build_where(argument: &ParsedArgument, parameters: &mut Parameters) -> Result<String, BuilderError> {
+  let name = match &argument.aggregate {
+    Some(aggregate) => aggregate_expression(aggregate, &argument.name)?,
+    None => argument.name.clone(),
+  };
+
+  if let Some(filter) = &argument.filter {
+    return build_filter(&name, filter, parameters);
+  }
 
-pub(crate) fn build_where(argument: &ParsedArgument) -> Result<String, BuilderError> {
   let value_string = match &argument.value {
     Some(ParsedValue::Custom(custom_value)) => {
-      format!("{} {}", argument.name, custom_value)
+      format!("{} {}", name, custom_value)
+    }
+    Some(ParsedValue::Null) => {
+      format!("{} IS NULL", name)
     }
+    None => return Err(BuilderError::MissingArgumentValue("Where arguments require a value or a filter.")),
     _ => {
-      format!("{} = {}", argument.name, parse_value(&argument.value))
+      format!("{} = {}", name, bind_value(&argument.value, parameters))
     }
   };
 
   Ok(value_string)
 }
 
+pub(crate) fn build_filter(name: &str, filter: &ParsedFilter, parameters: &mut Parameters) -> Result<String, BuilderError> {
+  let condition = match filter {
+    ParsedFilter::Equals(ParsedValue::Null) | ParsedFilter::IsNull => format!("{} IS NULL", name),
+    ParsedFilter::Not(ParsedValue::Null) | ParsedFilter::IsNotNull => format!("{} IS NOT NULL", name),
+    ParsedFilter::Equals(value) => build_comparison(name, "=", value, parameters),
+    ParsedFilter::Not(value) => build_comparison(name, "<>", value, parameters),
+    ParsedFilter::Gt(value) => build_comparison(name, ">", value, parameters),
+    ParsedFilter::Gte(value) => build_comparison(name, ">=", value, parameters),
+    ParsedFilter::Lt(value) => build_comparison(name, "<", value, parameters),
+    ParsedFilter::Lte(value) => build_comparison(name, "<=", value, parameters),
+    // An empty list matches nothing, so IN is always false and NOT IN always true.
+    ParsedFilter::In(values) if values.is_empty() => "FALSE".to_string(),
+    ParsedFilter::NotIn(values) if values.is_empty() => "TRUE".to_string(),
+    ParsedFilter::In(values) => format!("{}{}({})", name, constants::IN, bind_list(values, parameters)),
+    ParsedFilter::NotIn(values) => format!("{}{}({})", name, constants::NOT_IN, bind_list(values, parameters)),
+    ParsedFilter::Contains(value) => build_like(name, "%", value, "%", parameters)?,
+    ParsedFilter::StartsWith(value) => build_like(name, "", value, "%", parameters)?,
+    ParsedFilter::EndsWith(value) => build_like(name, "%", value, "", parameters)?,
+    ParsedFilter::Between(from, to) => format!(
+      "{} BETWEEN {} AND {}",
+      name,
+      bind_value(&Some(from.clone()), parameters),
+      bind_value(&Some(to.clone()), parameters)
+    ),
+    ParsedFilter::And(arguments) => build_group(arguments, constants::AND, "TRUE", parameters)?,
+    ParsedFilter::Or(arguments) => build_group(arguments, constants::OR, "FALSE", parameters)?,
+    ParsedFilter::Negate(arguments) => match arguments.is_empty() {
+      true => "TRUE".to_string(),
+      false => format!("NOT {}", build_group(arguments, constants::AND, "TRUE", parameters)?),
+    },
+    ParsedFilter::Relation(relation) => build_relation_filter(relation, parameters)?,
+  };
+
+  Ok(condition)
+}
+
+/// Renders `some` as `EXISTS` a related row matching the conditions, `none` as `NOT EXISTS` one, and
+/// `every` as `NOT EXISTS` a related row the conditions aren't true for.
+fn build_relation_filter(relation: &ParsedRelationFilter, parameters: &mut Parameters) -> Result<String, BuilderError> {
+  let table = match &relation.table {
+    Some(table) if !relation.keys.is_empty() => table,
+    _ => return Err(BuilderError::InputError("Relation filters need a relation to filter by.")),
+  };
+
+  let mut conditions = relation
+    .keys
+    .iter()
+    .map(|(key, references)| format!("{}.{} = {}.{}", relation.alias, key, relation.parent, references))
+    .collect::<Vec<_>>();
+
+  if !relation.arguments.is_empty() {
+    let condition = build_group(&relation.arguments, constants::AND, "TRUE", parameters)?;
+
+    match relation.quantifier {
+      ParsedQuantifier::Every => conditions.push(format!("{} IS NOT TRUE", condition)),
+      _ => conditions.push(condition),
+    }
+  } else if relation.quantifier == ParsedQuantifier::Every {
+    return Ok("TRUE".to_string());
+  }
+
+  let exists = match relation.quantifier {
+    ParsedQuantifier::Some => "EXISTS",
+    ParsedQuantifier::Every | ParsedQuantifier::None => "NOT EXISTS",
+  };
+
+  Ok(format!(
+    "{} (SELECT 1 FROM {} AS {} WHERE {})",
+    exists,
+    table,
+    relation.alias,
+    conditions.join(constants::AND)
+  ))
+}
+
+/// Joins nested conditions with `separator` inside parentheses so the group keeps its meaning
+/// wherever it is embedded. An empty group renders as its identity, `TRUE` for AND, `FALSE` for OR.
+fn build_group(
+  arguments: &[ParsedArgument],
+  separator: &str,
+  empty: &str,
+  parameters: &mut Parameters,
+) -> Result<String, BuilderError> {
+  if arguments.is_empty() {
+    return Ok(empty.to_string());
+  }
+
+  let conditions = arguments
+    .iter()
+    .map(|argument| build_where(argument, parameters))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  Ok(format!("({})", conditions.join(separator)))
+}
+
+fn build_comparison(name: &str, operator: &str, value: &ParsedValue, parameters: &mut Parameters) -> String {
+  format!("{} {} {}", name, operator, bind_value(&Some(value.clone()), parameters))
+}
+
+fn bind_list(values: &[ParsedValue], parameters: &mut Parameters) -> String {
+  values
+    .iter()
+    .map(|value| bind_value(&Some(value.clone()), parameters))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// Binds a LIKE pattern around `value`. Wildcards inside the value itself are escaped so they
+/// match literally.
+fn build_like(
+  name: &str,
+  prefix: &str,
+  value: &ParsedValue,
+  suffix: &str,
+  parameters: &mut Parameters,
+) -> Result<String, BuilderError> {
+  let value = match value {
+    ParsedValue::String(value) => value,
+    _ => return Err(BuilderError::InputError("Pattern filters need a string value.")),
+  };
+
+  let escaped = value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+  let pattern = ParsedValue::String(format!("{}{}{}", prefix, escaped, suffix));
+
+  Ok(format!("{} LIKE {}", name, parameters.bind(pattern)))
+}
+
+pub(crate) fn build_set(argument: &ParsedArgument, parameters: &mut Parameters) -> String {
+  let name = &argument.name;
+
+  let value = match &argument.update {
+    Some(ParsedUpdate::Increment(value)) => format!("{} + {}", name, bind_value(&Some(value.clone()), parameters)),
+    Some(ParsedUpdate::Decrement(value)) => format!("{} - {}", name, bind_value(&Some(value.clone()), parameters)),
+    Some(ParsedUpdate::Multiply(value)) => format!("{} * {}", name, bind_value(&Some(value.clone()), parameters)),
+    Some(ParsedUpdate::Divide(value)) => format!("{} / {}", name, bind_value(&Some(value.clone()), parameters)),
+    Some(ParsedUpdate::Push(value)) => format!("array_append({}, {})", name, bind_value(&Some(value.clone()), parameters)),
+    Some(ParsedUpdate::Remove(value)) => format!("array_remove({}, {})", name, bind_value(&Some(value.clone()), parameters)),
+    Some(ParsedUpdate::Append(value)) => format!("{} || {}", name, bind_value(&Some(value.clone()), parameters)),
+    None => bind_value(&argument.value, parameters),
+  };
+
+  format!("{} = {}", name, value)
+}
+
 pub(crate) fn build_return(argument: &ParsedArgument) -> Result<String, BuilderError> {
   Ok(argument.name.to_string())
 }
@@ -22,18 +185,51 @@ pub(crate) fn build_select(argument: &ParsedArgument) -> Result<String, BuilderE
   Ok(argument.name.to_string())
 }
 
+/// Renders an aggregate column. Without an aggregate operator the column is averaged, which is
+/// what the `average` command expects. `_all` stands for every row and can only be counted.
 pub(crate) fn build_aggregate(argument: &ParsedArgument) -> Result<String, BuilderError> {
-  Ok(format!("AVG({})", argument.name.to_string()))
+  let aggregate = match &argument.aggregate {
+    Some(aggregate) => aggregate,
+    None => return Ok(format!("AVG({})", argument.name)),
+  };
+
+  Ok(format!(
+    "{} AS \"{}.{}\"",
+    aggregate_expression(aggregate, &argument.name)?,
+    aggregate.key(),
+    argument.name
+  ))
+}
+
+pub(crate) fn aggregate_expression(aggregate: &ParsedAggregate, column: &str) -> Result<String, BuilderError> {
+  let column = match (column, aggregate) {
+    ("_all", ParsedAggregate::Count) => "*",
+    ("_all", _) => return Err(BuilderError::InputError("Only _count can aggregate _all.")),
+    (column, _) => column,
+  };
+
+  let expression = match aggregate {
+    ParsedAggregate::Sum => format!("SUM({})", column),
+    ParsedAggregate::Min => format!("MIN({})", column),
+    ParsedAggregate::Max => format!("MAX({})", column),
+    ParsedAggregate::Avg => format!("AVG({})", column),
+    ParsedAggregate::Count => format!("COUNT({})", column),
+    ParsedAggregate::CountDistinct => format!("COUNT(DISTINCT {})", column),
+  };
+
+  Ok(expression)
 }
 
-pub(crate) fn parse_value(value: &Option<ParsedValue>) -> String {
+/// Renders a value as a placeholder bound in `parameters`. `NULL` and `Custom` fragments are
+/// the only values still written into the SQL text. Values the drivers send as text, such as arrays
+/// or enum labels of a known column, are cast to their type.
+pub(crate) fn bind_value(value: &Option<ParsedValue>, parameters: &mut Parameters) -> String {
   match value {
-    Some(ParsedValue::String(s)) | Some(ParsedValue::Date(s)) | Some(ParsedValue::DateTime(s)) => format!("'{}'", s),
-    Some(ParsedValue::Number(n)) => n.to_string(),
-    Some(ParsedValue::Boolean(b)) => b.to_string(),
-    Some(ParsedValue::Float(f)) => f.to_string(),
-    Some(ParsedValue::Null) => "NULL".to_string(),
+    Some(ParsedValue::Null) | None => "NULL".to_string(),
     Some(ParsedValue::Custom(c)) => c.to_string(),
-    None => "NULL".to_string(),
+    Some(value) => match value.cast() {
+      Some(cast) => format!("CAST({} AS {})", parameters.bind(value.clone()), cast),
+      None => parameters.bind(value.clone()),
+    },
   }
 }
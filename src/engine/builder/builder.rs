@@ -1,12 +1,19 @@
-use super::{error::BuilderError, fields, options::build_options};
+use super::{
+  error::BuilderError,
+  fields,
+  options::{build_cursor, build_options},
+  parameters::{BuiltQuery, Parameters},
+  relations::{build_relation_columns, build_relation_joins},
+};
 use crate::{
   constants::{constants, errors},
-  parser::parsed::{FieldName, ParsedQuery},
+  parser::parsed::{FieldName, ParsedQuery, ParsedRelation, ParsedValue},
 };
 
 pub struct QueryBuilder<'a> {
   query: &'a ParsedQuery,
   field_order: Vec<FieldName>,
+  parameters: Parameters,
 }
 
 impl<'a> QueryBuilder<'a> {
@@ -14,6 +21,7 @@ impl<'a> QueryBuilder<'a> {
     QueryBuilder {
       query,
       field_order: Vec::new(),
+      parameters: Parameters::new(),
     }
   }
 
@@ -30,39 +38,76 @@ impl<'a> QueryBuilder<'a> {
     }
   }
 
+  fn relations(&self) -> &'a [ParsedRelation] {
+    let query = self.query;
+
+    match &query.options.include {
+      Some(include) => &include.relations,
+      None => &[],
+    }
+  }
+
   fn build_select(&self) -> Result<String, BuilderError> {
-    if let Some(select_field) = self.query.fields.iter().find(|field| field.name == FieldName::Select) {
-      fields::build_select(select_field)
+    let relations = build_relation_columns(self.relations());
+
+    let select = if let Some(select_field) = self.query.fields.iter().find(|field| field.name == FieldName::Select) {
+      fields::build_select(select_field)?
+    } else if relations.is_empty() {
+      format!("{} FROM", constants::SELECT_ALL)
     } else {
-      Ok(format!("{} FROM", constants::SELECT_ALL))
+      // The relations' lateral joins add columns of their own, so only the table's are selected.
+      format!("{} {}.{}.* FROM", constants::SELECT, self.query.schema, self.query.table)
+    };
+
+    if relations.is_empty() {
+      return Ok(select);
     }
+
+    // Included relations are selected after the columns, before the FROM ending the select list.
+    let columns = select.strip_suffix(" FROM").unwrap_or(&select);
+    Ok(format!("{}, {} FROM", columns, relations.join(", ")))
   }
 
-  fn build_where(&self) -> Result<String, BuilderError> {
-    if let Some(where_field) = self.query.fields.iter().find(|field| field.name == FieldName::Where) {
-      fields::build_where(where_field)
+  fn build_where(&mut self) -> Result<String, BuilderError> {
+    let where_clause = if let Some(where_field) = self.query.fields.iter().find(|field| field.name == FieldName::Where) {
+      fields::build_where(where_field, &mut self.parameters)?
     } else {
-      Ok(String::new())
+      String::new()
+    };
+
+    let cursor = build_cursor(&self.query.options, &mut self.parameters)?;
+
+    match (where_clause.is_empty(), cursor.is_empty()) {
+      (_, true) => Ok(where_clause),
+      (true, false) => Ok(format!("{} {}", constants::WHERE, cursor)),
+      (false, false) => Ok(format!("{}{}{}", where_clause, constants::AND, cursor)),
     }
   }
 
-  fn build_set(&self) -> Result<String, BuilderError> {
+  fn build_set(&mut self) -> Result<String, BuilderError> {
     if let Some(set_field) = self.query.fields.iter().find(|field| field.name == FieldName::Set) {
-      fields::build_set(set_field)
+      fields::build_set(set_field, &mut self.parameters)
     } else {
       Ok(String::new())
     }
   }
 
-  fn build_data_one(&self) -> Result<String, BuilderError> {
+  fn build_conflict(&mut self) -> Result<String, BuilderError> {
+    let conflict_field = self.query.fields.iter().find(|field| field.name == FieldName::Conflict);
+    let set_field = self.query.fields.iter().find(|field| field.name == FieldName::Set);
+
+    fields::build_conflict(conflict_field, set_field, &mut self.parameters)
+  }
+
+  fn build_data_one(&mut self) -> Result<String, BuilderError> {
     if let Some(data_field) = self.query.fields.iter().find(|field| field.name == FieldName::Data) {
-      fields::build_one_data(data_field)
+      fields::build_one_data(data_field, &mut self.parameters)
     } else {
       Ok(String::new())
     }
   }
 
-  fn build_from(&self) -> Result<String, BuilderError> {
+  fn build_from(&mut self) -> Result<String, BuilderError> {
     if self.query.schema.is_empty() {
       return Err(BuilderError::MissingSchema(errors::MISSING_SCHEMA));
     }
@@ -71,11 +116,32 @@ impl<'a> QueryBuilder<'a> {
     }
 
     let from_clause = format!("{}.{}", self.query.schema, self.query.table);
-    Ok(from_clause)
+
+    // Relations are only selected by queries with a select list, which joins them here.
+    if self.relations().is_empty() || !self.field_order.contains(&FieldName::Select) {
+      return Ok(from_clause);
+    }
+
+    let joins = build_relation_joins(
+      self.relations(),
+      &self.query.schema,
+      &self.query.table,
+      &mut self.parameters,
+    )?;
+    Ok(format!("{} {}", from_clause, joins))
+  }
+
+  fn build_having(&mut self) -> Result<String, BuilderError> {
+    if let Some(having_field) = self.query.fields.iter().find(|field| field.name == FieldName::Having) {
+      fields::build_having(having_field, &mut self.parameters)
+    } else {
+      Ok(String::new())
+    }
   }
 
-  fn build_options(&self) -> Result<String, BuilderError> {
-    let options = build_options(self.query.options.clone())?;
+  fn build_options(&mut self) -> Result<String, BuilderError> {
+    let having = self.build_having()?;
+    let options = build_options(self.query.options.clone(), &having, &self.query.schema, &self.query.table)?;
     Ok(options)
   }
 
@@ -89,7 +155,7 @@ impl<'a> QueryBuilder<'a> {
     }
   }
 
-  fn build_data_many(&self) -> Result<String, BuilderError> {
+  fn build_data_many(&mut self) -> Result<String, BuilderError> {
     let data_field = match self.query.fields.iter().find(|field| field.name == FieldName::Data) {
       Some(field) => field,
       None => return Err(BuilderError::InvalidStatement(errors::INVALID_STATEMENT)),
@@ -104,10 +170,10 @@ impl<'a> QueryBuilder<'a> {
       return Err(BuilderError::InvalidStatement(errors::INVALID_STATEMENT));
     }
 
-    fields::build_many_data(data_field, num_rows)
+    fields::build_many_data(data_field, num_rows, &mut self.parameters)
   }
 
-  fn build_data(&self) -> Result<String, BuilderError> {
+  fn build_data(&mut self) -> Result<String, BuilderError> {
     let num_rows = self.query.options.num_of_rows.unwrap_or(1);
 
     if num_rows > 1 {
@@ -125,17 +191,25 @@ impl<'a> QueryBuilder<'a> {
     }
   }
 
-  pub fn build_ordered(&self) -> Result<String, BuilderError> {
+  pub fn build_ordered(&mut self) -> Result<String, BuilderError> {
     let mut ordered_fields = Vec::new();
+    let field_order = self.field_order.clone();
 
-    for name in &self.field_order {
+    for name in &field_order {
       match name {
         FieldName::Select => ordered_fields.push(self.build_select()?),
-        FieldName::Include => ordered_fields.push(self.build_include()?),
+        FieldName::Include => {
+          // An include without joins renders nothing, which would leave a double space behind.
+          let joins = self.build_include()?;
+          if !joins.is_empty() {
+            ordered_fields.push(joins);
+          }
+        }
         FieldName::From => ordered_fields.push(self.build_from()?),
         FieldName::Where => ordered_fields.push(self.build_where()?),
         FieldName::Set => ordered_fields.push(self.build_set()?),
         FieldName::Data => ordered_fields.push(self.build_data()?),
+        FieldName::Conflict => ordered_fields.push(self.build_conflict()?),
         FieldName::Return => ordered_fields.push(self.build_return()?),
         FieldName::Aggs => ordered_fields.push(self.build_aggregate()?),
         _ => return Err(BuilderError::InvalidFieldName(errors::INVALID_FIELD)),
@@ -146,4 +220,14 @@ impl<'a> QueryBuilder<'a> {
 
     Ok(ordered_fields.join(" "))
   }
+
+  /// Binds a value used by SQL text the caller wraps around the built query.
+  pub fn bind(&mut self, value: ParsedValue) -> String {
+    self.parameters.bind(value)
+  }
+
+  /// Wraps the final SQL text together with every parameter bound while building it.
+  pub fn finish(&mut self, sql: String) -> BuiltQuery {
+    BuiltQuery::new(sql, std::mem::take(&mut self.parameters).into_vec())
+  }
 }
@@ -1,4 +1,4 @@
-use super::{arguments, error::BuilderError};
+use super::{arguments, error::BuilderError, parameters::Parameters};
 use crate::{
   constants::{constants, errors},
   parser::parsed::{ParsedField, ParsedInclude},
@@ -19,11 +19,11 @@ pub fn build_select(select_field: &ParsedField) -> Result<String, BuilderError>
   Ok(format!("{} {} FROM", constants::SELECT, arguments_str))
 }
 
-pub fn build_where(where_field: &ParsedField) -> Result<String, BuilderError> {
+pub fn build_where(where_field: &ParsedField, parameters: &mut Parameters) -> Result<String, BuilderError> {
   let arguments_str = where_field
     .arguments
     .iter()
-    .map(arguments::build_where)
+    .map(|arg| arguments::build_where(arg, parameters))
     .collect::<Result<Vec<String>, BuilderError>>()?
     .join(constants::AND);
 
@@ -34,11 +34,26 @@ pub fn build_where(where_field: &ParsedField) -> Result<String, BuilderError> {
   Ok(format!("{} {}", constants::WHERE, arguments_str))
 }
 
-pub fn build_set(set_field: &ParsedField) -> Result<String, BuilderError> {
+pub fn build_having(having_field: &ParsedField, parameters: &mut Parameters) -> Result<String, BuilderError> {
+  let arguments_str = having_field
+    .arguments
+    .iter()
+    .map(|arg| arguments::build_where(arg, parameters))
+    .collect::<Result<Vec<String>, BuilderError>>()?
+    .join(constants::AND);
+
+  if arguments_str.is_empty() {
+    return Err(BuilderError::InvalidStatement(errors::INVALID_STATEMENT));
+  }
+
+  Ok(format!("{} {}", constants::HAVING, arguments_str))
+}
+
+pub fn build_set(set_field: &ParsedField, parameters: &mut Parameters) -> Result<String, BuilderError> {
   let set_values = set_field
     .arguments
     .iter()
-    .map(|arg| format!("{} = {}", arg.name, arguments::parse_value(&arg.value)))
+    .map(|arg| arguments::build_set(arg, parameters))
     .collect::<Vec<_>>()
     .join(", ");
 
@@ -49,7 +64,52 @@ pub fn build_set(set_field: &ParsedField) -> Result<String, BuilderError> {
   Ok(format!("SET {}", set_values))
 }
 
-pub fn build_one_data(data_field: &ParsedField) -> Result<String, BuilderError> {
+/// Renders the `ON CONFLICT` clause of an upsert. `set` arguments without a value take the
+/// value that was proposed for insertion, and a missing `set` field leaves the existing row untouched.
+pub fn build_conflict(
+  conflict_field: Option<&ParsedField>,
+  set_field: Option<&ParsedField>,
+  parameters: &mut Parameters,
+) -> Result<String, BuilderError> {
+  let target = match conflict_field {
+    Some(conflict_field) if !conflict_field.arguments.is_empty() => format!(
+      " ({})",
+      conflict_field
+        .arguments
+        .iter()
+        .map(|arg| arg.name.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+    ),
+    _ => String::new(),
+  };
+
+  let set_field = match set_field {
+    Some(set_field) => set_field,
+    None => return Ok(format!("ON CONFLICT{} DO NOTHING", target)),
+  };
+
+  // DO UPDATE needs to know which constraint it resolves.
+  if target.is_empty() || set_field.arguments.is_empty() {
+    return Err(BuilderError::InvalidStatement(errors::INVALID_STATEMENT));
+  }
+
+  let set_values = set_field
+    .arguments
+    .iter()
+    .map(|arg| match (&arg.update, &arg.value) {
+      // Column references are ambiguous between the existing and the excluded row here.
+      (Some(_), _) => Err(BuilderError::InputError("Update operators are not supported in upserts.")),
+      (None, Some(_)) => Ok(format!("{} = {}", arg.name, arguments::bind_value(&arg.value, parameters))),
+      (None, None) => Ok(format!("{} = EXCLUDED.{}", arg.name, arg.name)),
+    })
+    .collect::<Result<Vec<_>, BuilderError>>()?
+    .join(", ");
+
+  Ok(format!("ON CONFLICT{} DO UPDATE SET {}", target, set_values))
+}
+
+pub fn build_one_data(data_field: &ParsedField, parameters: &mut Parameters) -> Result<String, BuilderError> {
   let arguments = &data_field.arguments;
 
   if arguments.is_empty() {
@@ -64,7 +124,7 @@ pub fn build_one_data(data_field: &ParsedField) -> Result<String, BuilderError>
 
   let values = arguments
     .iter()
-    .map(|arg| arguments::parse_value(&arg.value))
+    .map(|arg| arguments::bind_value(&arg.value, parameters))
     .collect::<Vec<_>>()
     .join(", ");
 
@@ -90,7 +150,11 @@ pub fn build_return(return_field: &ParsedField) -> Result<String, BuilderError>
   Ok(format!("{}{}", constants::RETURN, arguments))
 }
 
-pub fn build_many_data(data_field: &ParsedField, num_rows: usize) -> Result<String, BuilderError> {
+pub fn build_many_data(
+  data_field: &ParsedField,
+  num_rows: usize,
+  parameters: &mut Parameters,
+) -> Result<String, BuilderError> {
   let columns = data_field
     .arguments
     .iter()
@@ -115,7 +179,7 @@ pub fn build_many_data(data_field: &ParsedField, num_rows: usize) -> Result<Stri
 
     let row_values = columns
       .iter()
-      .map(|col| arguments::parse_value(&row_args.iter().find(|arg| &arg.name == col).unwrap().value))
+      .map(|col| arguments::bind_value(&row_args.iter().find(|arg| &arg.name == col).unwrap().value, parameters))
       .collect::<Vec<_>>();
 
     let row_values_str = row_values.join(", ");
@@ -128,28 +192,31 @@ pub fn build_many_data(data_field: &ParsedField, num_rows: usize) -> Result<Stri
   Ok(format!("({})\nVALUES\n{}", columns_str, values_str))
 }
 
+/// Renders one `JOIN` per entry. An aliased table is referenced by its alias in the join condition,
+/// which is what lets a table be joined to itself.
 pub fn build_include(parsed_include: &ParsedInclude, schema: &str) -> Result<String, BuilderError> {
-  if parsed_include.joins.is_empty() {
-    return Ok(String::new()); // No joins to include
-  }
-
-  let join_conditions: Vec<String> = parsed_include
+  let joins = parsed_include
     .joins
     .iter()
-    .map(|(source_table, source_key, joining_table, joining_key)| {
-      format!("{}.{} = {}.{}", source_table, source_key, joining_table, joining_key)
+    .map(|join| {
+      let joined = join.alias.as_deref().unwrap_or(&join.joining_table);
+      let conditions = join
+        .keys
+        .iter()
+        .map(|(key, joining_key)| format!("{}.{} = {}.{}", join.table, key, joined, joining_key))
+        .collect::<Vec<_>>()
+        .join(constants::AND);
+
+      let table = match &join.alias {
+        Some(alias) => format!("{}.{} AS {}", schema, join.joining_table, alias),
+        None => format!("{}.{}", schema, join.joining_table),
+      };
+
+      format!("{} JOIN {} ON {}", join.kind, table, conditions)
     })
-    .collect();
-
-  let join_condition_str = join_conditions.join(" AND ");
-  let join_tables_str: Vec<String> = parsed_include
-    .joins
-    .iter()
-    .map(|(_, _, table, _)| format!("{}.{}", schema, table))
-    .collect();
-  let join_tables_str = join_tables_str.join(", ");
+    .collect::<Vec<_>>();
 
-  Ok(format!("INNER JOIN {} ON {}", join_tables_str, join_condition_str))
+  Ok(joins.join(" "))
 }
 
 pub fn build_aggregate(aggregate_field: &ParsedField) -> Result<String, BuilderError> {
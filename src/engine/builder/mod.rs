@@ -10,8 +10,9 @@
 ********************************************************************/
 use self::{
   error::BuilderError,
+  parameters::BuiltQuery,
   read::find,
-  write::{delete, insert, update},
+  write::{delete, insert, update, upsert},
 };
 use crate::parser::parsed::{ParsedOperation, ParsedStatement};
 
@@ -19,15 +20,17 @@ mod arguments;
 mod builder;
 mod error;
 mod fields;
+mod relations;
 mod utils;
 mod tests;
 
 pub mod aggregations;
 pub mod options;
+pub mod parameters;
 pub mod read;
 pub mod write;
 
-pub fn build(statement: &ParsedStatement) -> Result<String, BuilderError> {
+pub fn build(statement: &ParsedStatement) -> Result<BuiltQuery, BuilderError> {
   match statement.operation {
     ParsedOperation::FindFirst => find::unique(&statement.query),
     ParsedOperation::FindMany => find::many(&statement.query),
@@ -37,7 +40,11 @@ pub fn build(statement: &ParsedStatement) -> Result<String, BuilderError> {
     ParsedOperation::UpdateOne => update::one(&statement.query),
     ParsedOperation::UpdateMany => update::many(&statement.query),
     ParsedOperation::InsertMany => insert::many(&statement.query),
+    ParsedOperation::UpsertOne => upsert::one(&statement.query),
+    ParsedOperation::UpsertMany => upsert::many(&statement.query),
     ParsedOperation::Count => aggregations::aggregations::count(&statement.query),
     ParsedOperation::Average => aggregations::aggregations::average(&statement.query),
+    ParsedOperation::Aggregate => aggregations::aggregations::aggregate(&statement.query),
+    ParsedOperation::GroupBy => aggregations::aggregations::group_by(&statement.query),
   }
 }
@@ -1,21 +1,141 @@
-use std::collections::HashMap;
-
-use super::{error::BuilderError, utils};
+use super::{arguments, error::BuilderError, parameters::Parameters, utils};
 use crate::{
   constants::constants,
-  parser::{parsed::QueryOptions, utils::camel_to_snake_case},
+  parser::{
+    parsed::{ParsedOrderBy, ParsedTimeBucket, ParsedValue, QueryOptions},
+    utils::camel_to_snake_case,
+  },
 };
 
-fn build_order_by(order_by: &HashMap<String, String>) -> Result<String, BuilderError> {
+/// Whether `take` pages backwards from the cursor. The query then runs in reverse order and the
+/// page is flipped back afterwards, see [`build_page_order`].
+pub fn is_backwards(options: &QueryOptions) -> bool {
+  options.take.is_some_and(|take| take < 0)
+}
+
+/// Columns the query is ordered by, each with whether it is descending: the `orderBy` entries
+/// followed by any cursor column they leave out, so the keyset predicate always selects a stable page.
+/// Relation and group aggregates are left out since there is no row value to compare them against.
+pub fn ordering_columns(options: &QueryOptions) -> Vec<(String, bool)> {
+  let mut columns = Vec::new();
+
+  for order_by in options.order_by.iter().flatten() {
+    if is_column(order_by) {
+      columns.push((order_column(order_by), order_by.order.eq_ignore_ascii_case("desc")));
+    }
+  }
+
+  for argument in options.cursor.iter().flatten() {
+    if !columns.iter().any(|(column, _)| column == &argument.name) {
+      columns.push((argument.name.clone(), false));
+    }
+  }
+
+  columns
+}
+
+fn is_column(order_by: &ParsedOrderBy) -> bool {
+  order_by.relation.is_none() && order_by.aggregate.is_none()
+}
+
+fn order_column(order_by: &ParsedOrderBy) -> String {
+  let column = camel_to_snake_case(order_by.field.as_str());
+
+  match &order_by.table {
+    Some(table) => format!("{}.{}", table, column),
+    None => column,
+  }
+}
+
+fn order_expression(order_by: &ParsedOrderBy, schema: &str, table: &str) -> Result<String, BuilderError> {
+  if let Some(aggregate) = &order_by.aggregate {
+    return arguments::aggregate_expression(aggregate, &order_column(order_by));
+  }
+
+  let relation = match &order_by.relation {
+    Some(relation) => relation,
+    None => return Ok(order_column(order_by)),
+  };
+
+  // A relation is aggregated per row of the table the ordering is qualified by, the queried table otherwise.
+  let parent = match &order_by.table {
+    Some(table) => table.clone(),
+    None => format!("{}.{}", schema, table),
+  };
+
+  let aggregated = match relation.function.as_str() {
+    "count" => "*".to_string(),
+    _ => format!("{}.{}.{}", schema, relation.table, camel_to_snake_case(order_by.field.as_str())),
+  };
+
+  Ok(format!(
+    "(SELECT {}({}) FROM {}.{} WHERE {}.{}.{} = {}.{})",
+    relation.function.to_uppercase(),
+    aggregated,
+    schema,
+    relation.table,
+    schema,
+    relation.table,
+    relation.key,
+    parent,
+    relation.references
+  ))
+}
+
+/// Renders the `ORDER BY` clause, by the names of the output columns instead of the expressions
+/// they are computed from when `output` is set.
+fn build_order_by(
+  options: &QueryOptions,
+  schema: &str,
+  table: &str,
+  backwards: bool,
+  output: bool,
+) -> Result<String, BuilderError> {
+  let direction = |descending: bool| match descending != backwards {
+    true => "DESC",
+    false => "ASC",
+  };
+
   let mut order_by_parts = Vec::new();
-  for (field, order) in order_by.iter() {
-    let mut field_parts = Vec::new();
-    field_parts.push(camel_to_snake_case(field.as_str()));
-    field_parts.push(order.to_string().to_uppercase());
-    order_by_parts.push(field_parts.join(" "));
+  for order_by in options.order_by.iter().flatten() {
+    let expression = match output {
+      true => camel_to_snake_case(order_by.field.as_str()),
+      false => order_expression(order_by, schema, table)?,
+    };
+    let mut part = format!("{} {}", expression, direction(order_by.order.eq_ignore_ascii_case("desc")));
+
+    // Reversing the order for a backwards page moves explicitly placed nulls to the other end as well.
+    match order_by.nulls.as_deref().map(|nulls| (nulls == "first") != backwards) {
+      Some(true) => part.push_str(" NULLS FIRST"),
+      Some(false) => part.push_str(" NULLS LAST"),
+      None => {}
+    }
+
+    order_by_parts.push(part);
   }
 
-  let order_by_option = utils::build_string_from_parts(vec![constants::ORDER_BY, order_by_parts.join(",").as_str()]);
+  // Cursor columns always take part in the ordering so the keyset predicate selects a stable page.
+  for argument in options.cursor.iter().flatten() {
+    let is_ordered = options
+      .order_by
+      .iter()
+      .flatten()
+      .any(|order_by| is_column(order_by) && order_column(order_by) == argument.name);
+
+    if !is_ordered {
+      let column = match output {
+        true => output_column(&argument.name),
+        false => argument.name.as_str(),
+      };
+      order_by_parts.push(format!("{} {}", column, direction(false)));
+    }
+  }
+
+  if order_by_parts.is_empty() {
+    return Ok(String::new());
+  }
+
+  let order_by_option = utils::build_string_from_parts(vec![constants::ORDER_BY, order_by_parts.join(", ").as_str()]);
   Ok(order_by_option)
 }
 
@@ -31,7 +151,43 @@ fn build_offset_options(offset: &str) -> Result<String, BuilderError> {
   Ok(offset_option)
 }
 
-fn build_group_by(group_by: &Vec<String>) -> Result<String, BuilderError> {
+/// Truncates the bucket's column to the start of its interval, in the bucket's time zone if it has one.
+/// `AT TIME ZONE` converts a `timestamptz` into local time, but reads a `timestamp` as local time, so
+/// `timestamp` columns are taken as UTC first. Dates have no time of day to convert. `date_trunc`
+/// returns a different type for each, so the bucket is cast to the `timestamp` the gap filling series
+/// is made of.
+pub fn bucket_expression(bucket: &ParsedTimeBucket) -> String {
+  let truncated = match (&bucket.timezone, bucket.column_type.as_deref()) {
+    (Some(timezone), Some("timestamp")) => format!(
+      "date_trunc('{}', ({} AT TIME ZONE 'UTC') AT TIME ZONE '{}')",
+      bucket.unit, bucket.column, timezone
+    ),
+    (Some(timezone), column_type) if column_type != Some("date") => {
+      format!("date_trunc('{}', {} AT TIME ZONE '{}')", bucket.unit, bucket.column, timezone)
+    }
+    _ => format!("date_trunc('{}', {})", bucket.unit, bucket.column),
+  };
+
+  format!("CAST({} AS timestamp)", truncated)
+}
+
+/// Interval between two consecutive buckets, used to generate a series without gaps.
+pub fn bucket_interval(bucket: &ParsedTimeBucket) -> String {
+  match bucket.unit.as_str() {
+    "quarter" => "interval '3 months'".to_string(),
+    unit => format!("interval '1 {}'", unit),
+  }
+}
+
+fn build_group_by(options: &QueryOptions) -> Result<String, BuilderError> {
+  let mut group_by = Vec::new();
+
+  if let Some(bucket) = &options.time_bucket {
+    group_by.push(bucket_expression(bucket));
+  }
+
+  group_by.extend(options.group_by.iter().flatten().cloned());
+
   if group_by.is_empty() {
     return Ok(String::new());
   }
@@ -42,19 +198,196 @@ fn build_group_by(group_by: &Vec<String>) -> Result<String, BuilderError> {
   Ok(result)
 }
 
-pub fn build_options(options: QueryOptions) -> Result<String, BuilderError> {
-  let mut options_string = String::new();
+/// A column of the keyset predicate, with its direction and where its nulls sort in the order the
+/// query actually runs in.
+struct KeysetColumn {
+  name: String,
+  /// The bound cursor value, `None` when the cursor row has no value in this column.
+  value: Option<String>,
+  descending: bool,
+  nulls_first: bool,
+}
 
-  if let Some(ref order_by) = options.order_by {
-    let order_by = match build_order_by(&order_by) {
-      Ok(order_by) => order_by,
-      Err(err) => return Err(err),
+impl KeysetColumn {
+  fn equal(&self) -> String {
+    match &self.value {
+      Some(value) => format!("{} = {}", self.name, value),
+      None => format!("{} IS NULL", self.name),
+    }
+  }
+
+  /// Conditions matching rows that sort strictly after the cursor in this column.
+  fn after(&self) -> Vec<String> {
+    let operator = match self.descending {
+      true => "<",
+      false => ">",
+    };
+
+    match (&self.value, self.nulls_first) {
+      (Some(value), true) => vec![format!("{} {} {}", self.name, operator, value)],
+      (Some(value), false) => vec![
+        format!("{} {} {}", self.name, operator, value),
+        format!("{} IS NULL", self.name),
+      ],
+      (None, true) => vec![format!("{} IS NOT NULL", self.name)],
+      (None, false) => vec![],
+    }
+  }
+}
+
+/// Renders the keyset predicate selecting the rows at or after the cursor in the query's order,
+/// or at or before it when paging backwards. Paginated queries leave the cursor row itself out,
+/// since it is the last row of the page the client already has. Nulls are matched by where they
+/// sort, Postgres putting them last in ascending order unless `nulls` says otherwise.
+pub fn build_cursor(options: &QueryOptions, parameters: &mut Parameters) -> Result<String, BuilderError> {
+  let cursor = match &options.cursor {
+    Some(cursor) if !cursor.is_empty() => cursor,
+    _ => return Ok(String::new()),
+  };
+
+  if options.order_by.iter().flatten().any(|order_by| order_by.relation.is_some()) {
+    return Err(BuilderError::InputError("Cursors can't be combined with ordering by a relation."));
+  }
+
+  if options.order_by.iter().flatten().any(|order_by| order_by.aggregate.is_some()) {
+    return Err(BuilderError::InputError("Cursors can't be combined with ordering by an aggregate."));
+  }
+
+  let backwards = is_backwards(options);
+  let inclusive = !options.paginate;
+  let mut columns = Vec::new();
+  for (column, descending) in ordering_columns(options) {
+    let argument = match cursor.iter().find(|argument| argument.name == column) {
+      Some(argument) => argument,
+      None => {
+        return Err(BuilderError::InputError(
+          "Cursors need a value for every column the query is ordered by.",
+        ))
+      }
+    };
+
+    let value = match &argument.value {
+      Some(ParsedValue::Null) => None,
+      Some(_) => Some(arguments::bind_value(&argument.value, parameters)),
+      None => return Err(BuilderError::MissingArgumentValue("Cursor values are required.")),
     };
 
-    options_string.push_str(order_by.as_str());
+    let nulls = options
+      .order_by
+      .iter()
+      .flatten()
+      .find(|order_by| is_column(order_by) && order_column(order_by) == column)
+      .and_then(|order_by| order_by.nulls.as_deref());
+
+    columns.push(KeysetColumn {
+      name: column,
+      value,
+      descending: descending != backwards,
+      nulls_first: match nulls {
+        Some(nulls) => (nulls == "first") != backwards,
+        None => descending != backwards,
+      },
+    });
+  }
+
+  let mut alternatives = Vec::new();
+
+  // A single direction can use a row comparison, which an index on the cursor columns serves
+  // directly. Rows with nulls sorting after the cursor never compare and are matched separately.
+  let single_direction = columns.iter().all(|column| column.descending == columns[0].descending);
+  if single_direction && columns.iter().all(|column| column.value.is_some()) {
+    let names = columns.iter().map(|column| column.name.as_str()).collect::<Vec<_>>().join(", ");
+    let values = columns.iter().flat_map(|column| column.value.as_deref()).collect::<Vec<_>>().join(", ");
+    let operator = match (columns[0].descending, inclusive) {
+      (false, true) => ">=",
+      (false, false) => ">",
+      (true, true) => "<=",
+      (true, false) => "<",
+    };
+
+    let comparison = match columns.len() {
+      1 => format!("{} {} {}", names, operator, values),
+      _ => format!("({}) {} ({})", names, operator, values),
+    };
+
+    for (index, column) in columns.iter().enumerate().filter(|(_, column)| !column.nulls_first) {
+      let mut conditions = columns[..index].iter().map(KeysetColumn::equal).collect::<Vec<_>>();
+      conditions.push(format!("{} IS NULL", column.name));
+      alternatives.push(format!("({})", conditions.join(constants::AND)));
+    }
+
+    return match alternatives.is_empty() {
+      true => Ok(comparison),
+      false => Ok(format!("({}{}{})", comparison, constants::OR, alternatives.join(constants::OR))),
+    };
+  }
+
+  for (index, column) in columns.iter().enumerate() {
+    for condition in column.after() {
+      let mut conditions = columns[..index].iter().map(KeysetColumn::equal).collect::<Vec<_>>();
+      conditions.push(condition);
+      alternatives.push(format!("({})", conditions.join(constants::AND)));
+    }
+  }
+
+  if inclusive {
+    let conditions = columns.iter().map(KeysetColumn::equal).collect::<Vec<_>>();
+    alternatives.push(format!("({})", conditions.join(constants::AND)));
+  }
+
+  match alternatives.is_empty() {
+    true => Ok("FALSE".to_string()),
+    false => Ok(format!("({})", alternatives.join(constants::OR))),
   }
+}
+
+fn build_order_by_clause(options: &QueryOptions, schema: &str, table: &str) -> Result<String, BuilderError> {
+  if options.order_by.is_none() && options.cursor.is_none() {
+    return Ok(String::new());
+  }
+
+  build_order_by(options, schema, table, is_backwards(options), false)
+}
+
+/// Name a column ordered by as `table.column` has in the rows the query returns.
+pub fn output_column(column: &str) -> &str {
+  column.rsplit('.').next().unwrap_or(column)
+}
+
+/// Restores the requested order of a page that was fetched backwards. The page is a subquery by
+/// then, so it is ordered by its output columns, which relation and aggregate orderings have none of.
+pub fn build_page_order(options: &QueryOptions) -> Result<String, BuilderError> {
+  if options.order_by.iter().flatten().any(|order_by| !is_column(order_by)) {
+    return Err(BuilderError::InputError(
+      "Taking rows backwards can't be combined with ordering by a relation or an aggregate.",
+    ));
+  }
+
+  build_order_by(options, "", "", false, true)
+}
 
-  if let Some(ref limit) = options.limit {
+/// Renders the clauses following the `WHERE` clause in the order SQL expects them: `GROUP BY`,
+/// the already built `having` condition, `ORDER BY`, `LIMIT` and `OFFSET`.
+pub fn build_options(options: QueryOptions, having: &str, schema: &str, table: &str) -> Result<String, BuilderError> {
+  let mut options_string = String::new();
+
+  options_string.push_str(build_group_by(&options)?.as_str());
+
+  for clause in [having.to_string(), build_order_by_clause(&options, schema, table)?] {
+    if clause.is_empty() {
+      continue;
+    }
+
+    if !options_string.is_empty() {
+      options_string.push(' ');
+    }
+
+    options_string.push_str(clause.as_str());
+  }
+
+  if let Some(take) = options.take {
+    options_string.push_str(build_limit_options(&take.unsigned_abs().to_string())?.as_str());
+  } else if let Some(ref limit) = options.limit {
     let limit = match build_limit_options(limit) {
       Ok(limit) => limit,
       Err(err) => return Err(err),
@@ -63,7 +396,9 @@ pub fn build_options(options: QueryOptions) -> Result<String, BuilderError> {
     options_string.push_str(limit.as_str());
   }
 
-  if let Some(ref offset) = options.offset {
+  if let Some(skip) = options.skip {
+    options_string.push_str(build_offset_options(&skip.to_string())?.as_str());
+  } else if let Some(ref offset) = options.offset {
     let offset = match build_offset_options(offset) {
       Ok(offset) => offset,
       Err(err) => return Err(err),
@@ -72,14 +407,5 @@ pub fn build_options(options: QueryOptions) -> Result<String, BuilderError> {
     options_string.push_str(offset.as_str());
   }
 
-  if let Some(ref group_by) = options.group_by {
-    let group_by = match build_group_by(group_by) {
-      Ok(group_by) => group_by,
-      Err(err) => return Err(err),
-    };
-
-    options_string.push_str(group_by.as_str());
-  }
-
   Ok(options_string)
 }
@@ -0,0 +1,44 @@
+use crate::parser::parsed::ParsedValue;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+  pub name: String,
+  pub value: ParsedValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Parameters {
+  parameters: Vec<Parameter>,
+}
+
+impl Parameters {
+  pub fn new() -> Self {
+    Self { parameters: Vec::new() }
+  }
+
+  /// Registers the value as the next named parameter and returns its placeholder (`:p1`, `:p2`, ...).
+  pub fn bind(&mut self, value: ParsedValue) -> String {
+    let name = format!("p{}", self.parameters.len() + 1);
+    let placeholder = format!(":{}", name);
+
+    self.parameters.push(Parameter { name, value });
+
+    placeholder
+  }
+
+  pub fn into_vec(self) -> Vec<Parameter> {
+    self.parameters
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuiltQuery {
+  pub sql: String,
+  pub parameters: Vec<Parameter>,
+}
+
+impl BuiltQuery {
+  pub fn new(sql: String, parameters: Vec<Parameter>) -> Self {
+    Self { sql, parameters }
+  }
+}
@@ -1,20 +1,33 @@
 use crate::{
-  engine::builder::{error::BuilderError, builder::QueryBuilder},
+  engine::builder::{
+    builder::QueryBuilder,
+    error::BuilderError,
+    options::{build_page_order, is_backwards},
+    parameters::BuiltQuery,
+  },
   parser::parsed::{FieldName, ParsedQuery},
 };
 
-pub fn unique(query_schema: &ParsedQuery) -> Result<String, BuilderError> {
-  let builder = QueryBuilder::new(&query_schema)
+pub fn unique(query_schema: &ParsedQuery) -> Result<BuiltQuery, BuilderError> {
+  let mut builder = QueryBuilder::new(query_schema);
+  let query = builder
     .order_fields(vec![FieldName::Select, FieldName::From, FieldName::Include, FieldName::Where])
     .build_ordered()?;
 
-  Ok(builder)
+  Ok(builder.finish(query))
 }
 
-pub fn many(query_schema: &ParsedQuery) -> Result<String, BuilderError> {
-  let builder = QueryBuilder::new(&query_schema)
-    .order_fields(vec![FieldName::Select, FieldName::From, FieldName::Where])
+pub fn many(query_schema: &ParsedQuery) -> Result<BuiltQuery, BuilderError> {
+  let mut builder = QueryBuilder::new(query_schema);
+  let query = builder
+    .order_fields(vec![FieldName::Select, FieldName::From, FieldName::Include, FieldName::Where])
     .build_ordered()?;
 
-  Ok(builder)
+  // Pages taken backwards are fetched in reverse and flipped back into the requested order.
+  if is_backwards(&query_schema.options) {
+    let page_order = build_page_order(&query_schema.options)?;
+    return Ok(builder.finish(format!("SELECT * FROM ({}) AS auro_page {}", query, page_order)));
+  }
+
+  Ok(builder.finish(query))
 }
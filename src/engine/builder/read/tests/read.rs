@@ -2,7 +2,7 @@
 mod tests {
   use crate::{
     engine::builder::read::find::{many, unique},
-    parser::parsed::{FieldName, ParsedArgument, ParsedField, ParsedQuery, ParsedValue, QueryOptions, ParsedInclude},
+    parser::parsed::{FieldName, ParsedArgument, ParsedField, ParsedQuery, ParsedValue, QueryOptions, ParsedInclude, ParsedJoin, ParsedOrderBy, ParsedRelation, ParsedFilter, ParsedRelationOrder},
   };
 
   #[test]
@@ -14,6 +14,7 @@ mod tests {
       num_of_rows: Some(100),
       include: None,
       group_by: Some(vec![]),
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -26,6 +27,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -33,6 +35,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -41,10 +44,10 @@ mod tests {
     let result = unique(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "SELECT arg1 FROM my_schema.my_table  WHERE arg1 = 'value1'  LIMIT 10 OFFSET 5"
+      "SELECT arg1 FROM my_schema.my_table WHERE arg1 = :p1  LIMIT 10 OFFSET 5"
     );
   }
 
@@ -57,6 +60,7 @@ mod tests {
       num_of_rows: Some(100),
       include: None,
       group_by: Some(vec![]),
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -69,6 +73,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -76,6 +81,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -84,22 +90,30 @@ mod tests {
     let result = many(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "SELECT arg1 FROM my_schema.my_table WHERE arg1 = 'value1'  LIMIT 10 OFFSET 5"
+      "SELECT arg1 FROM my_schema.my_table WHERE arg1 = :p1  LIMIT 10 OFFSET 5"
     );
   }
 
   #[test]
   fn test_unique_with_order_by() {
     let options = QueryOptions {
-      order_by: Some(vec![("field1".to_string(), "asc".to_string())].into_iter().collect()),
+      order_by: Some(vec![ParsedOrderBy {
+        field: "field1".to_string(),
+        order: "asc".to_string(),
+        nulls: None,
+        table: None,
+        relation: None,
+        aggregate: None,
+      }]),
       limit: Some("10".to_string()),
       offset: Some("5".to_string()),
       num_of_rows: Some(100),
       include: None,
       group_by: Some(vec![]),
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -112,6 +126,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -119,6 +134,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -127,10 +143,10 @@ mod tests {
     let result = unique(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "SELECT arg1 FROM my_schema.my_table  WHERE arg1 = 'value1' ORDER BY field1 ASC LIMIT 10 OFFSET 5"
+      "SELECT arg1 FROM my_schema.my_table WHERE arg1 = :p1 ORDER BY field1 ASC LIMIT 10 OFFSET 5"
     );
   }
 
@@ -143,6 +159,7 @@ mod tests {
       num_of_rows: Some(100),
       include: None,
       group_by: Some(vec![]),
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -155,6 +172,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -162,6 +180,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -170,10 +189,10 @@ mod tests {
     let result = many(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "SELECT arg1 FROM my_schema.my_table WHERE arg1 = 'value1'  LIMIT 10 OFFSET 5"
+      "SELECT arg1 FROM my_schema.my_table WHERE arg1 = :p1  LIMIT 10 OFFSET 5"
     );
   }
 
@@ -186,6 +205,7 @@ mod tests {
       num_of_rows: Some(100),
       include: None,
       group_by: Some(vec!["column1".to_string(), "column2".to_string()]),
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -198,6 +218,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -205,6 +226,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -213,10 +235,10 @@ mod tests {
     let result = unique(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "SELECT arg1 FROM my_schema.my_table  WHERE arg1 = 'value1'  LIMIT 10 OFFSET 5 GROUP BY column1, column2"
+      "SELECT arg1 FROM my_schema.my_table WHERE arg1 = :p1 GROUP BY column1, column2 LIMIT 10 OFFSET 5"
     );
   }
 
@@ -229,6 +251,7 @@ mod tests {
       num_of_rows: Some(100),
       include: None,
       group_by: Some(vec![]),
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -241,6 +264,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -248,6 +272,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -256,10 +281,10 @@ mod tests {
     let result = many(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "SELECT arg1 FROM my_schema.my_table WHERE arg1 = 'value1'  LIMIT 10 OFFSET 5"
+      "SELECT arg1 FROM my_schema.my_table WHERE arg1 = :p1  LIMIT 10 OFFSET 5"
     );
   }
 
@@ -272,6 +297,7 @@ mod tests {
       num_of_rows: Some(100),
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -284,6 +310,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -291,6 +318,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -299,8 +327,8 @@ mod tests {
     let result = unique(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
-    assert_eq!(generated_sql, "SELECT arg1 FROM my_schema.my_table  WHERE arg1 = 'value1' ");
+    let generated_sql = result.unwrap().sql;
+    assert_eq!(generated_sql, "SELECT arg1 FROM my_schema.my_table WHERE arg1 = :p1 ");
   }
 
   #[test]
@@ -312,6 +340,7 @@ mod tests {
       num_of_rows: Some(100),
       include: None,
       group_by: Some(vec![]),
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -324,6 +353,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -332,10 +362,10 @@ mod tests {
     let result = many(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "SELECT * FROM my_schema.my_table WHERE arg1 = 'value1'  LIMIT 10 OFFSET 5"
+      "SELECT * FROM my_schema.my_table WHERE arg1 = :p1  LIMIT 10 OFFSET 5"
     );
   }
 
@@ -348,6 +378,7 @@ mod tests {
       num_of_rows: Some(100),
       include: None,
       group_by: Some(vec![]),
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -360,14 +391,21 @@ mod tests {
     let result = unique(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
-    assert_eq!(generated_sql, "SELECT * FROM my_schema.my_table    LIMIT 10 OFFSET 5");
+    let generated_sql = result.unwrap().sql;
+    assert_eq!(generated_sql, "SELECT * FROM my_schema.my_table   LIMIT 10 OFFSET 5");
   }
 
   #[test]
   fn test_unique_with_include() {
     let include_field = ParsedInclude {
-        joins: vec![("table1".to_string(), "column1".to_string(), "table2".to_string(), "column2".to_string())]
+        joins: vec![ParsedJoin {
+          kind: "INNER".to_string(),
+          table: "table1".to_string(),
+          joining_table: "table2".to_string(),
+          alias: None,
+          keys: vec![("column1".to_string(), "column2".to_string())],
+        }],
+        relations: vec![],
     };
 
     let options = QueryOptions {
@@ -377,6 +415,7 @@ mod tests {
       num_of_rows: Some(100),
       include: Some(include_field),
       group_by: Some(vec![]),
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -389,7 +428,313 @@ mod tests {
     let result = unique(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(generated_sql, "SELECT * FROM my_schema.my_table INNER JOIN my_schema.table2 ON table1.column1 = table2.column2   LIMIT 10 OFFSET 5");
   }
+
+  #[test]
+  fn test_many_with_cursor_backwards() {
+    let options = QueryOptions {
+      order_by: None,
+      limit: None,
+      offset: None,
+      num_of_rows: None,
+      include: None,
+      group_by: None,
+      cursor: Some(vec![ParsedArgument {
+        name: "id".to_string(),
+        value: Some(ParsedValue::Number("42".to_string())),
+        filter: None,
+        update: None,
+        aggregate: None,
+      }]),
+      take: Some(-10),
+      skip: Some(1),
+      ..Default::default()
+    };
+
+    let parsed_query = ParsedQuery {
+      table: "events".to_string(),
+      schema: "my_schema".to_string(),
+      options: options.clone(),
+      fields: vec![ParsedField {
+        name: FieldName::Where,
+        arguments: vec![ParsedArgument {
+          name: "kind".to_string(),
+          value: Some(ParsedValue::String("click".to_string())),
+          ..Default::default()
+        }],
+      }],
+    };
+
+    let result = many(&parsed_query);
+    assert!(result.is_ok());
+
+    let generated_sql = result.unwrap().sql;
+    assert_eq!(
+      generated_sql,
+      "SELECT * FROM (SELECT * FROM my_schema.events WHERE kind = :p1 AND id <= :p2 ORDER BY id DESC LIMIT 10 OFFSET 1) AS auro_page ORDER BY id ASC"
+    );
+  }
+
+  #[test]
+  fn test_many_with_nested_relations() {
+    let relation = |name: &str, table: &str, key: &str, references: &str| ParsedRelation {
+      name: name.to_string(),
+      table: table.to_string(),
+      key: key.to_string(),
+      references: references.to_string(),
+      one: false,
+      include: vec![],
+      ..Default::default()
+    };
+
+    let posts = ParsedRelation {
+      include: vec![ParsedRelation {
+        one: true,
+        ..relation("author", "users", "id", "user_id")
+      }],
+      select: vec!["id".to_string(), "title".to_string()],
+      filter: vec![ParsedArgument {
+        name: "published".to_string(),
+        value: Some(ParsedValue::Boolean("true".to_string())),
+        filter: None,
+        update: None,
+        aggregate: None,
+      }],
+      order_by: Some(vec![ParsedOrderBy {
+        field: "publishedAt".to_string(),
+        order: "desc".to_string(),
+        nulls: None,
+        table: None,
+        relation: None,
+        aggregate: None,
+      }]),
+      take: Some(5),
+      ..relation("posts", "posts", "user_id", "id")
+    };
+
+    let options = QueryOptions {
+      include: Some(ParsedInclude {
+        joins: vec![],
+        relations: vec![posts],
+      }),
+      ..Default::default()
+    };
+
+    let parsed_query = ParsedQuery {
+      table: "users".to_string(),
+      schema: "blog".to_string(),
+      options,
+      fields: vec![ParsedField {
+        name: FieldName::Where,
+        arguments: vec![ParsedArgument {
+          name: "active".to_string(),
+          value: Some(ParsedValue::Boolean("true".to_string())),
+          ..Default::default()
+        }],
+      }],
+    };
+
+    let result = many(&parsed_query);
+    assert!(result.is_ok());
+
+    let built_query = result.unwrap();
+    assert_eq!(
+      built_query.sql,
+      "SELECT blog.users.*, auro_posts_json.posts::text AS posts FROM blog.users \
+       LEFT JOIN LATERAL (SELECT COALESCE(json_agg(auro_1_row), '[]'::json) AS posts FROM (\
+       SELECT auro_1.id, auro_1.title, auro_author_json.author AS author FROM blog.posts AS auro_1 \
+       LEFT JOIN LATERAL (SELECT row_to_json(auro_2_row) AS author FROM (\
+       SELECT auro_2.* FROM blog.users AS auro_2 WHERE auro_2.id = auro_1.user_id LIMIT 1\
+       ) AS auro_2_row) AS auro_author_json ON TRUE \
+       WHERE auro_1.user_id = blog.users.id AND auro_1.published = :p1 ORDER BY auro_1.published_at DESC LIMIT 5\
+       ) AS auro_1_row) AS auro_posts_json ON TRUE WHERE active = :p2 "
+    );
+    assert_eq!(built_query.parameters.len(), 2);
+  }
+
+  #[test]
+  fn test_many_with_relation_ordered_by_aggregate() {
+    let posts = ParsedRelation {
+      name: "posts".to_string(),
+      table: "posts".to_string(),
+      key: "user_id".to_string(),
+      references: "id".to_string(),
+      filter: vec![ParsedArgument {
+        name: "OR".to_string(),
+        filter: Some(ParsedFilter::Or(vec![
+          ParsedArgument {
+            name: "published".to_string(),
+            value: Some(ParsedValue::Boolean("true".to_string())),
+            ..Default::default()
+          },
+          ParsedArgument {
+            name: "views".to_string(),
+            filter: Some(ParsedFilter::Gt(ParsedValue::Number("100".to_string()))),
+            ..Default::default()
+          },
+        ])),
+        ..Default::default()
+      }],
+      order_by: Some(vec![ParsedOrderBy {
+        field: "comments".to_string(),
+        order: "desc".to_string(),
+        relation: Some(ParsedRelationOrder {
+          table: "comments".to_string(),
+          key: "post_id".to_string(),
+          references: "id".to_string(),
+          function: "count".to_string(),
+        }),
+        ..Default::default()
+      }]),
+      ..Default::default()
+    };
+
+    let parsed_query = ParsedQuery {
+      table: "users".to_string(),
+      schema: "blog".to_string(),
+      options: QueryOptions {
+        include: Some(ParsedInclude {
+          joins: vec![],
+          relations: vec![posts],
+        }),
+        ..Default::default()
+      },
+      fields: vec![],
+    };
+
+    let result = many(&parsed_query);
+    assert!(result.is_ok());
+
+    assert_eq!(
+      result.unwrap().sql,
+      "SELECT blog.users.*, auro_posts_json.posts::text AS posts FROM blog.users \
+       LEFT JOIN LATERAL (SELECT COALESCE(json_agg(auro_1_row), '[]'::json) AS posts FROM (\
+       SELECT auro_1.* FROM blog.posts AS auro_1 \
+       WHERE auro_1.user_id = blog.users.id AND (auro_1.published = :p1 OR auro_1.views > :p2) \
+       ORDER BY (SELECT COUNT(*) FROM blog.comments WHERE blog.comments.post_id = auro_1.id) DESC\
+       ) AS auro_1_row) AS auro_posts_json ON TRUE  "
+    );
+  }
+
+  #[test]
+  fn test_many_with_join() {
+    let options = QueryOptions {
+      include: Some(ParsedInclude {
+        joins: vec![ParsedJoin {
+          kind: "INNER".to_string(),
+          table: "posts".to_string(),
+          joining_table: "users".to_string(),
+          alias: None,
+          keys: vec![("author_id".to_string(), "id".to_string())],
+        }],
+        relations: vec![],
+      }),
+      order_by: Some(vec![ParsedOrderBy {
+        field: "createdAt".to_string(),
+        order: "desc".to_string(),
+        table: Some("posts".to_string()),
+        ..Default::default()
+      }]),
+      limit: Some("10".to_string()),
+      ..Default::default()
+    };
+
+    let parsed_query = ParsedQuery {
+      table: "posts".to_string(),
+      schema: "blog".to_string(),
+      options,
+      fields: vec![
+        ParsedField {
+          name: FieldName::Select,
+          arguments: vec![
+            ParsedArgument {
+              name: "posts.title".to_string(),
+              ..Default::default()
+            },
+            ParsedArgument {
+              name: "users.name".to_string(),
+              ..Default::default()
+            },
+          ],
+        },
+        ParsedField {
+          name: FieldName::Where,
+          arguments: vec![ParsedArgument {
+            name: "users.active".to_string(),
+            value: Some(ParsedValue::Boolean("true".to_string())),
+            ..Default::default()
+          }],
+        },
+      ],
+    };
+
+    let built_query = many(&parsed_query).unwrap();
+    assert_eq!(
+      built_query.sql,
+      "SELECT posts.title, users.name FROM blog.posts INNER JOIN blog.users ON posts.author_id = users.id \
+       WHERE users.active = :p1 ORDER BY posts.created_at DESC LIMIT 10"
+    );
+  }
+
+  #[test]
+  fn test_many_with_aliased_joins() {
+    let options = QueryOptions {
+      include: Some(ParsedInclude {
+        joins: vec![
+          ParsedJoin {
+            kind: "LEFT".to_string(),
+            table: "employees".to_string(),
+            joining_table: "employees".to_string(),
+            alias: Some("managers".to_string()),
+            keys: vec![("manager_id".to_string(), "id".to_string())],
+          },
+          ParsedJoin {
+            kind: "INNER".to_string(),
+            table: "employees".to_string(),
+            joining_table: "offices".to_string(),
+            alias: None,
+            keys: vec![
+              ("company_id".to_string(), "company_id".to_string()),
+              ("office_code".to_string(), "code".to_string()),
+            ],
+          },
+        ],
+        relations: vec![],
+      }),
+      ..Default::default()
+    };
+
+    let parsed_query = ParsedQuery {
+      table: "employees".to_string(),
+      schema: "hr".to_string(),
+      options,
+      fields: vec![ParsedField {
+        name: FieldName::Select,
+        arguments: vec![
+          ParsedArgument {
+            name: "employees.name".to_string(),
+            ..Default::default()
+          },
+          ParsedArgument {
+            name: "managers.email".to_string(),
+            ..Default::default()
+          },
+          ParsedArgument {
+            name: "offices.city".to_string(),
+            ..Default::default()
+          },
+        ],
+      }],
+    };
+
+    let built_query = many(&parsed_query).unwrap();
+    assert_eq!(
+      built_query.sql,
+      "SELECT employees.name, managers.email, offices.city FROM hr.employees \
+       LEFT JOIN hr.employees AS managers ON employees.manager_id = managers.id \
+       INNER JOIN hr.offices ON employees.company_id = offices.company_id AND employees.office_code = offices.code  "
+    );
+  }
 }
@@ -0,0 +1,134 @@
+use super::{arguments, error::BuilderError, options::build_options, parameters::Parameters};
+use crate::{
+  constants::constants,
+  parser::parsed::{ParsedArgument, ParsedFilter, ParsedOrderBy, ParsedRelation, QueryOptions},
+};
+
+/// Columns holding the JSON of each included relation, selected next to the columns of the
+/// parent table. They are sent as text, since the Data API doesn't return JSON columns as JSON,
+/// and the engine parses them back before the rows are returned.
+pub fn build_relation_columns(relations: &[ParsedRelation]) -> Vec<String> {
+  relations
+    .iter()
+    .map(|relation| format!("{}.{}::text AS {}", json_alias(relation), relation.name, relation.name))
+    .collect()
+}
+
+/// Joins every included relation as a LATERAL subquery, so each parent row gets its own filtered,
+/// ordered and limited set of related rows. Nested relations are joined the same way inside the
+/// subquery of their parent relation.
+pub fn build_relation_joins(
+  relations: &[ParsedRelation],
+  schema: &str,
+  table: &str,
+  parameters: &mut Parameters,
+) -> Result<String, BuilderError> {
+  let parent = format!("{}.{}", schema, table);
+  let mut num_aliases = 0;
+
+  let joins = relations
+    .iter()
+    .map(|relation| build_relation(relation, schema, &parent, &mut num_aliases, parameters))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  Ok(joins.join(" "))
+}
+
+fn json_alias(relation: &ParsedRelation) -> String {
+  format!("auro_{}_json", relation.name)
+}
+
+/// Aliases every related table, since a relation may point back at a table already in the query.
+fn build_relation(
+  relation: &ParsedRelation,
+  schema: &str,
+  parent: &str,
+  num_aliases: &mut usize,
+  parameters: &mut Parameters,
+) -> Result<String, BuilderError> {
+  *num_aliases += 1;
+  let alias = format!("auro_{}", num_aliases);
+
+  let mut columns = match relation.select.is_empty() {
+    true => vec![format!("{}.*", alias)],
+    false => relation.select.iter().map(|column| format!("{}.{}", alias, column)).collect(),
+  };
+
+  let mut from = format!("{}.{} AS {}", schema, relation.table, alias);
+  for nested in &relation.include {
+    from.push(' ');
+    from.push_str(&build_relation(nested, schema, &alias, num_aliases, parameters)?);
+    columns.push(format!("{}.{} AS {}", json_alias(nested), nested.name, nested.name));
+  }
+
+  let mut conditions = vec![format!("{}.{} = {}.{}", alias, relation.key, parent, relation.references)];
+  for argument in qualify(&relation.filter, &alias) {
+    conditions.push(arguments::build_where(&argument, parameters)?);
+  }
+
+  // Orderings are qualified by the alias too, which relation aggregates are correlated with.
+  let order_by = relation.order_by.clone().map(|order_by| {
+    order_by
+      .into_iter()
+      .map(|order_by| ParsedOrderBy {
+        table: Some(alias.clone()),
+        ..order_by
+      })
+      .collect()
+  });
+
+  let options = QueryOptions {
+    order_by,
+    take: match relation.one {
+      true => Some(1),
+      false => relation.take,
+    },
+    ..Default::default()
+  };
+  let options = build_options(options, "", schema, &relation.table)?;
+
+  let mut rows = format!(
+    "SELECT {} FROM {} {} {}",
+    columns.join(", "),
+    from,
+    constants::WHERE,
+    conditions.join(constants::AND)
+  );
+  if !options.is_empty() {
+    rows.push(' ');
+    rows.push_str(options.trim_start());
+  }
+
+  let value = match relation.one {
+    true => format!("row_to_json({}_row)", alias),
+    false => format!("COALESCE(json_agg({}_row), '[]'::json)", alias),
+  };
+
+  Ok(format!(
+    "LEFT JOIN LATERAL (SELECT {} AS {} FROM ({}) AS {}_row) AS {} ON TRUE",
+    value,
+    relation.name,
+    rows,
+    alias,
+    json_alias(relation)
+  ))
+}
+
+/// Qualifies the columns a relation is filtered on by the alias of its table, so they never resolve to
+/// a column of the parent. Relation filters inside are correlated with the alias instead.
+fn qualify(arguments: &[ParsedArgument], alias: &str) -> Vec<ParsedArgument> {
+  arguments
+    .iter()
+    .cloned()
+    .map(|mut argument| {
+      match &mut argument.filter {
+        Some(ParsedFilter::Relation(relation)) => relation.parent = alias.to_string(),
+        Some(ParsedFilter::And(nested)) | Some(ParsedFilter::Or(nested)) | Some(ParsedFilter::Negate(nested)) => {
+          *nested = qualify(nested, alias)
+        }
+        _ => argument.name = format!("{}.{}", alias, argument.name),
+      }
+      argument
+    })
+    .collect()
+}
@@ -1,14 +1,22 @@
 #[cfg(test)]
 mod args_tests {
-  use crate::{parser::parsed::{ParsedValue, ParsedArgument}, engine::builder::arguments::{build_where, build_return, build_select, parse_value}};
+  use crate::{
+    engine::builder::{
+      arguments::{bind_value, build_filter, build_return, build_select, build_set, build_where},
+      error::BuilderError,
+      parameters::{Parameter, Parameters},
+    },
+    parser::parsed::{ParsedArgument, ParsedFilter, ParsedQuantifier, ParsedRelationFilter, ParsedUpdate, ParsedValue},
+  };
 
   #[test]
   fn test_build_where_with_custom_value() {
     let argument = ParsedArgument {
       name: "column_name".to_string(),
       value: Some(ParsedValue::Custom("custom_value".to_string())),
+      ..Default::default()
     };
-    let result = build_where(&argument);
+    let result = build_where(&argument, &mut Parameters::new());
     assert!(result.is_ok());
 
     let generated_sql = result.unwrap();
@@ -17,35 +25,291 @@ mod args_tests {
 
   #[test]
   fn test_build_where_with_other_values() {
+    let mut parameters = Parameters::new();
+
     // Test with ParsedValue::String
     let argument_string = ParsedArgument {
       name: "column_name".to_string(),
       value: Some(ParsedValue::String("value1".to_string())),
+      ..Default::default()
     };
-    let result_string = build_where(&argument_string);
+    let result_string = build_where(&argument_string, &mut parameters);
     assert!(result_string.is_ok());
     let generated_sql_string = result_string.unwrap();
-    assert_eq!(generated_sql_string, "column_name = 'value1'");
+    assert_eq!(generated_sql_string, "column_name = :p1");
 
     // Test with ParsedValue::Number
     let argument_number = ParsedArgument {
       name: "column_name".to_string(),
       value: Some(ParsedValue::Number("123".to_string())),
+      ..Default::default()
     };
-    let result_number = build_where(&argument_number);
+    let result_number = build_where(&argument_number, &mut parameters);
     assert!(result_number.is_ok());
     let generated_sql_number = result_number.unwrap();
-    assert_eq!(generated_sql_number, "column_name = 123");
+    assert_eq!(generated_sql_number, "column_name = :p2");
 
     // Test with ParsedValue::Null
     let argument_null = ParsedArgument {
       name: "column_name".to_string(),
       value: Some(ParsedValue::Null),
+      ..Default::default()
     };
-    let result_null = build_where(&argument_null);
+    let result_null = build_where(&argument_null, &mut parameters);
     assert!(result_null.is_ok());
     let generated_sql_null = result_null.unwrap();
-    assert_eq!(generated_sql_null, "column_name = NULL");
+    assert_eq!(generated_sql_null, "column_name IS NULL");
+
+    // NULL is rendered inline, so only the string and the number are bound
+    assert_eq!(
+      parameters.into_vec(),
+      vec![
+        Parameter {
+          name: "p1".to_string(),
+          value: ParsedValue::String("value1".to_string()),
+        },
+        Parameter {
+          name: "p2".to_string(),
+          value: ParsedValue::Number("123".to_string()),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn test_build_where_with_filter() {
+    let mut parameters = Parameters::new();
+
+    let argument = ParsedArgument {
+      name: "age".to_string(),
+      value: None,
+      filter: Some(ParsedFilter::Gte(ParsedValue::Number("18".to_string()))),
+      ..Default::default()
+    };
+    assert_eq!(build_where(&argument, &mut parameters).unwrap(), "age >= :p1");
+
+    let argument = ParsedArgument {
+      name: "deleted_at".to_string(),
+      value: None,
+      filter: Some(ParsedFilter::Not(ParsedValue::Null)),
+      ..Default::default()
+    };
+    assert_eq!(build_where(&argument, &mut parameters).unwrap(), "deleted_at IS NOT NULL");
+
+    assert_eq!(parameters.into_vec().len(), 1);
+  }
+
+  #[test]
+  fn test_build_where_without_value() {
+    let mut parameters = Parameters::new();
+
+    let argument = ParsedArgument {
+      name: "deleted_at".to_string(),
+      value: None,
+      ..Default::default()
+    };
+    assert_eq!(
+      build_where(&argument, &mut parameters),
+      Err(BuilderError::MissingArgumentValue("Where arguments require a value or a filter."))
+    );
+  }
+
+  #[test]
+  fn test_build_filter() {
+    let mut parameters = Parameters::new();
+
+    let cases = vec![
+      (ParsedFilter::Equals(ParsedValue::Number("1".to_string())), "id = :p1"),
+      (ParsedFilter::Not(ParsedValue::Number("1".to_string())), "id <> :p2"),
+      (ParsedFilter::Gt(ParsedValue::Number("1".to_string())), "id > :p3"),
+      (ParsedFilter::Lt(ParsedValue::Number("1".to_string())), "id < :p4"),
+      (ParsedFilter::Lte(ParsedValue::Number("1".to_string())), "id <= :p5"),
+      (
+        ParsedFilter::In(vec![ParsedValue::Number("1".to_string()), ParsedValue::Number("2".to_string())]),
+        "id IN (:p6, :p7)",
+      ),
+      (ParsedFilter::NotIn(vec![ParsedValue::Number("3".to_string())]), "id NOT IN (:p8)"),
+      (
+        ParsedFilter::Between(ParsedValue::Number("1".to_string()), ParsedValue::Number("9".to_string())),
+        "id BETWEEN :p9 AND :p10",
+      ),
+      (ParsedFilter::Equals(ParsedValue::Null), "id IS NULL"),
+      (ParsedFilter::IsNull, "id IS NULL"),
+      (ParsedFilter::IsNotNull, "id IS NOT NULL"),
+    ];
+
+    for (filter, expected) in cases {
+      assert_eq!(build_filter("id", &filter, &mut parameters).unwrap(), expected);
+    }
+
+    assert_eq!(parameters.into_vec().len(), 10);
+  }
+
+  #[test]
+  fn test_build_filter_with_empty_list() {
+    let mut parameters = Parameters::new();
+
+    assert_eq!(build_filter("id", &ParsedFilter::In(vec![]), &mut parameters).unwrap(), "FALSE");
+    assert_eq!(build_filter("id", &ParsedFilter::NotIn(vec![]), &mut parameters).unwrap(), "TRUE");
+    assert!(parameters.into_vec().is_empty());
+  }
+
+  #[test]
+  fn test_build_filter_with_groups() {
+    let mut parameters = Parameters::new();
+
+    let argument = |name: &str, value: &str| ParsedArgument {
+      name: name.to_string(),
+      value: Some(ParsedValue::String(value.to_string())),
+      ..Default::default()
+    };
+
+    let filter = ParsedFilter::And(vec![
+      argument("status", "open"),
+      ParsedArgument {
+        name: "or".to_string(),
+        value: None,
+        filter: Some(ParsedFilter::Or(vec![
+          argument("owner", "me"),
+          ParsedArgument {
+            name: "not".to_string(),
+            value: None,
+            filter: Some(ParsedFilter::Negate(vec![argument("shared", "false")])),
+            update: None,
+            aggregate: None,
+          },
+        ])),
+        ..Default::default()
+      },
+    ]);
+
+    assert_eq!(
+      build_filter("and", &filter, &mut parameters).unwrap(),
+      "(status = :p1 AND (owner = :p2 OR NOT (shared = :p3)))"
+    );
+    assert_eq!(parameters.into_vec().len(), 3);
+
+    let mut parameters = Parameters::new();
+    assert_eq!(build_filter("and", &ParsedFilter::And(vec![]), &mut parameters).unwrap(), "TRUE");
+    assert_eq!(build_filter("or", &ParsedFilter::Or(vec![]), &mut parameters).unwrap(), "FALSE");
+    assert_eq!(build_filter("not", &ParsedFilter::Negate(vec![]), &mut parameters).unwrap(), "TRUE");
+  }
+
+  #[test]
+  fn test_build_filter_with_relations() {
+    let unpaid = ParsedArgument {
+      name: "auro_invoices_1.status".to_string(),
+      value: None,
+      filter: Some(ParsedFilter::Equals(ParsedValue::String("unpaid".to_string()))),
+      ..Default::default()
+    };
+    let relation = |quantifier: ParsedQuantifier, arguments: Vec<ParsedArgument>| {
+      ParsedFilter::Relation(ParsedRelationFilter {
+        quantifier,
+        table: Some("billing.invoices".to_string()),
+        keys: vec![("user_id".to_string(), "id".to_string())],
+        alias: "auro_invoices_1".to_string(),
+        parent: "billing.users".to_string(),
+        arguments,
+      })
+    };
+
+    let mut parameters = Parameters::new();
+    assert_eq!(
+      build_filter("invoices", &relation(ParsedQuantifier::Some, vec![unpaid.clone()]), &mut parameters).unwrap(),
+      "EXISTS (SELECT 1 FROM billing.invoices AS auro_invoices_1 WHERE auro_invoices_1.user_id = billing.users.id AND (auro_invoices_1.status = :p1))"
+    );
+    assert_eq!(
+      build_filter("invoices", &relation(ParsedQuantifier::None, vec![unpaid.clone()]), &mut parameters).unwrap(),
+      "NOT EXISTS (SELECT 1 FROM billing.invoices AS auro_invoices_1 WHERE auro_invoices_1.user_id = billing.users.id AND (auro_invoices_1.status = :p2))"
+    );
+    // Related rows the conditions are NULL for don't match them either.
+    assert_eq!(
+      build_filter("invoices", &relation(ParsedQuantifier::Every, vec![unpaid]), &mut parameters).unwrap(),
+      "NOT EXISTS (SELECT 1 FROM billing.invoices AS auro_invoices_1 WHERE auro_invoices_1.user_id = billing.users.id AND (auro_invoices_1.status = :p3) IS NOT TRUE)"
+    );
+
+    assert_eq!(
+      build_filter("invoices", &relation(ParsedQuantifier::Some, vec![]), &mut parameters).unwrap(),
+      "EXISTS (SELECT 1 FROM billing.invoices AS auro_invoices_1 WHERE auro_invoices_1.user_id = billing.users.id)"
+    );
+    assert_eq!(
+      build_filter("invoices", &relation(ParsedQuantifier::Every, vec![]), &mut parameters).unwrap(),
+      "TRUE"
+    );
+
+    let unresolved = ParsedFilter::Relation(ParsedRelationFilter {
+      quantifier: ParsedQuantifier::Some,
+      table: None,
+      keys: vec![],
+      alias: "auro_comments_1".to_string(),
+      parent: "billing.users".to_string(),
+      arguments: vec![],
+    });
+    assert_eq!(
+      build_filter("comments", &unresolved, &mut parameters),
+      Err(BuilderError::InputError("Relation filters need a relation to filter by."))
+    );
+  }
+
+  #[test]
+  fn test_build_filter_with_pattern() {
+    let mut parameters = Parameters::new();
+
+    let contains = ParsedFilter::Contains(ParsedValue::String("50%_off".to_string()));
+    assert_eq!(build_filter("name", &contains, &mut parameters).unwrap(), "name LIKE :p1");
+
+    let starts_with = ParsedFilter::StartsWith(ParsedValue::String("Jo".to_string()));
+    assert_eq!(build_filter("name", &starts_with, &mut parameters).unwrap(), "name LIKE :p2");
+
+    let ends_with = ParsedFilter::EndsWith(ParsedValue::String("son".to_string()));
+    assert_eq!(build_filter("name", &ends_with, &mut parameters).unwrap(), "name LIKE :p3");
+
+    let values = parameters.into_vec().into_iter().map(|parameter| parameter.value).collect::<Vec<_>>();
+    assert_eq!(
+      values,
+      vec![
+        ParsedValue::String("%50\\%\\_off%".to_string()),
+        ParsedValue::String("Jo%".to_string()),
+        ParsedValue::String("%son".to_string()),
+      ]
+    );
+
+    let not_a_string = ParsedFilter::Contains(ParsedValue::Number("1".to_string()));
+    assert_eq!(
+      build_filter("name", &not_a_string, &mut Parameters::new()),
+      Err(BuilderError::InputError("Pattern filters need a string value."))
+    );
+  }
+
+  #[test]
+  fn test_build_set() {
+    let mut parameters = Parameters::new();
+
+    let number = || ParsedValue::Number("2".to_string());
+    let cases = vec![
+      (None, "views = :p1"),
+      (Some(ParsedUpdate::Increment(number())), "views = views + :p2"),
+      (Some(ParsedUpdate::Decrement(number())), "views = views - :p3"),
+      (Some(ParsedUpdate::Multiply(number())), "views = views * :p4"),
+      (Some(ParsedUpdate::Divide(number())), "views = views / :p5"),
+      (Some(ParsedUpdate::Push(number())), "views = array_append(views, :p6)"),
+      (Some(ParsedUpdate::Remove(number())), "views = array_remove(views, :p7)"),
+      (Some(ParsedUpdate::Append(number())), "views = views || :p8"),
+    ];
+
+    for (update, expected) in cases {
+      let argument = ParsedArgument {
+        name: "views".to_string(),
+        value: Some(number()),
+        update,
+        ..Default::default()
+      };
+
+      assert_eq!(build_set(&argument, &mut parameters), expected);
+    }
+
+    assert_eq!(parameters.into_vec().len(), 8);
   }
 
   #[test]
@@ -53,6 +317,7 @@ mod args_tests {
     let argument = ParsedArgument {
       name: "column_name".to_string(),
       value: Some(ParsedValue::String("value1".to_string())),
+      ..Default::default()
     };
     let result = build_return(&argument);
     assert!(result.is_ok());
@@ -66,6 +331,7 @@ mod args_tests {
     let argument = ParsedArgument {
       name: "column_name".to_string(),
       value: Some(ParsedValue::String("value1".to_string())),
+      ..Default::default()
     };
     let result = build_select(&argument);
     assert!(result.is_ok());
@@ -75,20 +341,69 @@ mod args_tests {
   }
 
   #[test]
-  fn test_parse_value() {
+  fn test_bind_value() {
+    let mut parameters = Parameters::new();
+
     // Test with ParsedValue::String
-    let parsed_value_string = Some(ParsedValue::String("value1".to_string()));
-    let result_string = parse_value(&parsed_value_string);
-    assert_eq!(result_string, "'value1'");
+    let parsed_value_string = Some(ParsedValue::String("it's quoted".to_string()));
+    let result_string = bind_value(&parsed_value_string, &mut parameters);
+    assert_eq!(result_string, ":p1");
 
     // Test with ParsedValue::Number
     let parsed_value_number = Some(ParsedValue::Number("123".to_string()));
-    let result_number = parse_value(&parsed_value_number);
-    assert_eq!(result_number, "123");
+    let result_number = bind_value(&parsed_value_number, &mut parameters);
+    assert_eq!(result_number, ":p2");
 
     // Test with ParsedValue::Null
     let parsed_value_null = Some(ParsedValue::Null);
-    let result_null = parse_value(&parsed_value_null);
+    let result_null = bind_value(&parsed_value_null, &mut parameters);
     assert_eq!(result_null, "NULL");
+
+    // Test with ParsedValue::Custom
+    let parsed_value_custom = Some(ParsedValue::Custom("now()".to_string()));
+    let result_custom = bind_value(&parsed_value_custom, &mut parameters);
+    assert_eq!(result_custom, "now()");
+
+    let parameters = parameters.into_vec();
+    assert_eq!(parameters.len(), 2);
+    assert_eq!(parameters[0].value, ParsedValue::String("it's quoted".to_string()));
+  }
+
+  #[test]
+  fn test_bind_value_with_cast() {
+    let mut parameters = Parameters::new();
+
+    let jsonb = Some(ParsedValue::Jsonb("{}".to_string()));
+    assert_eq!(bind_value(&jsonb, &mut parameters), "CAST(:p1 AS jsonb)");
+
+    let status = Some(ParsedValue::Cast(
+      Box::new(ParsedValue::Enum("paid".to_string())),
+      "shop.order_status".to_string(),
+    ));
+    assert_eq!(bind_value(&status, &mut parameters), "CAST(:p2 AS shop.order_status)");
+
+    let numbers = Some(ParsedValue::Array(vec![
+      ParsedValue::Number("1".to_string()),
+      ParsedValue::Null,
+    ]));
+    assert_eq!(bind_value(&numbers, &mut parameters), "CAST(:p3 AS bigint[])");
+
+    let uuid = Some(ParsedValue::Uuid("8c1e0a44-5f1b-4a36-9d8a-2b6f3c1d9e70".to_string()));
+    assert_eq!(bind_value(&uuid, &mut parameters), ":p4");
+  }
+
+  #[test]
+  fn test_array_literal() {
+    let values = vec![
+      ParsedValue::String("say \"hi\"".to_string()),
+      ParsedValue::String("back\\slash".to_string()),
+      ParsedValue::Null,
+      ParsedValue::Array(vec![ParsedValue::Number("1".to_string())]),
+    ];
+
+    assert_eq!(
+      ParsedValue::array_literal(&values),
+      r#"{"say \"hi\"","back\\slash",NULL,{"1"}}"#
+    );
   }
 }
@@ -4,9 +4,10 @@ mod filed_tests {
     constants::errors,
     engine::builder::{
       error::BuilderError,
-      fields::{build_one_data, build_return, build_select, build_set, build_where},
+      fields::{build_include, build_one_data, build_return, build_select, build_set, build_where},
+      parameters::{Parameter, Parameters},
     },
-    parser::parsed::{FieldName, ParsedArgument, ParsedField, ParsedValue},
+    parser::parsed::{FieldName, ParsedArgument, ParsedField, ParsedInclude, ParsedJoin, ParsedValue},
   };
 
   #[test]
@@ -17,10 +18,12 @@ mod filed_tests {
         ParsedArgument {
           name: "arg1".to_string(),
           value: Some(ParsedValue::String("value1".to_string())),
+          ..Default::default()
         },
         ParsedArgument {
           name: "arg2".to_string(),
           value: Some(ParsedValue::String("value2".to_string())),
+          ..Default::default()
         },
       ],
     };
@@ -51,25 +54,41 @@ mod filed_tests {
         ParsedArgument {
           name: "arg1".to_string(),
           value: Some(ParsedValue::String("value1".to_string())),
+          ..Default::default()
         },
         ParsedArgument {
           name: "arg2".to_string(),
           value: Some(ParsedValue::String("value2".to_string())),
+          ..Default::default()
         },
       ],
     };
-    let result = build_where(&parsed_field);
+    let mut parameters = Parameters::new();
+    let result = build_where(&parsed_field, &mut parameters);
     assert!(result.is_ok());
 
     let generated_sql = result.unwrap();
-    assert_eq!(generated_sql, "WHERE arg1 = 'value1' AND arg2 = 'value2'");
+    assert_eq!(generated_sql, "WHERE arg1 = :p1 AND arg2 = :p2");
+    assert_eq!(
+      parameters.into_vec(),
+      vec![
+        Parameter {
+          name: "p1".to_string(),
+          value: ParsedValue::String("value1".to_string()),
+        },
+        Parameter {
+          name: "p2".to_string(),
+          value: ParsedValue::String("value2".to_string()),
+        },
+      ]
+    );
 
     // Test with no arguments
     let parsed_field_no_args = ParsedField {
       name: FieldName::Where,
       arguments: vec![],
     };
-    let result_no_args = build_where(&parsed_field_no_args);
+    let result_no_args = build_where(&parsed_field_no_args, &mut Parameters::new());
     assert!(result_no_args.is_err());
     assert_eq!(
       result_no_args.err().unwrap(),
@@ -85,25 +104,41 @@ mod filed_tests {
         ParsedArgument {
           name: "arg1".to_string(),
           value: Some(ParsedValue::String("value1".to_string())),
+          ..Default::default()
         },
         ParsedArgument {
           name: "arg2".to_string(),
           value: Some(ParsedValue::String("value2".to_string())),
+          ..Default::default()
         },
       ],
     };
-    let result = build_set(&parsed_field);
+    let mut parameters = Parameters::new();
+    let result = build_set(&parsed_field, &mut parameters);
     assert!(result.is_ok());
 
     let generated_sql = result.unwrap();
-    assert_eq!(generated_sql, "SET arg1 = 'value1', arg2 = 'value2'");
+    assert_eq!(generated_sql, "SET arg1 = :p1, arg2 = :p2");
+    assert_eq!(
+      parameters.into_vec(),
+      vec![
+        Parameter {
+          name: "p1".to_string(),
+          value: ParsedValue::String("value1".to_string()),
+        },
+        Parameter {
+          name: "p2".to_string(),
+          value: ParsedValue::String("value2".to_string()),
+        },
+      ]
+    );
 
     // Test with no arguments
     let parsed_field_no_args = ParsedField {
       name: FieldName::Set,
       arguments: vec![],
     };
-    let result_no_args = build_set(&parsed_field_no_args);
+    let result_no_args = build_set(&parsed_field_no_args, &mut Parameters::new());
     assert!(result_no_args.is_err());
     assert_eq!(
       result_no_args.err().unwrap(),
@@ -119,25 +154,41 @@ mod filed_tests {
         ParsedArgument {
           name: "arg1".to_string(),
           value: Some(ParsedValue::String("value1".to_string())),
+          ..Default::default()
         },
         ParsedArgument {
           name: "arg2".to_string(),
           value: Some(ParsedValue::String("value2".to_string())),
+          ..Default::default()
         },
       ],
     };
-    let result = build_one_data(&parsed_field);
+    let mut parameters = Parameters::new();
+    let result = build_one_data(&parsed_field, &mut parameters);
     assert!(result.is_ok());
 
     let generated_sql = result.unwrap();
-    assert_eq!(generated_sql, "(arg1, arg2) VALUES ('value1', 'value2')");
+    assert_eq!(generated_sql, "(arg1, arg2) VALUES (:p1, :p2)");
+    assert_eq!(
+      parameters.into_vec(),
+      vec![
+        Parameter {
+          name: "p1".to_string(),
+          value: ParsedValue::String("value1".to_string()),
+        },
+        Parameter {
+          name: "p2".to_string(),
+          value: ParsedValue::String("value2".to_string()),
+        },
+      ]
+    );
 
     // Test with no arguments
     let parsed_field_no_args = ParsedField {
       name: FieldName::Data,
       arguments: vec![],
     };
-    let result_no_args = build_one_data(&parsed_field_no_args);
+    let result_no_args = build_one_data(&parsed_field_no_args, &mut Parameters::new());
     assert!(result_no_args.is_err());
     assert_eq!(
       result_no_args.err().unwrap(),
@@ -153,10 +204,12 @@ mod filed_tests {
         ParsedArgument {
           name: "arg1".to_string(),
           value: Some(ParsedValue::String("value1".to_string())),
+          ..Default::default()
         },
         ParsedArgument {
           name: "arg2".to_string(),
           value: Some(ParsedValue::String("value2".to_string())),
+          ..Default::default()
         },
       ],
     };
@@ -175,4 +228,36 @@ mod filed_tests {
     assert!(result_no_args.is_ok());
     assert_eq!(result_no_args.unwrap(), String::from(""));
   }
+
+  #[test]
+  fn test_build_include_with_join_kinds() {
+    let parsed_include = ParsedInclude {
+      joins: vec![
+        ParsedJoin {
+          kind: "LEFT".to_string(),
+          table: "employees".to_string(),
+          joining_table: "employees".to_string(),
+          alias: Some("manager".to_string()),
+          keys: vec![
+            ("manager_id".to_string(), "id".to_string()),
+            ("tenant_id".to_string(), "tenant_id".to_string()),
+          ],
+        },
+        ParsedJoin {
+          kind: "INNER".to_string(),
+          table: "employees".to_string(),
+          joining_table: "departments".to_string(),
+          alias: None,
+          keys: vec![("department_id".to_string(), "id".to_string())],
+        },
+      ],
+      relations: vec![],
+    };
+
+    assert_eq!(
+      build_include(&parsed_include, "hr").unwrap(),
+      "LEFT JOIN hr.employees AS manager ON employees.manager_id = manager.id AND employees.tenant_id = manager.tenant_id \
+       INNER JOIN hr.departments ON employees.department_id = departments.id"
+    );
+  }
 }
@@ -1,53 +1,371 @@
 #[cfg(test)]
 mod option_tests {
-  use std::collections::HashMap;
+  use crate::{
+    engine::builder::{
+      error::BuilderError,
+      options::{bucket_expression, build_cursor, build_options, build_page_order},
+      parameters::Parameters,
+    },
+    parser::parsed::{
+      ParsedAggregate, ParsedArgument, ParsedOrderBy, ParsedRelationOrder, ParsedTimeBucket, ParsedValue, QueryOptions,
+    },
+  };
 
-  use crate::{engine::builder::options::build_options, parser::parsed::QueryOptions};
+  fn order_by_field(field: &str, order: &str) -> ParsedOrderBy {
+    ParsedOrderBy {
+      field: field.to_string(),
+      order: order.to_string(),
+      nulls: None,
+      table: None,
+      relation: None,
+      aggregate: None,
+    }
+  }
+
+  fn cursor_options(order_by: Vec<(&str, &str)>, cursor: Vec<(&str, &str)>, take: i64) -> QueryOptions {
+    QueryOptions {
+      order_by: match order_by.is_empty() {
+        true => None,
+        false => Some(
+          order_by
+            .into_iter()
+            .map(|(field, order)| order_by_field(field, order))
+            .collect(),
+        ),
+      },
+      limit: None,
+      offset: None,
+      num_of_rows: None,
+      include: None,
+      group_by: None,
+      cursor: Some(
+        cursor
+          .into_iter()
+          .map(|(name, value)| ParsedArgument {
+            name: name.to_string(),
+            value: Some(ParsedValue::Number(value.to_string())),
+            filter: None,
+            update: None,
+            aggregate: None,
+          })
+          .collect(),
+      ),
+      take: Some(take),
+      skip: Some(1),
+      paginate: false,
+      page_cursor: None,
+      time_bucket: None,
+    }
+  }
 
   #[test]
   fn test_build_options() {
     let options = QueryOptions {
-      order_by: Some({
-        let mut order_by = HashMap::new();
-        order_by.insert("fieldName".to_string(), "asc".to_string());
-        order_by
-      }),
+      order_by: Some(vec![order_by_field("fieldName", "asc")]),
       limit: Some("10".to_string()),
       offset: Some("5".to_string()),
       num_of_rows: Some(100),
       include: None, // You can provide a ParsedInclude here
       group_by: Some(vec!["column1".to_string(), "column2".to_string()]),
+      ..Default::default()
     };
 
-    let result = build_options(options);
+    let result = build_options(options, "", "my_schema", "my_table");
     assert!(result.is_ok());
 
     let generated_sql = result.unwrap();
     assert_eq!(
       generated_sql,
-      "ORDER BY field_name ASC LIMIT 10 OFFSET 5 GROUP BY column1, column2"
+      "GROUP BY column1, column2 ORDER BY field_name ASC LIMIT 10 OFFSET 5"
     );
   }
 
   #[test]
   fn test_build_options_with_empty_group_by() {
     let options = QueryOptions {
-      order_by: Some({
-        let mut order_by = HashMap::new();
-        order_by.insert("fieldName".to_string(), "asc".to_string());
-        order_by
-      }),
+      order_by: Some(vec![order_by_field("fieldName", "asc")]),
       limit: Some("10".to_string()),
       offset: Some("5".to_string()),
       num_of_rows: Some(100),
       include: None, // You can provide a ParsedInclude here
       group_by: Some(vec![]),
+      ..Default::default()
     };
 
-    let result = build_options(options);
+    let result = build_options(options, "", "my_schema", "my_table");
     assert!(result.is_ok());
 
     let generated_sql = result.unwrap();
     assert_eq!(generated_sql, "ORDER BY field_name ASC LIMIT 10 OFFSET 5");
   }
+
+  #[test]
+  fn test_build_cursor_forwards() {
+    let options = cursor_options(vec![], vec![("id", "42")], 20);
+    let mut parameters = Parameters::new();
+
+    // Nulls sort last in ascending order, so they follow every cursor value.
+    assert_eq!(build_cursor(&options, &mut parameters).unwrap(), "(id >= :p1 OR (id IS NULL))");
+    assert_eq!(build_options(options, "", "my_schema", "my_table").unwrap(), "ORDER BY id ASC LIMIT 20 OFFSET 1");
+  }
+
+  #[test]
+  fn test_build_cursor_backwards() {
+    let options = cursor_options(vec![("createdAt", "desc")], vec![("created_at", "100"), ("id", "42")], -20);
+    let mut parameters = Parameters::new();
+
+    // Descending created_at flips to ascending when paging backwards, while id flips to descending.
+    assert_eq!(
+      build_cursor(&options, &mut parameters).unwrap(),
+      "((created_at > :p1) OR (created_at IS NULL) OR (created_at = :p1 AND id < :p2) OR (created_at = :p1 AND id = :p2))"
+    );
+    assert_eq!(parameters.into_vec().len(), 2);
+    assert_eq!(
+      build_options(options.clone(), "", "my_schema", "my_table").unwrap(),
+      "ORDER BY created_at ASC, id DESC LIMIT 20 OFFSET 1"
+    );
+    assert_eq!(build_page_order(&options).unwrap(), "ORDER BY created_at DESC, id ASC");
+  }
+
+  #[test]
+  fn test_build_page_order() {
+    let mut options = cursor_options(vec![], vec![("authors.id", "42")], -10);
+    options.order_by = Some(vec![ParsedOrderBy {
+      field: "name".to_string(),
+      order: "asc".to_string(),
+      nulls: Some("first".to_string()),
+      table: Some("authors".to_string()),
+      ..Default::default()
+    }]);
+
+    // The page is ordered by the names its columns have in the subquery.
+    assert_eq!(build_page_order(&options).unwrap(), "ORDER BY name ASC NULLS FIRST, id ASC");
+
+    options.order_by.as_mut().unwrap()[0].aggregate = Some(ParsedAggregate::Count);
+    assert_eq!(
+      build_page_order(&options),
+      Err(BuilderError::InputError(
+        "Taking rows backwards can't be combined with ordering by a relation or an aggregate."
+      ))
+    );
+  }
+
+  #[test]
+  fn test_build_cursor_with_same_direction() {
+    let options = cursor_options(vec![("createdAt", "desc")], vec![("created_at", "100")], 10);
+    let mut parameters = Parameters::new();
+
+    assert_eq!(build_cursor(&options, &mut parameters).unwrap(), "created_at <= :p1");
+
+    let options = cursor_options(vec![], vec![("tenant_id", "1"), ("id", "42")], 10);
+    assert_eq!(
+      build_cursor(&options, &mut Parameters::new()).unwrap(),
+      "((tenant_id, id) >= (:p1, :p2) OR (tenant_id IS NULL) OR (tenant_id = :p1 AND id IS NULL))"
+    );
+  }
+
+  #[test]
+  fn test_build_cursor_with_null_values() {
+    let mut options = cursor_options(vec![("score", "desc")], vec![("id", "42")], 10);
+    options.paginate = true;
+    options.cursor = Some(vec![
+      ParsedArgument {
+        name: "score".to_string(),
+        value: Some(ParsedValue::Null),
+        ..Default::default()
+      },
+      ParsedArgument {
+        name: "id".to_string(),
+        value: Some(ParsedValue::Number("42".to_string())),
+        ..Default::default()
+      },
+    ]);
+
+    // Nulls sort first in descending order, so every score follows a null one.
+    let mut parameters = Parameters::new();
+    assert_eq!(
+      build_cursor(&options, &mut parameters).unwrap(),
+      "((score IS NOT NULL) OR (score IS NULL AND id > :p1) OR (score IS NULL AND id IS NULL))"
+    );
+    assert_eq!(parameters.into_vec().len(), 1);
+
+    // Placed last, nothing follows a null score but the rows sharing it.
+    options.order_by.as_mut().unwrap()[0].nulls = Some("last".to_string());
+    assert_eq!(
+      build_cursor(&options, &mut Parameters::new()).unwrap(),
+      "((score IS NULL AND id > :p1) OR (score IS NULL AND id IS NULL))"
+    );
+  }
+
+  #[test]
+  fn test_build_cursor_with_explicit_nulls() {
+    let mut options = cursor_options(vec![("score", "asc")], vec![("score", "7")], 10);
+    options.order_by.as_mut().unwrap()[0].nulls = Some("first".to_string());
+
+    assert_eq!(build_cursor(&options, &mut Parameters::new()).unwrap(), "score >= :p1");
+
+    // Paging backwards moves the nulls to the end.
+    options.take = Some(-10);
+    assert_eq!(build_cursor(&options, &mut Parameters::new()).unwrap(), "(score <= :p1 OR (score IS NULL))");
+  }
+
+  #[test]
+  fn test_build_cursor_without_ordered_column() {
+    let options = cursor_options(vec![("createdAt", "desc")], vec![("id", "42")], 10);
+
+    assert_eq!(
+      build_cursor(&options, &mut Parameters::new()),
+      Err(BuilderError::InputError("Cursors need a value for every column the query is ordered by."))
+    );
+  }
+
+  #[test]
+  fn test_build_options_with_smallest_take() {
+    let mut options = cursor_options(vec![], vec![], i64::MIN);
+    options.cursor = None;
+    options.skip = None;
+
+    assert_eq!(
+      build_options(options, "", "my_schema", "my_table").unwrap(),
+      " LIMIT 9223372036854775808"
+    );
+  }
+
+  #[test]
+  fn test_build_options_with_ordered_list() {
+    let mut options = cursor_options(vec![], vec![], 10);
+    options.cursor = None;
+    options.take = None;
+    options.skip = None;
+    options.order_by = Some(vec![
+      ParsedOrderBy {
+        field: "publishedAt".to_string(),
+        order: "desc".to_string(),
+        nulls: Some("last".to_string()),
+        ..Default::default()
+      },
+      ParsedOrderBy {
+        field: "name".to_string(),
+        order: "asc".to_string(),
+        table: Some("authors".to_string()),
+        ..Default::default()
+      },
+      ParsedOrderBy {
+        field: "id".to_string(),
+        order: "desc".to_string(),
+        relation: Some(ParsedRelationOrder {
+          table: "comments".to_string(),
+          key: "post_id".to_string(),
+          references: "id".to_string(),
+          function: "count".to_string(),
+        }),
+        ..Default::default()
+      },
+      order_by_field("id", "asc"),
+    ]);
+
+    assert_eq!(
+      build_options(options, "", "blog", "posts").unwrap(),
+      "ORDER BY published_at DESC NULLS LAST, authors.name ASC, (SELECT COUNT(*) FROM blog.comments WHERE blog.comments.post_id = blog.posts.id) DESC, id ASC"
+    );
+  }
+
+  #[test]
+  fn test_build_options_backwards_with_nulls() {
+    let mut options = cursor_options(vec![], vec![("id", "42")], -5);
+    options.order_by = Some(vec![ParsedOrderBy {
+      field: "score".to_string(),
+      order: "desc".to_string(),
+      nulls: Some("first".to_string()),
+      ..Default::default()
+    }]);
+
+    assert_eq!(
+      build_options(options, "", "my_schema", "my_table").unwrap(),
+      "ORDER BY score ASC NULLS LAST, id DESC LIMIT 5 OFFSET 1"
+    );
+  }
+
+  #[test]
+  fn test_build_cursor_with_relation_order() {
+    let mut options = cursor_options(vec![], vec![("id", "42")], 5);
+    options.order_by = Some(vec![ParsedOrderBy {
+      field: "amount".to_string(),
+      order: "desc".to_string(),
+      relation: Some(ParsedRelationOrder {
+        table: "orders".to_string(),
+        key: "customer_id".to_string(),
+        references: "id".to_string(),
+        function: "sum".to_string(),
+      }),
+      ..Default::default()
+    }]);
+
+    assert!(build_cursor(&options, &mut Parameters::new()).is_err());
+    assert_eq!(
+      build_options(options, "", "shop", "customers").unwrap(),
+      "ORDER BY (SELECT SUM(shop.orders.amount) FROM shop.orders WHERE shop.orders.customer_id = shop.customers.id) DESC, id ASC LIMIT 5 OFFSET 1"
+    );
+  }
+
+  #[test]
+  fn test_build_options_with_group_by_and_having() {
+    let mut options = cursor_options(vec![], vec![], 10);
+    options.cursor = None;
+    options.skip = None;
+    options.group_by = Some(vec!["customer_id".to_string()]);
+    options.order_by = Some(vec![ParsedOrderBy {
+      field: "totalAmount".to_string(),
+      order: "desc".to_string(),
+      aggregate: Some(ParsedAggregate::Sum),
+      ..Default::default()
+    }]);
+
+    assert_eq!(
+      build_options(options.clone(), "HAVING COUNT(*) > :p1", "shop", "orders").unwrap(),
+      "GROUP BY customer_id HAVING COUNT(*) > :p1 ORDER BY SUM(total_amount) DESC LIMIT 10"
+    );
+
+    options.cursor = Some(vec![ParsedArgument {
+      name: "customer_id".to_string(),
+      value: Some(ParsedValue::Number("1".to_string())),
+      ..Default::default()
+    }]);
+    assert_eq!(
+      build_cursor(&options, &mut Parameters::new()),
+      Err(BuilderError::InputError("Cursors can't be combined with ordering by an aggregate."))
+    );
+  }
+
+  #[test]
+  fn test_time_bucket_of_timestamp_column() {
+    let bucket = ParsedTimeBucket {
+      column: "created_at".to_string(),
+      unit: "day".to_string(),
+      timezone: Some("Europe/Berlin".to_string()),
+      column_type: Some("timestamp".to_string()),
+      fill: None,
+    };
+
+    // Timestamps without a time zone are read as UTC before converting them to the bucket's zone.
+    assert_eq!(
+      bucket_expression(&bucket),
+      "CAST(date_trunc('day', (created_at AT TIME ZONE 'UTC') AT TIME ZONE 'Europe/Berlin') AS timestamp)"
+    );
+
+    let bucket = ParsedTimeBucket {
+      column_type: Some("timestamptz".to_string()),
+      ..bucket
+    };
+    assert_eq!(
+      bucket_expression(&bucket),
+      "CAST(date_trunc('day', created_at AT TIME ZONE 'Europe/Berlin') AS timestamp)"
+    );
+
+    let bucket = ParsedTimeBucket {
+      column_type: Some("date".to_string()),
+      ..bucket
+    };
+    assert_eq!(bucket_expression(&bucket), "CAST(date_trunc('day', created_at) AS timestamp)");
+  }
 }
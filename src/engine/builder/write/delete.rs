@@ -1,20 +1,22 @@
 use crate::{
-  engine::builder::{error::BuilderError, builder::QueryBuilder},
+  engine::builder::{builder::QueryBuilder, error::BuilderError, parameters::BuiltQuery},
   parser::parsed::{self, FieldName},
 };
 
-pub fn unique(query_schema: &parsed::ParsedQuery) -> Result<String, BuilderError> {
-  let builder = QueryBuilder::new(&query_schema)
+pub fn unique(query_schema: &parsed::ParsedQuery) -> Result<BuiltQuery, BuilderError> {
+  let mut builder = QueryBuilder::new(query_schema);
+  let query = builder
     .order_fields(vec![FieldName::From, FieldName::Where, FieldName::Return])
     .build_ordered()?;
- 
-  Ok(format!("DELETE FROM {}", builder))
+
+  Ok(builder.finish(format!("DELETE FROM {}", query)))
 }
 
-pub fn many(query_schema: &parsed::ParsedQuery) -> Result<String, BuilderError> {
-  let builder = QueryBuilder::new(&query_schema)
+pub fn many(query_schema: &parsed::ParsedQuery) -> Result<BuiltQuery, BuilderError> {
+  let mut builder = QueryBuilder::new(query_schema);
+  let query = builder
     .order_fields(vec![FieldName::From, FieldName::Where, FieldName::Return])
     .build_ordered()?;
 
-  Ok(format!("DELETE FROM {}", builder))
+  Ok(builder.finish(format!("DELETE FROM {}", query)))
 }
\ No newline at end of file
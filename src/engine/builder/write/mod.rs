@@ -1,4 +1,5 @@
 mod tests;
 pub mod insert;
 pub mod delete;
-pub mod update;
\ No newline at end of file
+pub mod update;
+pub mod upsert;
\ No newline at end of file
@@ -14,6 +14,7 @@ mod tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -26,6 +27,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -33,6 +35,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg3".to_string(),
             value: Some(ParsedValue::String("value3".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -41,10 +44,10 @@ mod tests {
     let result = unique(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "DELETE FROM my_schema.my_table WHERE arg3 = 'value3' RETURNING arg1 "
+      "DELETE FROM my_schema.my_table WHERE arg3 = :p1 RETURNING arg1 "
     );
   }
 
@@ -57,6 +60,7 @@ mod tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -69,6 +73,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg3".to_string(),
             value: Some(ParsedValue::String("value3".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -76,6 +81,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -84,10 +90,10 @@ mod tests {
     let result = many(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "DELETE FROM my_schema.my_table WHERE arg3 = 'value3' RETURNING arg1 "
+      "DELETE FROM my_schema.my_table WHERE arg3 = :p1 RETURNING arg1 "
     );
   }
 
@@ -100,6 +106,7 @@ mod tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -112,6 +119,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg3".to_string(),
             value: Some(ParsedValue::String("value3".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -124,8 +132,8 @@ mod tests {
     let result = unique(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
-    assert_eq!(generated_sql, "DELETE FROM my_schema.my_table WHERE arg3 = 'value3'  ");
+    let generated_sql = result.unwrap().sql;
+    assert_eq!(generated_sql, "DELETE FROM my_schema.my_table WHERE arg3 = :p1  ");
   }
 
   #[test]
@@ -137,6 +145,7 @@ mod tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -149,6 +158,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg3".to_string(),
             value: Some(ParsedValue::String("value3".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -156,6 +166,7 @@ mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -164,10 +175,10 @@ mod tests {
     let result = unique(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "DELETE FROM my_schema.my_table WHERE arg3 = 'value3' RETURNING arg1  LIMIT 10 OFFSET 5"
+      "DELETE FROM my_schema.my_table WHERE arg3 = :p1 RETURNING arg1  LIMIT 10 OFFSET 5"
     );
   }
 
@@ -180,6 +191,7 @@ mod tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -191,6 +203,7 @@ mod tests {
         arguments: vec![ParsedArgument {
           name: "arg3".to_string(),
           value: Some(ParsedValue::String("value3".to_string())),
+          ..Default::default()
         }],
       }],
     };
@@ -198,10 +211,10 @@ mod tests {
     let result = unique(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "DELETE FROM my_schema.my_table WHERE arg3 = 'value3' RETURNING * ORDER BY  LIMIT 10 OFFSET 5"
+      "DELETE FROM my_schema.my_table WHERE arg3 = :p1 RETURNING *  LIMIT 10 OFFSET 5"
     );
   }
 }
@@ -14,6 +14,7 @@ mod insert_tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -25,6 +26,7 @@ mod insert_tests {
         arguments: vec![ParsedArgument {
           name: "arg1".to_string(),
           value: Some(ParsedValue::String("value1".to_string())),
+          ..Default::default()
         }],
       }],
     };
@@ -32,10 +34,10 @@ mod insert_tests {
     let result = one(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "INSERT INTO my_schema.my_table (arg1) VALUES ('value1') RETURNING * "
+      "INSERT INTO my_schema.my_table (arg1) VALUES (:p1) RETURNING * "
     );
   }
 
@@ -48,6 +50,7 @@ mod insert_tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -60,10 +63,12 @@ mod insert_tests {
           ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           },
           ParsedArgument {
             name: "arg2".to_string(),
             value: Some(ParsedValue::String("value2".to_string())),
+            ..Default::default()
           },
         ],
       }],
@@ -72,10 +77,10 @@ mod insert_tests {
     let result = many(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "INSERT INTO my_schema.my_table (arg1, arg2) VALUES ('value1', 'value2') RETURNING * "
+      "INSERT INTO my_schema.my_table (arg1, arg2) VALUES (:p1, :p2) RETURNING * "
     );
   }
 
@@ -88,6 +93,7 @@ mod insert_tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -100,6 +106,7 @@ mod insert_tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -107,6 +114,7 @@ mod insert_tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -115,10 +123,10 @@ mod insert_tests {
     let result = one(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "INSERT INTO my_schema.my_table (arg1) VALUES ('value1') RETURNING arg1 "
+      "INSERT INTO my_schema.my_table (arg1) VALUES (:p1) RETURNING arg1 "
     );
   }
 
@@ -131,6 +139,7 @@ mod insert_tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -143,6 +152,7 @@ mod insert_tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -155,7 +165,7 @@ mod insert_tests {
     let result = one(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
-    assert_eq!(generated_sql, "INSERT INTO my_schema.my_table (arg1) VALUES ('value1')  ");
+    let generated_sql = result.unwrap().sql;
+    assert_eq!(generated_sql, "INSERT INTO my_schema.my_table (arg1) VALUES (:p1)  ");
   }
 }
@@ -1,3 +1,4 @@
 pub mod delete;
 pub mod insert;
-pub mod update;
\ No newline at end of file
+pub mod update;
+pub mod upsert;
\ No newline at end of file
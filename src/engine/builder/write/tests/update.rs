@@ -1,8 +1,11 @@
 #[cfg(test)]
 mod update_tests {
   use crate::{
-    engine::builder::update::{many, one},
-    parser::parsed::{FieldName, ParsedArgument, ParsedField, ParsedQuery, ParsedValue, QueryOptions},
+    engine::builder::{
+      parameters::Parameter,
+      update::{many, one},
+    },
+    parser::parsed::{FieldName, ParsedArgument, ParsedField, ParsedQuery, ParsedUpdate, ParsedValue, QueryOptions},
   };
 
   #[test]
@@ -14,6 +17,7 @@ mod update_tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -26,6 +30,7 @@ mod update_tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -33,6 +38,7 @@ mod update_tests {
           arguments: vec![ParsedArgument {
             name: "arg3".to_string(),
             value: Some(ParsedValue::String("value3".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -41,10 +47,23 @@ mod update_tests {
     let result = one(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let query = result.unwrap();
     assert_eq!(
-      generated_sql,
-      "UPDATE my_schema.my_table SET arg1 = 'value1' WHERE arg3 = 'value3' RETURNING * "
+      query.sql,
+      "UPDATE my_schema.my_table SET arg1 = :p1 WHERE arg3 = :p2 RETURNING * "
+    );
+    assert_eq!(
+      query.parameters,
+      vec![
+        Parameter {
+          name: "p1".to_string(),
+          value: ParsedValue::String("value1".to_string()),
+        },
+        Parameter {
+          name: "p2".to_string(),
+          value: ParsedValue::String("value3".to_string()),
+        },
+      ]
     );
   }
 
@@ -57,6 +76,7 @@ mod update_tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -70,10 +90,12 @@ mod update_tests {
             ParsedArgument {
               name: "arg1".to_string(),
               value: Some(ParsedValue::String("value1".to_string())),
+              ..Default::default()
             },
             ParsedArgument {
               name: "arg2".to_string(),
               value: Some(ParsedValue::String("value2".to_string())),
+              ..Default::default()
             },
           ],
         }
@@ -83,10 +105,65 @@ mod update_tests {
     let result = many(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
+    assert_eq!(
+      generated_sql,
+      "UPDATE my_schema.my_table SET arg1 = :p1, arg2 = :p2  RETURNING * "
+    );
+  }
+
+  #[test]
+  fn test_many_with_update_operators() {
+    let options = QueryOptions {
+      order_by: None,
+      limit: None,
+      offset: None,
+      num_of_rows: None,
+      include: None,
+      group_by: None,
+      ..Default::default()
+    };
+
+    let parsed_query = ParsedQuery {
+      table: "my_table".to_string(),
+      schema: "my_schema".to_string(),
+      options: options.clone(),
+      fields: vec![
+        ParsedField {
+          name: FieldName::Set,
+          arguments: vec![
+            ParsedArgument {
+              name: "views".to_string(),
+              value: None,
+              update: Some(ParsedUpdate::Increment(ParsedValue::Number("1".to_string()))),
+              ..Default::default()
+            },
+            ParsedArgument {
+              name: "tags".to_string(),
+              value: None,
+              update: Some(ParsedUpdate::Push(ParsedValue::String("new".to_string()))),
+              ..Default::default()
+            },
+          ],
+        },
+        ParsedField {
+          name: FieldName::Where,
+          arguments: vec![ParsedArgument {
+            name: "id".to_string(),
+            value: Some(ParsedValue::Number("7".to_string())),
+            ..Default::default()
+          }],
+        },
+      ],
+    };
+
+    let result = many(&parsed_query);
+    assert!(result.is_ok());
+
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "UPDATE my_schema.my_table SET arg1 = 'value1', arg2 = 'value2'  RETURNING * "
+      "UPDATE my_schema.my_table SET views = views + :p1, tags = array_append(tags, :p2) WHERE id = :p3 RETURNING * "
     );
   }
 
@@ -99,6 +176,7 @@ mod update_tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -112,10 +190,12 @@ mod update_tests {
             ParsedArgument {
               name: "arg1".to_string(),
               value: Some(ParsedValue::String("value1".to_string())),
+              ..Default::default()
             },
             ParsedArgument {
               name: "arg2".to_string(),
               value: Some(ParsedValue::String("value2".to_string())),
+              ..Default::default()
             },
           ],
         },
@@ -124,6 +204,7 @@ mod update_tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
       ],
@@ -132,10 +213,10 @@ mod update_tests {
     let result = one(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
+    let generated_sql = result.unwrap().sql;
     assert_eq!(
       generated_sql,
-      "UPDATE my_schema.my_table SET arg1 = 'value1', arg2 = 'value2'  RETURNING arg1 "
+      "UPDATE my_schema.my_table SET arg1 = :p1, arg2 = :p2  RETURNING arg1 "
     );
   }
 
@@ -148,6 +229,7 @@ mod update_tests {
       num_of_rows: None,
       include: None,
       group_by: None,
+      ..Default::default()
     };
 
     let parsed_query = ParsedQuery {
@@ -160,6 +242,7 @@ mod update_tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         },
         ParsedField {
@@ -172,7 +255,7 @@ mod update_tests {
     let result = one(&parsed_query);
     assert!(result.is_ok());
 
-    let generated_sql = result.unwrap();
-    assert_eq!(generated_sql, "UPDATE my_schema.my_table SET arg1 = 'value1'   ");
+    let generated_sql = result.unwrap().sql;
+    assert_eq!(generated_sql, "UPDATE my_schema.my_table SET arg1 = :p1   ");
   }
 }
@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod upsert_tests {
+  use crate::{
+    engine::builder::{
+      error::BuilderError,
+      parameters::Parameter,
+      upsert::{many, one},
+    },
+    parser::parsed::{FieldName, ParsedArgument, ParsedField, ParsedQuery, ParsedValue, QueryOptions},
+  };
+
+  fn argument(name: &str, value: Option<&str>) -> ParsedArgument {
+    ParsedArgument {
+      name: name.to_string(),
+      value: value.map(|value| ParsedValue::String(value.to_string())),
+      filter: None,
+      update: None,
+      aggregate: None,
+    }
+  }
+
+  fn options(num_of_rows: Option<usize>) -> QueryOptions {
+    QueryOptions {
+      order_by: None,
+      limit: None,
+      offset: None,
+      num_of_rows,
+      include: None,
+      group_by: None,
+      cursor: None,
+      take: None,
+      skip: None,
+      paginate: false,
+      page_cursor: None,
+      time_bucket: None,
+    }
+  }
+
+  #[test]
+  fn test_one() {
+    let parsed_query = ParsedQuery {
+      table: "my_table".to_string(),
+      schema: "my_schema".to_string(),
+      options: options(None),
+      fields: vec![
+        ParsedField {
+          name: FieldName::Data,
+          arguments: vec![argument("email", Some("a@b.c")), argument("name", Some("Ann"))],
+        },
+        ParsedField {
+          name: FieldName::Conflict,
+          arguments: vec![argument("email", None)],
+        },
+        ParsedField {
+          name: FieldName::Set,
+          arguments: vec![argument("name", None), argument("status", Some("active"))],
+        },
+      ],
+    };
+
+    let query = one(&parsed_query).unwrap();
+    assert_eq!(
+      query.sql,
+      "INSERT INTO my_schema.my_table (email, name) VALUES (:p1, :p2) ON CONFLICT (email) DO UPDATE SET name = EXCLUDED.name, status = :p3 RETURNING * "
+    );
+    assert_eq!(
+      query.parameters[2],
+      Parameter {
+        name: "p3".to_string(),
+        value: ParsedValue::String("active".to_string()),
+      }
+    );
+  }
+
+  #[test]
+  fn test_one_do_nothing() {
+    let parsed_query = ParsedQuery {
+      table: "my_table".to_string(),
+      schema: "my_schema".to_string(),
+      options: options(None),
+      fields: vec![
+        ParsedField {
+          name: FieldName::Data,
+          arguments: vec![argument("email", Some("a@b.c"))],
+        },
+        ParsedField {
+          name: FieldName::Conflict,
+          arguments: vec![argument("email", None)],
+        },
+        ParsedField {
+          name: FieldName::NoReturn,
+          arguments: vec![],
+        },
+      ],
+    };
+
+    let result = one(&parsed_query);
+    assert_eq!(
+      result.unwrap().sql,
+      "INSERT INTO my_schema.my_table (email) VALUES (:p1) ON CONFLICT (email) DO NOTHING  "
+    );
+  }
+
+  #[test]
+  fn test_one_update_without_conflict_target() {
+    let parsed_query = ParsedQuery {
+      table: "my_table".to_string(),
+      schema: "my_schema".to_string(),
+      options: options(None),
+      fields: vec![
+        ParsedField {
+          name: FieldName::Data,
+          arguments: vec![argument("email", Some("a@b.c"))],
+        },
+        ParsedField {
+          name: FieldName::Set,
+          arguments: vec![argument("email", None)],
+        },
+      ],
+    };
+
+    assert_eq!(
+      one(&parsed_query),
+      Err(BuilderError::InvalidStatement("Invalid statement."))
+    );
+  }
+
+  #[test]
+  fn test_many() {
+    let parsed_query = ParsedQuery {
+      table: "my_table".to_string(),
+      schema: "my_schema".to_string(),
+      options: options(Some(2)),
+      fields: vec![
+        ParsedField {
+          name: FieldName::Data,
+          arguments: vec![
+            argument("email", Some("a@b.c")),
+            argument("name", Some("Ann")),
+            argument("email", Some("d@e.f")),
+            argument("name", Some("Bob")),
+          ],
+        },
+        ParsedField {
+          name: FieldName::Conflict,
+          arguments: vec![argument("email", None)],
+        },
+        ParsedField {
+          name: FieldName::Set,
+          arguments: vec![argument("name", None)],
+        },
+        ParsedField {
+          name: FieldName::Return,
+          arguments: vec![argument("id", None)],
+        },
+      ],
+    };
+
+    let query = many(&parsed_query).unwrap();
+    assert_eq!(
+      query.sql,
+      "INSERT INTO my_schema.my_table (email, name)\nVALUES\n(:p1, :p2),\n(:p3, :p4) ON CONFLICT (email) DO UPDATE SET name = EXCLUDED.name RETURNING id "
+    );
+    assert_eq!(query.parameters.len(), 4);
+  }
+}
@@ -1,20 +1,22 @@
 use crate::{
-  engine::builder::{error::BuilderError, builder::QueryBuilder},
+  engine::builder::{builder::QueryBuilder, error::BuilderError, parameters::BuiltQuery},
   parser::parsed::{self, FieldName},
 };
 
-pub fn one(query_schema: &parsed::ParsedQuery) -> Result<String, BuilderError> {
-  let builder = QueryBuilder::new(&query_schema)
+pub fn one(query_schema: &parsed::ParsedQuery) -> Result<BuiltQuery, BuilderError> {
+  let mut builder = QueryBuilder::new(query_schema);
+  let query = builder
     .order_fields(vec![FieldName::From, FieldName::Set, FieldName::Where, FieldName::Return])
     .build_ordered()?;
 
-  Ok(format!("UPDATE {}", builder))
+  Ok(builder.finish(format!("UPDATE {}", query)))
 }
 
-pub fn many(query_schema: &parsed::ParsedQuery) -> Result<String, BuilderError> {
-  let builder = QueryBuilder::new(&query_schema)
+pub fn many(query_schema: &parsed::ParsedQuery) -> Result<BuiltQuery, BuilderError> {
+  let mut builder = QueryBuilder::new(query_schema);
+  let query = builder
     .order_fields(vec![FieldName::From, FieldName::Set, FieldName::Where, FieldName::Return])
     .build_ordered()?;
 
-  Ok(format!("UPDATE {}", builder))
+  Ok(builder.finish(format!("UPDATE {}", query)))
 }
\ No newline at end of file
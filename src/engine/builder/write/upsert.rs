@@ -0,0 +1,22 @@
+use crate::{
+  engine::builder::{builder::QueryBuilder, error::BuilderError, parameters::BuiltQuery},
+  parser::parsed::{self, FieldName},
+};
+
+pub fn one(query_schema: &parsed::ParsedQuery) -> Result<BuiltQuery, BuilderError> {
+  let mut builder = QueryBuilder::new(query_schema);
+  let query = builder
+    .order_fields(vec![FieldName::From, FieldName::Data, FieldName::Conflict, FieldName::Return])
+    .build_ordered()?;
+
+  Ok(builder.finish(format!("INSERT INTO {}", query)))
+}
+
+pub fn many(query_schema: &parsed::ParsedQuery) -> Result<BuiltQuery, BuilderError> {
+  let mut builder = QueryBuilder::new(query_schema);
+  let query = builder
+    .order_fields(vec![FieldName::From, FieldName::Data, FieldName::Conflict, FieldName::Return])
+    .build_ordered()?;
+
+  Ok(builder.finish(format!("INSERT INTO {}", query)))
+}
@@ -0,0 +1,95 @@
+use super::{connect, error::RuntimeError, Connection, ConnectionOptions};
+use crate::parser::models::ModelRegistry;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{OnceCell, RwLock};
+
+/// Connections by client name. The map is only locked to look up or insert the slot of a client;
+/// connecting happens on the slot itself, so concurrent calls never connect the same client twice
+/// and a slow connect doesn't hold up queries on other clients.
+#[derive(Default)]
+pub struct Clients {
+  connections: RwLock<HashMap<String, Arc<OnceCell<Connection>>>>,
+}
+
+impl Clients {
+  /// Connects a new client, failing when one with the same name is already connected.
+  pub async fn connect(&self, name: &str, options: ConnectionOptions) -> Result<Connection, RuntimeError> {
+    let slot = self.slot(name).await;
+
+    let mut connected = false;
+    let connection = slot
+      .get_or_try_init(|| async {
+        connected = true;
+        connect(options).await
+      })
+      .await?;
+
+    match connected {
+      true => Ok(connection.clone()),
+      false => Err(RuntimeError::Error(format!(
+        "Client {} is already connected, disconnect it first.",
+        name
+      ))),
+    }
+  }
+
+  /// Returns the client, waiting for it when it is still connecting.
+  pub async fn get(&self, name: &str) -> Result<Connection, RuntimeError> {
+    let slot = match self.connections.read().await.get(name) {
+      Some(slot) => slot.clone(),
+      None => return Err(not_connected(name)),
+    };
+
+    let connection = slot.get_or_try_init(|| async { Err(not_connected(name)) }).await?;
+
+    Ok(connection.clone())
+  }
+
+  /// Returns the client, connecting it with `options` when it isn't connected yet.
+  pub async fn get_or_connect(
+    &self,
+    name: &str,
+    options: impl FnOnce() -> ConnectionOptions,
+  ) -> Result<Connection, RuntimeError> {
+    let slot = self.slot(name).await;
+    let connection = slot.get_or_try_init(|| connect(options())).await?;
+
+    Ok(connection.clone())
+  }
+
+  /// Removes the client. Statements already running keep their connection until they finish.
+  pub async fn disconnect(&self, name: &str) -> Result<(), RuntimeError> {
+    match self.connections.write().await.remove(name) {
+      Some(slot) if slot.initialized() => Ok(()),
+      _ => Err(not_connected(name)),
+    }
+  }
+
+  pub async fn set_models(&self, name: &str, models: ModelRegistry) -> Result<(), RuntimeError> {
+    let mut connections = self.connections.write().await;
+
+    let mut connection = match connections.get(name).and_then(|slot| slot.get()) {
+      Some(connection) => connection.clone(),
+      None => return Err(not_connected(name)),
+    };
+
+    connection.models = Some(Arc::new(models));
+    connections.insert(name.to_string(), Arc::new(OnceCell::new_with(Some(connection))));
+
+    Ok(())
+  }
+
+  /// Slot of the client, added empty when there is none yet. A slot whose connect failed stays
+  /// empty, so the next connect tries again.
+  async fn slot(&self, name: &str) -> Arc<OnceCell<Connection>> {
+    if let Some(slot) = self.connections.read().await.get(name) {
+      return slot.clone();
+    }
+
+    self.connections.write().await.entry(name.to_string()).or_default().clone()
+  }
+}
+
+fn not_connected(name: &str) -> RuntimeError {
+  RuntimeError::Error(format!("Client {} is not connected.", name))
+}
@@ -0,0 +1,281 @@
+use super::{
+  driver::Driver,
+  error::RuntimeError,
+  parameters::to_sql_parameters,
+  records::{self, TypedRecords, TypedValue},
+  ConnectionOptions,
+};
+use crate::engine::builder::parameters::BuiltQuery;
+use async_trait::async_trait;
+use aws_config::{meta::region::RegionProviderChain, SdkConfig};
+use aws_sdk_rdsdata::{
+  client::fluent_builders::ExecuteStatement,
+  model::{ArrayValue, ColumnMetadata, Field, RecordsFormatType, SqlParameter},
+  output::ExecuteStatementOutput,
+  types::SdkError,
+  Client, Credentials, Region,
+};
+use std::fmt::Display;
+
+/// Loads the AWS configuration from the region, endpoint and credentials of the options, falling
+/// back to the environment for whatever they leave out.
+pub async fn sdk_config(options: &ConnectionOptions) -> Result<SdkConfig, RuntimeError> {
+  let region_provider = RegionProviderChain::first_try(options.region.clone().map(Region::new))
+    .or_default_provider()
+    .or_else(Region::new("eu-central-1"));
+
+  let mut loader = aws_config::from_env().region(region_provider);
+
+  if let Some(endpoint) = &options.endpoint {
+    loader = loader.endpoint_url(endpoint);
+  }
+
+  if let Some(credentials) = credentials(options)? {
+    loader = loader.credentials_provider(credentials);
+  }
+
+  Ok(loader.load().await)
+}
+
+/// Static credentials from the options, `None` to use the default AWS credentials chain. Setting
+/// only one of the access key id and the secret access key is an error rather than silently
+/// falling back to the chain.
+pub fn credentials(options: &ConnectionOptions) -> Result<Option<Credentials>, RuntimeError> {
+  match (&options.access_key_id, &options.secret_access_key) {
+    (Some(access_key_id), Some(secret_access_key)) => Ok(Some(Credentials::new(
+      access_key_id,
+      secret_access_key,
+      options.session_token.clone(),
+      None,
+      "ConnectionOptions",
+    ))),
+    (None, None) => Ok(None),
+    _ => Err(RuntimeError::InvalidCredentials(
+      "accessKeyId and secretAccessKey must be set together.".to_string(),
+    )),
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct DataApiDriver {
+  client: Client,
+  resource_arn: String,
+  secret_arn: String,
+  database: String,
+}
+
+impl DataApiDriver {
+  pub async fn connect(
+    resource_arn: String,
+    secret_arn: String,
+    database: String,
+    options: &ConnectionOptions,
+  ) -> Result<Self, RuntimeError> {
+    let shared_config = sdk_config(options).await?;
+
+    Ok(Self {
+      client: Client::new(&shared_config),
+      resource_arn,
+      secret_arn,
+      database,
+    })
+  }
+
+  pub fn statement(&self) -> ExecuteStatement {
+    self
+      .client
+      .execute_statement()
+      .resource_arn(&self.resource_arn)
+      .database(&self.database)
+      .secret_arn(&self.secret_arn)
+  }
+
+  pub async fn execute_statement(
+    &self,
+    query: String,
+    parameters: Vec<SqlParameter>,
+    transaction_id: Option<String>,
+  ) -> Result<ExecuteStatementOutput, RuntimeError> {
+    let st = self.statement().format_records_as(RecordsFormatType::Json);
+
+    send(st, query, parameters, transaction_id).await
+  }
+
+  /// Executes the statement asking for the column metadata instead of `formattedRecords`, so the
+  /// records can be decoded by the types of their columns.
+  pub async fn execute_statement_with_metadata(
+    &self,
+    query: String,
+    parameters: Vec<SqlParameter>,
+    transaction_id: Option<String>,
+  ) -> Result<ExecuteStatementOutput, RuntimeError> {
+    let st = self.statement().include_result_metadata(true);
+
+    send(st, query, parameters, transaction_id).await
+  }
+}
+
+async fn send(
+  st: ExecuteStatement,
+  query: String,
+  parameters: Vec<SqlParameter>,
+  transaction_id: Option<String>,
+) -> Result<ExecuteStatementOutput, RuntimeError> {
+  let st = st.sql(query).set_parameters(Some(parameters)).set_transaction_id(transaction_id);
+
+  match st.send().await {
+    Ok(result) => Ok(result),
+    Err(error) => Err(to_runtime_error(error)),
+  }
+}
+
+#[async_trait]
+impl Driver for DataApiDriver {
+  async fn execute(&self, query: &BuiltQuery, transaction_id: Option<String>) -> Result<Option<String>, RuntimeError> {
+    let parameters = to_sql_parameters(&query.parameters)?;
+    let result = self.execute_statement(query.sql.clone(), parameters, transaction_id).await?;
+
+    Ok(result.formatted_records().map(|formatted_records| formatted_records.to_string()))
+  }
+
+  async fn execute_typed(
+    &self,
+    query: &BuiltQuery,
+    transaction_id: Option<String>,
+  ) -> Result<Option<TypedRecords>, RuntimeError> {
+    let parameters = to_sql_parameters(&query.parameters)?;
+    let result = self
+      .execute_statement_with_metadata(query.sql.clone(), parameters, transaction_id)
+      .await?;
+
+    // Statements that return no rows come back without column metadata.
+    let metadata = match result.column_metadata() {
+      Some(metadata) if !metadata.is_empty() => metadata,
+      _ => return Ok(None),
+    };
+
+    let rows = result
+      .records()
+      .unwrap_or_default()
+      .iter()
+      .map(|record| {
+        record
+          .iter()
+          .zip(metadata)
+          .map(|(field, column)| decode_field(field, column))
+          .collect::<Result<Vec<_>, _>>()
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Some(TypedRecords {
+      columns: metadata.iter().map(|column| label(column).to_string()).collect(),
+      types: metadata
+        .iter()
+        .map(|column| column.type_name().unwrap_or_default().to_string())
+        .collect(),
+      rows,
+    }))
+  }
+
+  async fn begin_transaction(&self) -> Result<String, RuntimeError> {
+    let st = self
+      .client
+      .begin_transaction()
+      .resource_arn(&self.resource_arn)
+      .secret_arn(&self.secret_arn)
+      .database(&self.database);
+
+    match st.send().await {
+      Ok(result) => match result.transaction_id() {
+        Some(transaction_id) => Ok(transaction_id.to_string()),
+        None => Err(RuntimeError::Error("Transaction id is missing.".to_string())),
+      },
+      Err(error) => Err(to_runtime_error(error)),
+    }
+  }
+
+  async fn commit_transaction(&self, transaction_id: String) -> Result<(), RuntimeError> {
+    let st = self
+      .client
+      .commit_transaction()
+      .resource_arn(&self.resource_arn)
+      .secret_arn(&self.secret_arn)
+      .transaction_id(transaction_id);
+
+    match st.send().await {
+      Ok(_) => Ok(()),
+      Err(error) => Err(to_runtime_error(error)),
+    }
+  }
+
+  async fn rollback_transaction(&self, transaction_id: String) -> Result<(), RuntimeError> {
+    let st = self
+      .client
+      .rollback_transaction()
+      .resource_arn(&self.resource_arn)
+      .secret_arn(&self.secret_arn)
+      .transaction_id(transaction_id);
+
+    match st.send().await {
+      Ok(_) => Ok(()),
+      Err(error) => Err(to_runtime_error(error)),
+    }
+  }
+}
+
+fn label(column: &ColumnMetadata) -> &str {
+  column.label().or_else(|| column.name()).unwrap_or_default()
+}
+
+/// Decodes a field by the type of its column. The Data API already sends integers, floats, booleans
+/// and binary data typed; everything it sends as a string is decoded from its text.
+fn decode_field(field: &Field, column: &ColumnMetadata) -> Result<TypedValue, RuntimeError> {
+  let type_name = column.type_name().unwrap_or_default();
+
+  match field {
+    Field::IsNull(_) => Ok(TypedValue::Null),
+    Field::BooleanValue(boolean) => Ok(TypedValue::Boolean(*boolean)),
+    Field::LongValue(long) => Ok(decode_long(type_name, *long)),
+    Field::DoubleValue(double) => Ok(TypedValue::Number(*double)),
+    Field::StringValue(text) => Ok(records::decode_text(type_name, text)),
+    Field::BlobValue(blob) => Ok(TypedValue::Bytes(blob.as_ref().to_vec())),
+    Field::ArrayValue(array) => decode_array(array, type_name.trim_start_matches('_'))
+      .ok_or_else(|| records::invalid_field(label(column), type_name)),
+    _ => Err(records::invalid_field(label(column), type_name)),
+  }
+}
+
+/// Only `int8` columns can hold integers JS numbers can't represent exactly.
+fn decode_long(type_name: &str, long: i64) -> TypedValue {
+  match type_name {
+    "int8" | "bigserial" => TypedValue::BigInt(long),
+    _ => TypedValue::Number(long as f64),
+  }
+}
+
+fn decode_array(array: &ArrayValue, element_type: &str) -> Option<TypedValue> {
+  let values = match array {
+    ArrayValue::BooleanValues(values) => values.iter().map(|value| TypedValue::Boolean(*value)).collect(),
+    ArrayValue::LongValues(values) => values.iter().map(|value| decode_long(element_type, *value)).collect(),
+    ArrayValue::DoubleValues(values) => values.iter().map(|value| TypedValue::Number(*value)).collect(),
+    ArrayValue::StringValues(values) => values
+      .iter()
+      .map(|value| records::decode_text(element_type, value))
+      .collect(),
+    ArrayValue::ArrayValues(arrays) => arrays
+      .iter()
+      .map(|array| decode_array(array, element_type))
+      .collect::<Option<_>>()?,
+    _ => return None,
+  };
+
+  Some(TypedValue::Array(values))
+}
+
+fn to_runtime_error<E: Display>(error: SdkError<E>) -> RuntimeError {
+  if let SdkError::ServiceError(service_error) = error {
+    RuntimeError::Error(format!("{}", service_error.err()))
+  } else {
+    RuntimeError::Error("Unknown error occurred".to_string())
+  }
+}
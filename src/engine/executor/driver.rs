@@ -0,0 +1,119 @@
+use super::{
+  error::RuntimeError,
+  introspection::{
+    self,
+    schema::{DatabaseSchema, SchemaRecords},
+  },
+  records::TypedRecords,
+};
+use crate::{
+  engine::builder::parameters::{BuiltQuery, Parameter},
+  parser::parsed::ParsedValue,
+};
+use async_trait::async_trait;
+
+/// A database backend the engine can run built queries against.
+///
+/// Every driver returns rows as a JSON array string, the same shape the Data API produces for
+/// `formattedRecords`, so the rest of the engine does not care which backend it talks to.
+#[async_trait]
+pub trait Driver: Send + Sync {
+  async fn execute(&self, query: &BuiltQuery, transaction_id: Option<String>) -> Result<Option<String>, RuntimeError>;
+
+  /// Executes the query and returns its rows decoded by the types of their columns, `None` when
+  /// it returns no rows.
+  async fn execute_typed(&self, query: &BuiltQuery, transaction_id: Option<String>) -> Result<Option<TypedRecords>, RuntimeError>;
+
+  async fn begin_transaction(&self) -> Result<String, RuntimeError>;
+
+  async fn commit_transaction(&self, transaction_id: String) -> Result<(), RuntimeError>;
+
+  async fn rollback_transaction(&self, transaction_id: String) -> Result<(), RuntimeError>;
+
+  /// Executes every query in order inside a single transaction. The first failing query rolls
+  /// the whole transaction back and its index is reported in the returned error, together with
+  /// the rollback's own error if that fails as well.
+  async fn execute_batch(&self, queries: &[BuiltQuery]) -> Result<Vec<Option<String>>, RuntimeError> {
+    let transaction_id = self.begin_transaction().await?;
+    let mut results = Vec::with_capacity(queries.len());
+
+    for (index, query) in queries.iter().enumerate() {
+      match self.execute(query, Some(transaction_id.clone())).await {
+        Ok(formatted_records) => results.push(formatted_records),
+        Err(err) => {
+          let message = match self.rollback_transaction(transaction_id).await {
+            Ok(_) => err.to_string(),
+            Err(rollback_err) => format!("{} Rolling back failed as well: {}", err, rollback_err),
+          };
+
+          return Err(RuntimeError::StatementFailed(index, message));
+        }
+      }
+    }
+
+    self.commit_transaction(transaction_id).await?;
+
+    Ok(results)
+  }
+
+  async fn introspect(&self, schema: &str) -> Result<String, RuntimeError> {
+    let query = BuiltQuery::new(
+      introspection::SCHEMA_QUERY.to_string(),
+      vec![Parameter {
+        name: "p1".to_string(),
+        value: ParsedValue::String(schema.to_string()),
+      }],
+    );
+
+    Ok(self.execute(&query, None).await?.unwrap_or_default())
+  }
+
+  /// Introspects every schema of `schemas` into tables, views and enums.
+  async fn database_schema(&self, schemas: &[String]) -> Result<DatabaseSchema, RuntimeError> {
+    let parameters = vec![Parameter {
+      name: "p1".to_string(),
+      value: ParsedValue::Array(schemas.iter().cloned().map(ParsedValue::String).collect()),
+    }];
+
+    let mut records = Vec::with_capacity(5);
+    for sql in [
+      introspection::relations_query(),
+      introspection::columns_query(),
+      introspection::constraints_query(),
+      introspection::indexes_query(),
+      introspection::enums_query(),
+    ] {
+      let query = BuiltQuery::new(sql, parameters.clone());
+      records.push(self.execute(&query, None).await?.unwrap_or_default());
+    }
+
+    DatabaseSchema::from_records(
+      schemas.to_vec(),
+      SchemaRecords {
+        relations: &records[0],
+        columns: &records[1],
+        constraints: &records[2],
+        indexes: &records[3],
+        enums: &records[4],
+      },
+    )
+  }
+
+  async fn foreign_keys(&self, schema: &str, table: &str) -> Result<String, RuntimeError> {
+    let query = BuiltQuery::new(
+      introspection::FOREIGN_KEYS_QUERY.to_string(),
+      vec![
+        Parameter {
+          name: "p1".to_string(),
+          value: ParsedValue::String(schema.to_string()),
+        },
+        Parameter {
+          name: "p2".to_string(),
+          value: ParsedValue::String(table.to_string()),
+        },
+      ],
+    );
+
+    Ok(self.execute(&query, None).await?.unwrap_or_default())
+  }
+}
@@ -10,4 +10,7 @@ pub enum RuntimeError {
 
   #[error("{:?}", _0)]
   MissingVariable(String),
+
+  #[error("Statement at index {} failed: {}", _0, _1)]
+  StatementFailed(usize, String),
 }
\ No newline at end of file
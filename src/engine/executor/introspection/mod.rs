@@ -0,0 +1,167 @@
+pub mod schema;
+mod tests;
+
+/// Every column of a foreign key is paired with the referenced column at the same position of the
+/// referenced key, so composite keys come back as one row per column pair.
+pub const FOREIGN_KEYS_QUERY: &str = "SELECT
+    tc.table_schema, 
+    tc.constraint_name, 
+    tc.table_name, 
+    kcu.column_name, 
+    kcu.ordinal_position,
+    fkcu.table_schema AS foreign_table_schema,
+    fkcu.table_name AS foreign_table_name,
+    fkcu.column_name AS foreign_column_name 
+FROM information_schema.table_constraints AS tc 
+JOIN information_schema.key_column_usage AS kcu
+    ON tc.constraint_name = kcu.constraint_name
+    AND tc.table_schema = kcu.table_schema
+    AND tc.table_name = kcu.table_name
+JOIN information_schema.referential_constraints AS rc
+    ON rc.constraint_name = tc.constraint_name
+    AND rc.constraint_schema = tc.table_schema
+JOIN information_schema.key_column_usage AS fkcu
+    ON fkcu.constraint_name = rc.unique_constraint_name
+    AND fkcu.constraint_schema = rc.unique_constraint_schema
+    AND fkcu.ordinal_position = kcu.position_in_unique_constraint
+WHERE tc.constraint_type = 'FOREIGN KEY'
+    AND tc.table_schema = :p1
+    AND tc.table_name = :p2
+ORDER BY tc.constraint_name, kcu.ordinal_position
+  ";
+
+pub const SCHEMA_QUERY: &str = "SELECT tabl.table_name, columns.column_name, columns.data_type, columns.is_nullable,
+      CASE
+        WHEN primary_keys.column_name IS NOT NULL THEN 'YES'
+        ELSE 'NO'
+      END AS is_primary_key
+    FROM
+      (SELECT table_name FROM information_schema.tables WHERE table_schema = :p1) AS tabl
+    INNER JOIN 
+      (SELECT
+        table_name,
+        column_name,
+        data_type,
+        is_nullable
+      FROM information_schema.columns) AS columns
+    ON tabl.table_name = columns.table_name
+    LEFT JOIN (
+      SELECT tc.table_name, kcu.column_name
+      FROM information_schema.table_constraints tc
+      JOIN information_schema.key_column_usage kcu
+        ON tc.constraint_name = kcu.constraint_name
+      AND tc.table_schema = kcu.table_schema
+      AND tc.table_name = kcu.table_name
+      WHERE tc.constraint_type = 'PRIMARY KEY'
+    ) AS primary_keys
+    ON tabl.table_name = primary_keys.table_name
+      AND columns.column_name = primary_keys.column_name
+    GROUP BY
+      tabl.table_name,
+      columns.column_name,
+      columns.data_type,
+      columns.is_nullable,
+      is_primary_key
+  ";
+
+/// Schemas are bound as one text array, sent as an array literal like every array parameter.
+const IN_SCHEMAS: &str = "= ANY(CAST(:p1 AS text[]))";
+
+/// Tables, views and materialized views of the introspected schemas.
+pub fn relations_query() -> String {
+  format!(
+    "SELECT n.nspname::text AS table_schema, c.relname::text AS table_name, c.relkind::text AS kind,
+      obj_description(c.oid, 'pg_class') AS comment,
+      CASE WHEN c.relkind IN ('v', 'm') THEN pg_get_viewdef(c.oid, true) END AS definition
+    FROM pg_class c
+    JOIN pg_namespace n ON n.oid = c.relnamespace
+    WHERE n.nspname {} AND c.relkind IN ('r', 'p', 'f', 'v', 'm')
+    ORDER BY n.nspname, c.relname",
+    IN_SCHEMAS
+  )
+}
+
+/// Columns of every relation in their table order. The identity kind is `a` for `ALWAYS` and `d`
+/// for `BY DEFAULT`, empty for columns that aren't identities.
+pub fn columns_query() -> String {
+  format!(
+    "SELECT n.nspname::text AS table_schema, c.relname::text AS table_name, a.attname::text AS column_name,
+      format_type(a.atttypid, a.atttypmod) AS data_type, t.typname::text AS udt_name,
+      tn.nspname::text AS udt_schema, NOT a.attnotnull AS is_nullable,
+      pg_get_expr(d.adbin, d.adrelid) AS column_default, a.attidentity::text AS identity,
+      col_description(c.oid, a.attnum) AS comment
+    FROM pg_attribute a
+    JOIN pg_class c ON c.oid = a.attrelid
+    JOIN pg_namespace n ON n.oid = c.relnamespace
+    JOIN pg_type t ON t.oid = a.atttypid
+    JOIN pg_namespace tn ON tn.oid = t.typnamespace
+    LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+    WHERE n.nspname {} AND c.relkind IN ('r', 'p', 'f', 'v', 'm') AND a.attnum > 0 AND NOT a.attisdropped
+    ORDER BY n.nspname, c.relname, a.attnum",
+    IN_SCHEMAS
+  )
+}
+
+/// Primary key, unique, check and foreign key constraints. Column lists are returned as JSON text
+/// and actions as the single letter codes of `pg_constraint`.
+pub fn constraints_query() -> String {
+  format!(
+    "SELECT n.nspname::text AS table_schema, c.relname::text AS table_name, con.conname::text AS constraint_name,
+      con.contype::text AS constraint_type,
+      array_to_json(ARRAY(
+        SELECT a.attname::text FROM unnest(con.conkey) WITH ORDINALITY AS k(attnum, position)
+        JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum ORDER BY k.position
+      ))::text AS columns,
+      fn.nspname::text AS foreign_table_schema, fc.relname::text AS foreign_table_name,
+      array_to_json(ARRAY(
+        SELECT a.attname::text FROM unnest(con.confkey) WITH ORDINALITY AS k(attnum, position)
+        JOIN pg_attribute a ON a.attrelid = con.confrelid AND a.attnum = k.attnum ORDER BY k.position
+      ))::text AS foreign_columns,
+      con.confupdtype::text AS on_update, con.confdeltype::text AS on_delete,
+      pg_get_constraintdef(con.oid, true) AS definition
+    FROM pg_constraint con
+    JOIN pg_class c ON c.oid = con.conrelid
+    JOIN pg_namespace n ON n.oid = c.relnamespace
+    LEFT JOIN pg_class fc ON fc.oid = con.confrelid
+    LEFT JOIN pg_namespace fn ON fn.oid = fc.relnamespace
+    WHERE n.nspname {} AND con.contype IN ('p', 'u', 'c', 'f')
+    ORDER BY n.nspname, c.relname, con.conname",
+    IN_SCHEMAS
+  )
+}
+
+/// Indexes with their key columns or expressions as JSON text.
+pub fn indexes_query() -> String {
+  format!(
+    "SELECT n.nspname::text AS table_schema, t.relname::text AS table_name, i.relname::text AS index_name,
+      ix.indisunique AS is_unique, ix.indisprimary AS is_primary, am.amname::text AS method,
+      array_to_json(ARRAY(
+        SELECT pg_get_indexdef(ix.indexrelid, k.position, true)
+        FROM generate_series(1, ix.indnkeyatts::int) AS k(position) ORDER BY k.position
+      ))::text AS columns,
+      pg_get_expr(ix.indpred, ix.indrelid, true) AS predicate, pg_get_indexdef(ix.indexrelid) AS definition
+    FROM pg_index ix
+    JOIN pg_class i ON i.oid = ix.indexrelid
+    JOIN pg_class t ON t.oid = ix.indrelid
+    JOIN pg_namespace n ON n.oid = t.relnamespace
+    JOIN pg_am am ON am.oid = i.relam
+    WHERE n.nspname {}
+    ORDER BY n.nspname, t.relname, i.relname",
+    IN_SCHEMAS
+  )
+}
+
+/// Enum types with their labels in sort order as JSON text.
+pub fn enums_query() -> String {
+  format!(
+    "SELECT n.nspname::text AS enum_schema, t.typname::text AS enum_name,
+      array_to_json(array_agg(e.enumlabel::text ORDER BY e.enumsortorder))::text AS enum_values
+    FROM pg_type t
+    JOIN pg_enum e ON e.enumtypid = t.oid
+    JOIN pg_namespace n ON n.oid = t.typnamespace
+    WHERE n.nspname {}
+    GROUP BY n.nspname, t.typname
+    ORDER BY n.nspname, t.typname",
+    IN_SCHEMAS
+  )
+}
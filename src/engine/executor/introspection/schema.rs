@@ -0,0 +1,291 @@
+use super::super::error::RuntimeError;
+use napi_derive::napi;
+use serde_json::Value;
+
+/// Tables, views and enums of the introspected schemas.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DatabaseSchema {
+  pub schemas: Vec<String>,
+  pub tables: Vec<TableSchema>,
+  pub views: Vec<ViewSchema>,
+  pub enums: Vec<EnumSchema>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TableSchema {
+  pub schema: String,
+  pub name: String,
+  pub comment: Option<String>,
+  pub columns: Vec<ColumnSchema>,
+  pub primary_key: Option<KeyConstraint>,
+  pub unique_constraints: Vec<KeyConstraint>,
+  pub check_constraints: Vec<CheckConstraint>,
+  pub indexes: Vec<IndexSchema>,
+  pub foreign_keys: Vec<ForeignKeySchema>,
+}
+
+/// A view, or a materialized view when `materialized` is set.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ViewSchema {
+  pub schema: String,
+  pub name: String,
+  pub materialized: bool,
+  pub comment: Option<String>,
+  pub definition: Option<String>,
+  pub columns: Vec<ColumnSchema>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ColumnSchema {
+  pub name: String,
+  /// Postgres type including modifiers, e.g. `character varying(255)` or `integer[]`.
+  pub data_type: String,
+  /// Name of the underlying type, e.g. `varchar`, `_int4` for arrays or the name of an enum.
+  pub udt_name: String,
+  pub udt_schema: String,
+  pub nullable: bool,
+  /// Default expression as Postgres prints it, e.g. `now()`.
+  pub default: Option<String>,
+  /// `ALWAYS` or `BY DEFAULT` for identity columns.
+  pub identity: Option<String>,
+  /// Set for columns defaulting to the next value of a sequence, as `serial` columns do.
+  pub serial: bool,
+  pub comment: Option<String>,
+}
+
+/// A primary key or unique constraint.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct KeyConstraint {
+  pub name: String,
+  pub columns: Vec<String>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CheckConstraint {
+  pub name: String,
+  /// The constraint as Postgres prints it, e.g. `CHECK (price > 0)`.
+  pub definition: String,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct IndexSchema {
+  pub name: String,
+  /// Key columns, or the expressions of expression indexes.
+  pub columns: Vec<String>,
+  pub unique: bool,
+  pub primary: bool,
+  /// Access method, e.g. `btree` or `gin`.
+  pub method: String,
+  /// Condition of a partial index.
+  pub predicate: Option<String>,
+  pub definition: String,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ForeignKeySchema {
+  pub name: String,
+  pub columns: Vec<String>,
+  pub foreign_schema: String,
+  pub foreign_table: String,
+  pub foreign_columns: Vec<String>,
+  /// `NO ACTION`, `RESTRICT`, `CASCADE`, `SET NULL` or `SET DEFAULT`.
+  pub on_update: String,
+  pub on_delete: String,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EnumSchema {
+  pub schema: String,
+  pub name: String,
+  pub values: Vec<String>,
+}
+
+/// Rows returned by each introspection query, as the JSON array strings drivers return.
+pub struct SchemaRecords<'a> {
+  pub relations: &'a str,
+  pub columns: &'a str,
+  pub constraints: &'a str,
+  pub indexes: &'a str,
+  pub enums: &'a str,
+}
+
+impl DatabaseSchema {
+  /// Puts the rows of the introspection queries together, keeping the order they were queried in.
+  pub fn from_records(schemas: Vec<String>, records: SchemaRecords) -> Result<Self, RuntimeError> {
+    let columns = rows(records.columns)?;
+    let constraints = rows(records.constraints)?;
+    let indexes = rows(records.indexes)?;
+
+    let mut database = DatabaseSchema {
+      schemas,
+      ..Default::default()
+    };
+
+    for relation in rows(records.relations)? {
+      let schema = text(&relation, "table_schema");
+      let name = text(&relation, "table_name");
+      let belongs = |row: &&Value| text(row, "table_schema") == schema && text(row, "table_name") == name;
+      let relation_columns = columns.iter().filter(belongs).map(column).collect();
+
+      match text(&relation, "kind").as_str() {
+        "v" | "m" => database.views.push(ViewSchema {
+          materialized: text(&relation, "kind") == "m",
+          comment: optional_text(&relation, "comment"),
+          definition: optional_text(&relation, "definition"),
+          columns: relation_columns,
+          schema,
+          name,
+        }),
+        _ => {
+          let mut table = TableSchema {
+            comment: optional_text(&relation, "comment"),
+            columns: relation_columns,
+            indexes: indexes.iter().filter(belongs).map(index).collect::<Result<_, _>>()?,
+            ..Default::default()
+          };
+
+          for constraint in constraints.iter().filter(belongs) {
+            add_constraint(&mut table, constraint)?;
+          }
+
+          database.tables.push(TableSchema { schema, name, ..table });
+        }
+      }
+    }
+
+    database.enums = rows(records.enums)?
+      .iter()
+      .map(|row| {
+        Ok(EnumSchema {
+          schema: text(row, "enum_schema"),
+          name: text(row, "enum_name"),
+          values: list(row, "enum_values")?,
+        })
+      })
+      .collect::<Result<_, RuntimeError>>()?;
+
+    Ok(database)
+  }
+}
+
+fn add_constraint(table: &mut TableSchema, row: &Value) -> Result<(), RuntimeError> {
+  let name = text(row, "constraint_name");
+
+  match text(row, "constraint_type").as_str() {
+    "p" => {
+      table.primary_key = Some(KeyConstraint {
+        name,
+        columns: list(row, "columns")?,
+      })
+    }
+    "u" => table.unique_constraints.push(KeyConstraint {
+      name,
+      columns: list(row, "columns")?,
+    }),
+    "c" => table.check_constraints.push(CheckConstraint {
+      name,
+      definition: text(row, "definition"),
+    }),
+    "f" => table.foreign_keys.push(ForeignKeySchema {
+      name,
+      columns: list(row, "columns")?,
+      foreign_schema: text(row, "foreign_table_schema"),
+      foreign_table: text(row, "foreign_table_name"),
+      foreign_columns: list(row, "foreign_columns")?,
+      on_update: action(&text(row, "on_update")),
+      on_delete: action(&text(row, "on_delete")),
+    }),
+    _ => {}
+  }
+
+  Ok(())
+}
+
+fn column(row: &Value) -> ColumnSchema {
+  let default = optional_text(row, "column_default");
+
+  ColumnSchema {
+    name: text(row, "column_name"),
+    data_type: text(row, "data_type"),
+    udt_name: text(row, "udt_name"),
+    udt_schema: text(row, "udt_schema"),
+    nullable: flag(row, "is_nullable"),
+    serial: default.as_deref().is_some_and(|default| default.starts_with("nextval(")),
+    default,
+    identity: match text(row, "identity").as_str() {
+      "a" => Some("ALWAYS".to_string()),
+      "d" => Some("BY DEFAULT".to_string()),
+      _ => None,
+    },
+    comment: optional_text(row, "comment"),
+  }
+}
+
+fn index(row: &Value) -> Result<IndexSchema, RuntimeError> {
+  Ok(IndexSchema {
+    name: text(row, "index_name"),
+    columns: list(row, "columns")?,
+    unique: flag(row, "is_unique"),
+    primary: flag(row, "is_primary"),
+    method: text(row, "method"),
+    predicate: optional_text(row, "predicate"),
+    definition: text(row, "definition"),
+  })
+}
+
+/// Spells out the referential action codes of `pg_constraint`.
+fn action(code: &str) -> String {
+  match code {
+    "r" => "RESTRICT",
+    "c" => "CASCADE",
+    "n" => "SET NULL",
+    "d" => "SET DEFAULT",
+    _ => "NO ACTION",
+  }
+  .to_string()
+}
+
+fn rows(records: &str) -> Result<Vec<Value>, RuntimeError> {
+  if records.is_empty() {
+    return Ok(vec![]);
+  }
+
+  serde_json::from_str(records).map_err(|err| RuntimeError::Error(err.to_string()))
+}
+
+fn text(row: &Value, column: &str) -> String {
+  optional_text(row, column).unwrap_or_default()
+}
+
+fn optional_text(row: &Value, column: &str) -> Option<String> {
+  row.get(column).and_then(Value::as_str).map(str::to_string)
+}
+
+fn flag(row: &Value, column: &str) -> bool {
+  row.get(column).and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// Reads a list returned as JSON text, or as an array by drivers that decode it themselves.
+fn list(row: &Value, column: &str) -> Result<Vec<String>, RuntimeError> {
+  let value = match row.get(column) {
+    Some(Value::String(text)) => serde_json::from_str(text).map_err(|err| RuntimeError::Error(err.to_string()))?,
+    Some(value) => value.clone(),
+    None => return Ok(vec![]),
+  };
+
+  Ok(
+    value
+      .as_array()
+      .map(|values| values.iter().filter_map(Value::as_str).map(str::to_string).collect())
+      .unwrap_or_default(),
+  )
+}
@@ -0,0 +1,206 @@
+#[cfg(test)]
+mod schema_tests {
+  use crate::engine::executor::introspection::schema::{
+    CheckConstraint, ColumnSchema, DatabaseSchema, EnumSchema, ForeignKeySchema, IndexSchema, KeyConstraint, SchemaRecords,
+    TableSchema, ViewSchema,
+  };
+  use serde_json::json;
+
+  #[test]
+  fn test_from_records() {
+    let relations = json!([
+      { "table_schema": "shop", "table_name": "orders", "kind": "r", "comment": "Placed orders", "definition": null },
+      { "table_schema": "shop", "table_name": "order_totals", "kind": "m", "comment": null, "definition": " SELECT 1;" }
+    ]);
+    let columns = json!([
+      {
+        "table_schema": "shop", "table_name": "orders", "column_name": "id", "data_type": "integer",
+        "udt_name": "int4", "udt_schema": "pg_catalog", "is_nullable": false,
+        "column_default": "nextval('shop.orders_id_seq'::regclass)", "identity": "", "comment": null
+      },
+      {
+        "table_schema": "shop", "table_name": "orders", "column_name": "status", "data_type": "shop.order_status",
+        "udt_name": "order_status", "udt_schema": "shop", "is_nullable": true,
+        "column_default": null, "identity": "", "comment": "Where the order is at"
+      },
+      {
+        "table_schema": "shop", "table_name": "order_totals", "column_name": "total", "data_type": "numeric(10,2)",
+        "udt_name": "numeric", "udt_schema": "pg_catalog", "is_nullable": true,
+        "column_default": null, "identity": "", "comment": null
+      }
+    ]);
+    let constraints = json!([
+      {
+        "table_schema": "shop", "table_name": "orders", "constraint_name": "orders_customer_fkey",
+        "constraint_type": "f", "columns": "[\"tenant_id\",\"customer_id\"]", "foreign_table_schema": "shop",
+        "foreign_table_name": "customers", "foreign_columns": "[\"tenant_id\",\"id\"]",
+        "on_update": "a", "on_delete": "c", "definition": "FOREIGN KEY (tenant_id, customer_id) REFERENCES shop.customers(tenant_id, id) ON DELETE CASCADE"
+      },
+      {
+        "table_schema": "shop", "table_name": "orders", "constraint_name": "orders_pkey", "constraint_type": "p",
+        "columns": "[\"id\"]", "definition": "PRIMARY KEY (id)"
+      },
+      {
+        "table_schema": "shop", "table_name": "orders", "constraint_name": "orders_number_key", "constraint_type": "u",
+        "columns": "[\"number\"]", "definition": "UNIQUE (number)"
+      },
+      {
+        "table_schema": "shop", "table_name": "orders", "constraint_name": "orders_total_check", "constraint_type": "c",
+        "columns": "[\"total\"]", "definition": "CHECK (total >= 0)"
+      }
+    ]);
+    let indexes = json!([
+      {
+        "table_schema": "shop", "table_name": "orders", "index_name": "orders_open_idx", "is_unique": false,
+        "is_primary": false, "method": "btree", "columns": "[\"lower(number)\"]", "predicate": "status = 'open'",
+        "definition": "CREATE INDEX orders_open_idx ON shop.orders USING btree (lower(number)) WHERE status = 'open'"
+      }
+    ]);
+    let enums = json!([
+      { "enum_schema": "shop", "enum_name": "order_status", "enum_values": "[\"open\",\"paid\",\"shipped\"]" }
+    ]);
+
+    let database = DatabaseSchema::from_records(
+      vec!["shop".to_string()],
+      SchemaRecords {
+        relations: &relations.to_string(),
+        columns: &columns.to_string(),
+        constraints: &constraints.to_string(),
+        indexes: &indexes.to_string(),
+        enums: &enums.to_string(),
+      },
+    )
+    .unwrap();
+
+    let strings = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+      database,
+      DatabaseSchema {
+        schemas: strings(&["shop"]),
+        tables: vec![TableSchema {
+          schema: "shop".to_string(),
+          name: "orders".to_string(),
+          comment: Some("Placed orders".to_string()),
+          columns: vec![
+            ColumnSchema {
+              name: "id".to_string(),
+              data_type: "integer".to_string(),
+              udt_name: "int4".to_string(),
+              udt_schema: "pg_catalog".to_string(),
+              nullable: false,
+              default: Some("nextval('shop.orders_id_seq'::regclass)".to_string()),
+              identity: None,
+              serial: true,
+              comment: None,
+            },
+            ColumnSchema {
+              name: "status".to_string(),
+              data_type: "shop.order_status".to_string(),
+              udt_name: "order_status".to_string(),
+              udt_schema: "shop".to_string(),
+              nullable: true,
+              default: None,
+              identity: None,
+              serial: false,
+              comment: Some("Where the order is at".to_string()),
+            },
+          ],
+          primary_key: Some(KeyConstraint {
+            name: "orders_pkey".to_string(),
+            columns: strings(&["id"]),
+          }),
+          unique_constraints: vec![KeyConstraint {
+            name: "orders_number_key".to_string(),
+            columns: strings(&["number"]),
+          }],
+          check_constraints: vec![CheckConstraint {
+            name: "orders_total_check".to_string(),
+            definition: "CHECK (total >= 0)".to_string(),
+          }],
+          indexes: vec![IndexSchema {
+            name: "orders_open_idx".to_string(),
+            columns: strings(&["lower(number)"]),
+            unique: false,
+            primary: false,
+            method: "btree".to_string(),
+            predicate: Some("status = 'open'".to_string()),
+            definition: "CREATE INDEX orders_open_idx ON shop.orders USING btree (lower(number)) WHERE status = 'open'"
+              .to_string(),
+          }],
+          foreign_keys: vec![ForeignKeySchema {
+            name: "orders_customer_fkey".to_string(),
+            columns: strings(&["tenant_id", "customer_id"]),
+            foreign_schema: "shop".to_string(),
+            foreign_table: "customers".to_string(),
+            foreign_columns: strings(&["tenant_id", "id"]),
+            on_update: "NO ACTION".to_string(),
+            on_delete: "CASCADE".to_string(),
+          }],
+        }],
+        views: vec![ViewSchema {
+          schema: "shop".to_string(),
+          name: "order_totals".to_string(),
+          materialized: true,
+          comment: None,
+          definition: Some(" SELECT 1;".to_string()),
+          columns: vec![ColumnSchema {
+            name: "total".to_string(),
+            data_type: "numeric(10,2)".to_string(),
+            udt_name: "numeric".to_string(),
+            udt_schema: "pg_catalog".to_string(),
+            nullable: true,
+            default: None,
+            identity: None,
+            serial: false,
+            comment: None,
+          }],
+        }],
+        enums: vec![EnumSchema {
+          schema: "shop".to_string(),
+          name: "order_status".to_string(),
+          values: strings(&["open", "paid", "shipped"]),
+        }],
+      }
+    );
+  }
+
+  #[test]
+  fn test_from_records_with_identity_and_empty_results() {
+    let relations = json!([{ "table_schema": "public", "table_name": "events", "kind": "p" }]);
+    let columns = json!([{
+      "table_schema": "public", "table_name": "events", "column_name": "id", "data_type": "bigint",
+      "udt_name": "int8", "udt_schema": "pg_catalog", "is_nullable": false, "column_default": null, "identity": "d"
+    }]);
+
+    let database = DatabaseSchema::from_records(
+      vec!["public".to_string()],
+      SchemaRecords {
+        relations: &relations.to_string(),
+        columns: &columns.to_string(),
+        constraints: "",
+        indexes: "[]",
+        enums: "",
+      },
+    )
+    .unwrap();
+
+    let table = &database.tables[0];
+    assert_eq!(table.columns[0].identity, Some("BY DEFAULT".to_string()));
+    assert!(!table.columns[0].serial);
+    assert_eq!(table.primary_key, None);
+    assert!(database.views.is_empty() && database.enums.is_empty());
+
+    let invalid = DatabaseSchema::from_records(
+      vec![],
+      SchemaRecords {
+        relations: "not json",
+        columns: "",
+        constraints: "",
+        indexes: "",
+        enums: "",
+      },
+    );
+    assert!(invalid.is_err());
+  }
+}
@@ -1,181 +1,142 @@
+pub mod clients;
+pub mod data_api;
+pub mod driver;
 pub mod error;
+pub mod introspection;
+pub mod parameters;
+pub mod postgres;
+pub mod postgres_tls;
+pub mod postgres_values;
+pub mod records;
+mod tests;
 pub mod utils;
-use self::error::RuntimeError;
-use aws_config::meta::region::RegionProviderChain;
-use aws_sdk_rdsdata::{
-  client::fluent_builders::ExecuteStatement, model::RecordsFormatType, output::ExecuteStatementOutput, types::SdkError, Client,
-  Region,
+use self::{
+  data_api::DataApiDriver, driver::Driver, error::RuntimeError, introspection::schema::DatabaseSchema, postgres::PostgresDriver,
 };
+use crate::parser::models::ModelRegistry;
 use dotenv::dotenv;
 use napi_derive::napi;
+use std::sync::Arc;
 
 #[napi(object)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ConnectionOptions {
-  pub resource_arn: String,
-  pub secret_arn: String,
-  pub database: String,
-  pub region: String,
+  /// `dataApi` (default) or `postgres`.
+  pub driver: Option<String>,
+  pub resource_arn: Option<String>,
+  pub secret_arn: Option<String>,
+  pub database: Option<String>,
+  pub region: Option<String>,
+  /// Overrides the Data API endpoint, e.g. `http://localhost:8080` for a local emulator.
+  pub endpoint: Option<String>,
+  /// Static credentials used instead of the default AWS credentials chain, both or neither must be set.
+  pub access_key_id: Option<String>,
+  pub secret_access_key: Option<String>,
+  pub session_token: Option<String>,
+  /// Connection string used by the `postgres` driver.
+  pub url: Option<String>,
+  /// TLS of the `postgres` driver like libpq's `sslmode`: `disable`, `prefer` or `require` encrypt
+  /// without checking the server's certificate, `verify-full` checks it was issued to the host by a
+  /// trusted authority. Falls back to the `sslmode` of `url`, `prefer` by default.
+  pub ssl_mode: Option<String>,
+  /// PEM file of the certificate authorities `verify-full` trusts instead of the system's, e.g. the
+  /// RDS certificate bundle.
+  pub ssl_root_cert: Option<String>,
+  /// Schema used for introspection, falls back to the `SCHEMA` variable when missing.
+  pub schema: Option<String>,
 }
 
 impl ConnectionOptions {
-  pub fn new(resource_arn: String, secret_arn: String, database: String, region: String) -> Self {
+  pub fn from_env() -> Self {
+    dotenv().ok();
+
     Self {
-      resource_arn,
-      secret_arn,
-      database,
-      region,
+      driver: std::env::var("DRIVER").ok(),
+      resource_arn: std::env::var("RESOURCE_ARN").ok(),
+      secret_arn: std::env::var("SECRET_ARN").ok(),
+      database: std::env::var("DATABASE").ok(),
+      region: std::env::var("REGION").ok(),
+      endpoint: std::env::var("ENDPOINT").ok(),
+      access_key_id: None,
+      secret_access_key: None,
+      session_token: None,
+      url: std::env::var("DATABASE_URL").ok(),
+      ssl_mode: std::env::var("SSL_MODE").ok(),
+      ssl_root_cert: std::env::var("SSL_ROOT_CERT").ok(),
+      schema: std::env::var("SCHEMA").ok(),
     }
   }
 }
 
-pub async fn get_foreign_keys(client: &ExecuteStatement, table: &str) -> Result<String, RuntimeError> {
-  dotenv().ok();
-  let schema = std::env::var("SCHEMA").expect("Schema must be set.");
-
-  let introspection_query = format!(
-    "SELECT
-    tc.table_schema, 
-    tc.constraint_name, 
-    tc.table_name, 
-    kcu.column_name, 
-    ccu.table_schema AS foreign_table_schema,
-    ccu.table_name AS foreign_table_name,
-    ccu.column_name AS foreign_column_name 
-FROM information_schema.table_constraints AS tc 
-JOIN information_schema.key_column_usage AS kcu
-    ON tc.constraint_name = kcu.constraint_name
-    AND tc.table_schema = kcu.table_schema
-JOIN information_schema.constraint_column_usage AS ccu
-    ON ccu.constraint_name = tc.constraint_name
-WHERE tc.constraint_type = 'FOREIGN KEY'
-    AND tc.table_schema='{}'
-    AND tc.table_name='{}';
-  ",
-    schema, table
-  );
-
-  let st = client.clone().sql(introspection_query);
-
-  match st.send().await {
-    Ok(result) => {
-      let formatted_records = result.formatted_records().unwrap();
-      Ok(formatted_records.to_string())
-    }
-    Err(error) => {
-      if let SdkError::ServiceError(service_error) = error {
-        Err(RuntimeError::Error(format!("{}", service_error.err())))
-      } else {
-        Err(RuntimeError::Error("Unknown error occurred".to_string()))
-      }
-    }
-  }
+/// A connected driver together with the options it was created from, and the models statements
+/// run through it are validated against once registered.
+#[derive(Clone)]
+pub struct Connection {
+  pub driver: Arc<dyn Driver>,
+  pub options: ConnectionOptions,
+  pub models: Option<Arc<ModelRegistry>>,
 }
 
-pub async fn introspect_schema(client: &ExecuteStatement) -> Result<String, RuntimeError> {
-  dotenv().ok();
-  let schema = std::env::var("SCHEMA").expect("Schema must be set.");
-
-  let introspection_query = format!(
-    "SELECT tabl.table_name, columns.column_name, columns.data_type, columns.is_nullable,
-      CASE
-        WHEN primary_keys.column_name IS NOT NULL THEN 'YES'
-        ELSE 'NO'
-      END AS is_primary_key
-    FROM
-      (SELECT table_name FROM information_schema.tables WHERE table_schema = '{}') AS tabl
-    INNER JOIN 
-      (SELECT
-        table_name,
-        column_name,
-        data_type,
-        is_nullable
-      FROM information_schema.columns) AS columns
-    ON tabl.table_name = columns.table_name
-    LEFT JOIN (
-      SELECT tc.table_name, kcu.column_name
-      FROM information_schema.table_constraints tc
-      JOIN information_schema.key_column_usage kcu
-        ON tc.constraint_name = kcu.constraint_name
-      AND tc.table_schema = kcu.table_schema
-      AND tc.table_name = kcu.table_name
-      WHERE tc.constraint_type = 'PRIMARY KEY'
-    ) AS primary_keys
-    ON tabl.table_name = primary_keys.table_name
-      AND columns.column_name = primary_keys.column_name
-    GROUP BY
-      tabl.table_name,
-      columns.column_name,
-      columns.data_type,
-      columns.is_nullable,
-      is_primary_key;
-  ",
-    schema
-  );
-
-  let st = client.clone().sql(introspection_query);
-
-  match st.send().await {
-    Ok(result) => {
-      let formatted_records = result.formatted_records().unwrap();
-      Ok(formatted_records.to_string())
-    }
-    Err(error) => {
-      if let SdkError::ServiceError(service_error) = error {
-        Err(RuntimeError::Error(format!("{}", service_error.err())))
-      } else {
-        Err(RuntimeError::Error("Unknown error occurred".to_string()))
-      }
+impl Connection {
+  fn schema(&self) -> Result<String, RuntimeError> {
+    dotenv().ok();
+
+    match &self.options.schema {
+      Some(schema) => Ok(schema.to_string()),
+      None => std::env::var("SCHEMA").map_err(|_| RuntimeError::MissingVariable("Schema must be set.".to_string())),
     }
   }
 }
 
-fn get_connection_options() -> Result<ConnectionOptions, String> {
-  dotenv().ok();
+pub async fn get_foreign_keys(connection: &Connection, table: &str) -> Result<String, RuntimeError> {
+  let schema = connection.schema()?;
+
+  connection.driver.foreign_keys(&schema, table).await
+}
 
-  let resource_arn = std::env::var("RESOURCE_ARN").map_err(|_| "RESOURCE_ARN must be set.")?;
-  let secret_arn = std::env::var("SECRET_ARN").map_err(|_| "SECRET_ARN must be set.")?;
-  let database = std::env::var("DATABASE").map_err(|_| "DATABASE must be set.")?;
-  let region = std::env::var("REGION").map_err(|_| "REGION must be set.")?;
+pub async fn introspect_schema(connection: &Connection) -> Result<String, RuntimeError> {
+  let schema = connection.schema()?;
 
-  Ok(ConnectionOptions::new(resource_arn, secret_arn, database, region))
+  connection.driver.introspect(&schema).await
 }
 
-pub async fn execute_statement(query: String, client: &ExecuteStatement) -> Result<ExecuteStatementOutput, RuntimeError> {
-  let st = client.clone().sql(query);
-
-  match st.send().await {
-    Ok(result) => Ok(result),
-    Err(error) => {
-      if let SdkError::ServiceError(service_error) = error {
-        Err(RuntimeError::Error(format!("{}", service_error.err())))
-      } else {
-        Err(RuntimeError::Error("Unknown error occurred".to_string()))
-      }
-    }
+/// Introspects `schemas`, or the connection's schema when none are given, into typed models.
+pub async fn introspect_database(connection: &Connection, schemas: Option<Vec<String>>) -> Result<DatabaseSchema, RuntimeError> {
+  let schemas = match schemas {
+    Some(schemas) if !schemas.is_empty() => schemas,
+    _ => vec![connection.schema()?],
+  };
+
+  connection.driver.database_schema(&schemas).await
+}
+
+fn required(value: &Option<String>, name: &str) -> Result<String, RuntimeError> {
+  match value {
+    Some(value) => Ok(value.to_string()),
+    None => Err(RuntimeError::MissingVariable(format!("{} must be set.", name))),
   }
 }
 
-pub async fn initialize_client() -> ExecuteStatement {
-  let options = match get_connection_options() {
-    Ok(options) => options,
-    Err(err) => {
-      eprintln!("Error initializing client: {}", err);
-      std::process::exit(1);
-    }
-  };
-  let region = Region::new(options.region);
+pub async fn connect(options: ConnectionOptions) -> Result<Connection, RuntimeError> {
+  let driver: Arc<dyn Driver> = match options.driver.as_deref() {
+    Some("postgres") => {
+      let url = required(&options.url, "url")?;
 
-  let region_provider = RegionProviderChain::first_try(region.clone())
-    .or_default_provider()
-    .or_else(Region::new("eu-central-1"));
+      Arc::new(PostgresDriver::connect(&url, &options)?)
+    }
+    Some("dataApi") | None => {
+      let resource_arn = required(&options.resource_arn, "resourceArn")?;
+      let secret_arn = required(&options.secret_arn, "secretArn")?;
+      let database = required(&options.database, "database")?;
 
-  let shared_config = aws_config::from_env().region(region_provider).load().await;
+      Arc::new(DataApiDriver::connect(resource_arn, secret_arn, database, &options).await?)
+    }
+    Some(driver) => return Err(RuntimeError::Error(format!("Unknown driver {}.", driver))),
+  };
 
-  Client::new(&shared_config)
-    .execute_statement()
-    .resource_arn(options.resource_arn)
-    .database(options.database)
-    .secret_arn(options.secret_arn)
-    .format_records_as(RecordsFormatType::Json)
+  Ok(Connection {
+    driver,
+    options,
+    models: None,
+  })
 }
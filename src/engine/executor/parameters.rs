@@ -0,0 +1,82 @@
+use super::error::RuntimeError;
+use crate::{engine::builder::parameters::Parameter, parser::parsed::ParsedValue};
+use aws_sdk_rdsdata::{
+  model::{Field, SqlParameter, TypeHint},
+  types::Blob,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+pub fn to_sql_parameters(parameters: &[Parameter]) -> Result<Vec<SqlParameter>, RuntimeError> {
+  parameters.iter().map(to_sql_parameter).collect()
+}
+
+fn to_sql_parameter(parameter: &Parameter) -> Result<SqlParameter, RuntimeError> {
+  let (value, type_hint) = match &parameter.value {
+    ParsedValue::String(s) => (Field::StringValue(s.to_string()), None),
+    ParsedValue::Number(n) => match n.parse::<i64>() {
+      Ok(long) => (Field::LongValue(long), None),
+      // Anything that does not fit into a 64-bit integer is sent as text and cast by the database.
+      Err(_) => (Field::StringValue(n.to_string()), Some(TypeHint::Decimal)),
+    },
+    ParsedValue::Float(f) => match f.parse::<f64>() {
+      Ok(double) => (Field::DoubleValue(double), None),
+      Err(_) => return Err(invalid_value(parameter)),
+    },
+    ParsedValue::Boolean(b) => match b.parse::<bool>() {
+      Ok(boolean) => (Field::BooleanValue(boolean), None),
+      Err(_) => return Err(invalid_value(parameter)),
+    },
+    ParsedValue::Date(d) => (Field::StringValue(d.to_string()), Some(TypeHint::Date)),
+    ParsedValue::DateTime(dt) => (Field::StringValue(format_timestamp(dt)), Some(TypeHint::Timestamp)),
+    ParsedValue::Uuid(u) => (Field::StringValue(u.to_string()), Some(TypeHint::Uuid)),
+    ParsedValue::Json(j) => (Field::StringValue(j.to_string()), Some(TypeHint::Json)),
+    ParsedValue::Decimal(d) => (Field::StringValue(d.to_string()), Some(TypeHint::Decimal)),
+    ParsedValue::Bytes(b) => match STANDARD.decode(b) {
+      Ok(bytes) => (Field::BlobValue(Blob::new(bytes)), None),
+      Err(_) => return Err(invalid_value(parameter)),
+    },
+    // Values without a matching hint are sent as text and cast by the placeholder's `CAST`.
+    ParsedValue::Jsonb(_)
+    | ParsedValue::TimestampTz(_)
+    | ParsedValue::Enum(_)
+    | ParsedValue::Array(_)
+    | ParsedValue::Cast(_, _) => return to_text_parameter(parameter),
+    ParsedValue::Null => (Field::IsNull(true), None),
+    ParsedValue::Custom(_) => return Err(invalid_value(parameter)),
+  };
+
+  Ok(
+    SqlParameter::builder()
+      .name(parameter.name.to_string())
+      .value(value)
+      .set_type_hint(type_hint)
+      .build(),
+  )
+}
+
+/// Sends the value as text, except a cast value keeps the encoding of the value it casts so numbers
+/// and binary data stay typed.
+fn to_text_parameter(parameter: &Parameter) -> Result<SqlParameter, RuntimeError> {
+  match &parameter.value {
+    ParsedValue::Cast(value, _) if !matches!(value.as_ref(), ParsedValue::Array(_)) => to_sql_parameter(&Parameter {
+      name: parameter.name.to_string(),
+      value: value.as_ref().clone(),
+    }),
+    value => Ok(
+      SqlParameter::builder()
+        .name(parameter.name.to_string())
+        .value(Field::StringValue(value.text()))
+        .build(),
+    ),
+  }
+}
+
+/// The Data API only accepts `YYYY-MM-DD HH:MM:SS[.FFF]` for TIMESTAMP hints, so ISO 8601
+/// strings coming from JS (`2023-01-01T10:00:00.000Z`) are rewritten into that shape.
+fn format_timestamp(value: &str) -> String {
+  value.replacen('T', " ", 1).trim_end_matches('Z').to_string()
+}
+
+fn invalid_value(parameter: &Parameter) -> RuntimeError {
+  RuntimeError::Error(format!("Invalid value for parameter {}: {:?}", parameter.name, parameter.value))
+}
@@ -0,0 +1,483 @@
+use super::{
+  driver::Driver,
+  error::RuntimeError,
+  postgres_tls::{connector, TlsMode},
+  postgres_values::{is_readable, ColumnValue},
+  records::TypedRecords,
+  ConnectionOptions,
+};
+use crate::{engine::builder::parameters::BuiltQuery, parser::parsed::ParsedValue};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bytes::BytesMut;
+use deadpool_postgres::{Manager, ManagerConfig, Object, Pool, PoolError, RecyclingMethod};
+use std::{
+  collections::HashMap,
+  error::Error,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+  },
+  time::{Duration, Instant},
+};
+use tokio_postgres::{
+  types::{to_sql_checked, Format, IsNull, ToSql, Type},
+  Column, Row, Statement,
+};
+
+/// Open transactions without a statement for this long are rolled back, as the Data API does
+/// after three minutes.
+const TRANSACTION_TIMEOUT: Duration = Duration::from_secs(3 * 60);
+
+/// Talks to PostgreSQL over its wire protocol through a connection pool. Open transactions pin
+/// their pooled connection until they are committed, rolled back or time out.
+pub struct PostgresDriver {
+  pool: Pool,
+  transactions: Mutex<HashMap<String, OpenTransaction>>,
+  next_transaction_id: AtomicU64,
+}
+
+struct OpenTransaction {
+  connection: Arc<tokio::sync::Mutex<Object>>,
+  last_used: Instant,
+}
+
+impl PostgresDriver {
+  pub fn connect(url: &str, options: &ConnectionOptions) -> Result<Self, RuntimeError> {
+    let mut config = url
+      .parse::<tokio_postgres::Config>()
+      .map_err(|err| RuntimeError::InvalidCredentials(err.to_string()))?;
+
+    let tls_mode = match &options.ssl_mode {
+      Some(mode) => TlsMode::parse(mode)?,
+      None => TlsMode::from_config(&config),
+    };
+    config.ssl_mode(tls_mode.ssl_mode());
+
+    let manager = Manager::from_config(
+      config,
+      connector(tls_mode, options.ssl_root_cert.as_deref())?,
+      ManagerConfig {
+        recycling_method: RecyclingMethod::Fast,
+      },
+    );
+
+    let pool = Pool::builder(manager)
+      .build()
+      .map_err(|err| RuntimeError::Error(err.to_string()))?;
+
+    Ok(Self {
+      pool,
+      transactions: Mutex::new(HashMap::new()),
+      next_transaction_id: AtomicU64::new(1),
+    })
+  }
+
+  async fn connection(&self) -> Result<Object, RuntimeError> {
+    self.reap_transactions();
+    self.pool.get().await.map_err(to_pool_error)
+  }
+
+  fn transaction(&self, transaction_id: &str) -> Result<Arc<tokio::sync::Mutex<Object>>, RuntimeError> {
+    self.reap_transactions();
+
+    match self.transactions.lock().unwrap().get_mut(transaction_id) {
+      Some(transaction) => {
+        transaction.last_used = Instant::now();
+        Ok(transaction.connection.clone())
+      }
+      None => Err(unknown_transaction(transaction_id)),
+    }
+  }
+
+  /// Drops transactions that timed out. Their connections are detached from the pool and closed,
+  /// which makes the server roll them back, instead of being handed out again mid-transaction.
+  /// Transactions still running a statement are left alone.
+  fn reap_transactions(&self) {
+    let expired = {
+      let mut transactions = self.transactions.lock().unwrap();
+      let expired_ids = transactions
+        .iter()
+        .filter(|(_, transaction)| {
+          transaction.last_used.elapsed() > TRANSACTION_TIMEOUT && Arc::strong_count(&transaction.connection) == 1
+        })
+        .map(|(transaction_id, _)| transaction_id.clone())
+        .collect::<Vec<_>>();
+
+      expired_ids
+        .iter()
+        .filter_map(|transaction_id| transactions.remove(transaction_id))
+        .collect::<Vec<_>>()
+    };
+
+    for transaction in expired {
+      if let Ok(connection) = Arc::try_unwrap(transaction.connection) {
+        drop(Object::take(connection.into_inner()));
+      }
+    }
+  }
+
+  async fn finish_transaction(&self, transaction_id: String, command: &str) -> Result<(), RuntimeError> {
+    let connection = match self.transactions.lock().unwrap().remove(&transaction_id) {
+      Some(transaction) => transaction.connection,
+      None => return Err(unknown_transaction(&transaction_id)),
+    };
+
+    let connection = connection.lock().await;
+    connection.batch_execute(command).await.map_err(to_runtime_error)
+  }
+}
+
+#[async_trait]
+impl Driver for PostgresDriver {
+  async fn execute(&self, query: &BuiltQuery, transaction_id: Option<String>) -> Result<Option<String>, RuntimeError> {
+    match transaction_id {
+      Some(transaction_id) => {
+        let connection = self.transaction(&transaction_id)?;
+        let connection = connection.lock().await;
+        run(&connection, query).await
+      }
+      None => {
+        let connection = self.connection().await?;
+        run(&connection, query).await
+      }
+    }
+  }
+
+  async fn execute_typed(
+    &self,
+    query: &BuiltQuery,
+    transaction_id: Option<String>,
+  ) -> Result<Option<TypedRecords>, RuntimeError> {
+    match transaction_id {
+      Some(transaction_id) => {
+        let connection = self.transaction(&transaction_id)?;
+        let connection = connection.lock().await;
+        run_typed(&connection, query).await
+      }
+      None => {
+        let connection = self.connection().await?;
+        run_typed(&connection, query).await
+      }
+    }
+  }
+
+  async fn begin_transaction(&self) -> Result<String, RuntimeError> {
+    let connection = self.connection().await?;
+    connection.batch_execute("BEGIN").await.map_err(to_runtime_error)?;
+
+    let transaction_id = format!("pg-{}", self.next_transaction_id.fetch_add(1, Ordering::SeqCst));
+    self
+      .transactions
+      .lock()
+      .unwrap()
+      .insert(
+        transaction_id.clone(),
+        OpenTransaction {
+          connection: Arc::new(tokio::sync::Mutex::new(connection)),
+          last_used: Instant::now(),
+        },
+      );
+
+    Ok(transaction_id)
+  }
+
+  async fn commit_transaction(&self, transaction_id: String) -> Result<(), RuntimeError> {
+    self.finish_transaction(transaction_id, "COMMIT").await
+  }
+
+  async fn rollback_transaction(&self, transaction_id: String) -> Result<(), RuntimeError> {
+    self.finish_transaction(transaction_id, "ROLLBACK").await
+  }
+}
+
+/// Runs the query and, when it produces rows, writes them as a JSON array of objects in the order
+/// they were returned, the same shape as the Data API's `formattedRecords`.
+async fn run(client: &tokio_postgres::Client, query: &BuiltQuery) -> Result<Option<String>, RuntimeError> {
+  let rows = match query_rows(client, query).await? {
+    Some((_, _, rows)) => rows,
+    None => return Ok(None),
+  };
+
+  let mut json = String::from("[");
+  for (index, row) in rows.iter().enumerate() {
+    if index > 0 {
+      json.push(',');
+    }
+
+    json.push('{');
+    for (column_index, column) in row.columns().iter().enumerate() {
+      if column_index > 0 {
+        json.push(',');
+      }
+
+      json.push_str(&serde_json::Value::String(column.name().to_string()).to_string());
+      json.push(':');
+      let value = row.try_get::<_, ColumnValue>(column_index).map_err(to_runtime_error)?;
+      value.write_json(column.type_(), &mut json);
+    }
+    json.push('}');
+  }
+  json.push(']');
+
+  Ok(Some(json))
+}
+
+/// Prepares and runs the query, returning the prepared statement, the types its columns had before
+/// any were cast to text and the rows, or `None` when the statement returns no rows at all.
+async fn query_rows(
+  client: &tokio_postgres::Client,
+  query: &BuiltQuery,
+) -> Result<Option<(Statement, Vec<Type>, Vec<Row>)>, RuntimeError> {
+  let sql = to_positional_placeholders(query.sql.trim().trim_end_matches(';'), query.parameters.len());
+  let parameters = text_parameters(query)?;
+  let parameters = parameters.iter().map(|parameter| parameter as &(dyn ToSql + Sync)).collect::<Vec<_>>();
+
+  let mut statement = client.prepare(&sql).await.map_err(to_runtime_error)?;
+
+  if statement.columns().is_empty() {
+    client.execute(&statement, &parameters).await.map_err(to_runtime_error)?;
+    return Ok(None);
+  }
+
+  let types = statement.columns().iter().map(|column| column.type_().clone()).collect::<Vec<_>>();
+  if !types.iter().all(is_readable) {
+    let names = statement.columns().iter().map(Column::name).collect::<Vec<_>>();
+    let sql = with_text_columns(&sql, &names, &types);
+    statement = client.prepare(&sql).await.map_err(to_runtime_error)?;
+  }
+
+  let rows = client.query(&statement, &parameters).await.map_err(to_runtime_error)?;
+  Ok(Some((statement, types, rows)))
+}
+
+/// Wraps the query so every column of a type that can't be read from the binary format, such as
+/// `inet`, `money`, ranges or composite types, is cast to text and read the way Postgres prints it.
+/// Columns are renamed by position first, since a query may return several columns of one name.
+pub(crate) fn with_text_columns(sql: &str, names: &[&str], types: &[Type]) -> String {
+  let positions = (1..=names.len()).map(|position| format!("auro_{}", position)).collect::<Vec<_>>();
+
+  let columns = positions
+    .iter()
+    .zip(names.iter().zip(types))
+    .map(|(position, (name, ty))| {
+      let name = format!("\"{}\"", name.replace('"', "\"\""));
+      match ty.name() {
+        _ if is_readable(ty) => format!("{} AS {}", position, name),
+        // Casting `inet` to text always adds the netmask, `abbrev` prints it like the output function.
+        "inet" => format!("abbrev({}) AS {}", position, name),
+        _ => format!("{}::text AS {}", position, name),
+      }
+    })
+    .collect::<Vec<_>>();
+
+  format!(
+    "WITH auro_rows({}) AS ({}) SELECT {} FROM auro_rows",
+    positions.join(", "),
+    sql,
+    columns.join(", ")
+  )
+}
+
+/// Runs the query and returns every row with each value decoded by the type of its column, the
+/// same way [`run`] reads them. Column names come from the prepared statement, so they are known
+/// even when no rows are returned.
+async fn run_typed(client: &tokio_postgres::Client, query: &BuiltQuery) -> Result<Option<TypedRecords>, RuntimeError> {
+  let (statement, types, rows) = match query_rows(client, query).await? {
+    Some(result) => result,
+    None => return Ok(None),
+  };
+
+  let columns = statement.columns();
+  let rows = rows
+    .iter()
+    .map(|row| {
+      columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| Ok(row.try_get::<_, ColumnValue>(index).map_err(to_runtime_error)?.typed(column.type_())))
+        .collect::<Result<Vec<_>, RuntimeError>>()
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  Ok(Some(TypedRecords {
+    columns: columns.iter().map(|column| column.name().to_string()).collect(),
+    types: types.iter().map(type_name).collect(),
+    rows,
+  }))
+}
+
+/// Names a type so it can be cast to, qualified by its schema unless it is a built-in one.
+fn type_name(ty: &Type) -> String {
+  match ty.schema() {
+    "pg_catalog" => ty.name().to_string(),
+    schema => format!("{}.{}", schema, ty.name()),
+  }
+}
+
+fn text_parameters(query: &BuiltQuery) -> Result<Vec<TextParameter>, RuntimeError> {
+  query
+    .parameters
+    .iter()
+    .map(|parameter| TextParameter::try_from(&parameter.value))
+    .collect()
+}
+
+/// Rewrites the builder's named placeholders (`:p1`, `:p2`, ...) into positional ones (`$1`, `$2`, ...).
+/// String literals, quoted identifiers, dollar-quoted strings and comments are copied as they are,
+/// so text that only looks like a placeholder is never rewritten, and neither are `::` casts or
+/// placeholders past the number of parameters.
+pub(crate) fn to_positional_placeholders(sql: &str, num_parameters: usize) -> String {
+  let chars = sql.chars().collect::<Vec<_>>();
+  let mut result = String::with_capacity(sql.len());
+  let mut index = 0;
+
+  while index < chars.len() {
+    let end = match chars[index..] {
+      ['\'', ..] => {
+        let escapes = index > 0
+          && matches!(chars[index - 1], 'e' | 'E')
+          && (index == 1 || !is_identifier_char(chars[index - 2]));
+        quoted_end(&chars, index, '\'', escapes)
+      }
+      ['"', ..] => quoted_end(&chars, index, '"', false),
+      ['-', '-', ..] => find(&chars, index + 2, &['\n']).map_or(chars.len(), |end| end + 1),
+      ['/', '*', ..] => find(&chars, index + 2, &['*', '/']).map_or(chars.len(), |end| end + 2),
+      ['$', ..] => dollar_quoted_end(&chars, index).unwrap_or(index + 1),
+      [':', ':', ..] => index + 2,
+      [':', 'p', ..] => {
+        let digits_end = (index + 2..chars.len()).find(|&i| !chars[i].is_ascii_digit()).unwrap_or(chars.len());
+        let placeholder = chars[index + 2..digits_end].iter().collect::<String>().parse::<usize>().ok();
+        let ends_placeholder = digits_end == chars.len() || !is_identifier_char(chars[digits_end]);
+
+        match placeholder {
+          Some(position) if ends_placeholder && (1..=num_parameters).contains(&position) => {
+            result.push_str(&format!("${}", position));
+            index = digits_end;
+            continue;
+          }
+          _ => index + 2,
+        }
+      }
+      _ => index + 1,
+    };
+
+    result.extend(&chars[index..end]);
+    index = end;
+  }
+
+  result
+}
+
+fn is_identifier_char(c: char) -> bool {
+  c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn find(chars: &[char], from: usize, pattern: &[char]) -> Option<usize> {
+  (from..chars.len()).find(|&index| chars[index..].starts_with(pattern))
+}
+
+/// Finds the end of a literal opened by `quote` at `start`, where a doubled quote stands for the
+/// quote itself and, in `E'...'` strings, a backslash escapes the next character.
+fn quoted_end(chars: &[char], start: usize, quote: char, escapes: bool) -> usize {
+  let mut index = start + 1;
+
+  while index < chars.len() {
+    match chars[index] {
+      '\\' if escapes => index += 2,
+      c if c == quote && chars.get(index + 1) == Some(&quote) => index += 2,
+      c if c == quote => return index + 1,
+      _ => index += 1,
+    }
+  }
+
+  chars.len()
+}
+
+/// Finds the end of a `$tag$...$tag$` string starting at `start`, `None` when the `$` does not
+/// open one, e.g. for a positional parameter.
+fn dollar_quoted_end(chars: &[char], start: usize) -> Option<usize> {
+  let tag_end = (start + 1..chars.len()).find(|&index| chars[index] == '$')?;
+  let tag = &chars[start..=tag_end];
+
+  let valid_tag = tag[1..tag.len() - 1]
+    .iter()
+    .enumerate()
+    .all(|(position, c)| c.is_alphabetic() || *c == '_' || (position > 0 && c.is_ascii_digit()));
+  if !valid_tag {
+    return None;
+  }
+
+  find(chars, tag_end + 1, tag).map(|end| end + tag.len()).or(Some(chars.len()))
+}
+
+/// Sends every value in text format and lets the server parse it into whatever type it inferred
+/// for the placeholder, mirroring how the Data API treats string values with type hints.
+#[derive(Debug)]
+struct TextParameter(Option<String>);
+
+impl TryFrom<&ParsedValue> for TextParameter {
+  type Error = RuntimeError;
+
+  fn try_from(value: &ParsedValue) -> Result<Self, Self::Error> {
+    match value {
+      // `bytea` reads its text format as hex digits.
+      ParsedValue::Bytes(b) => {
+        let bytes = STANDARD
+          .decode(b)
+          .map_err(|err| RuntimeError::Error(format!("Invalid base64 value: {}", err)))?;
+        let hex = bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+
+        Ok(TextParameter(Some(format!("\\x{}", hex))))
+      }
+      ParsedValue::Cast(value, _) => TextParameter::try_from(value.as_ref()),
+      ParsedValue::Null => Ok(TextParameter(None)),
+      value => Ok(TextParameter(Some(value.text()))),
+    }
+  }
+}
+
+impl ToSql for TextParameter {
+  fn to_sql(&self, _ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+    match &self.0 {
+      Some(value) => {
+        out.extend_from_slice(value.as_bytes());
+        Ok(IsNull::No)
+      }
+      None => Ok(IsNull::Yes),
+    }
+  }
+
+  fn accepts(_ty: &Type) -> bool {
+    true
+  }
+
+  fn encode_format(&self, _ty: &Type) -> Format {
+    Format::Text
+  }
+
+  to_sql_checked!();
+}
+
+fn unknown_transaction(transaction_id: &str) -> RuntimeError {
+  RuntimeError::Error(format!("Unknown transaction {}.", transaction_id))
+}
+
+fn to_runtime_error(error: tokio_postgres::Error) -> RuntimeError {
+  match error.as_db_error() {
+    Some(db_error) => RuntimeError::Error(db_error.message().to_string()),
+    None => RuntimeError::Error(error.to_string()),
+  }
+}
+
+/// Failed connects only name the step that failed, the cause, e.g. a rejected certificate, is their source.
+fn to_pool_error(error: PoolError) -> RuntimeError {
+  match error {
+    PoolError::Backend(error) if error.as_db_error().is_none() => match error.source() {
+      Some(source) => RuntimeError::Error(format!("{}: {}", error, source)),
+      None => RuntimeError::Error(error.to_string()),
+    },
+    PoolError::Backend(error) => to_runtime_error(error),
+    error => RuntimeError::Error(error.to_string()),
+  }
+}
@@ -0,0 +1,129 @@
+use super::error::RuntimeError;
+use rustls::{
+  client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+  crypto::{ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider},
+  pki_types::{pem::PemObject, CertificateDer, ServerName, UnixTime},
+  ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use std::sync::Arc;
+use tokio_postgres::config::SslMode;
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+/// How the `postgres` driver secures its connections, named after libpq's `sslmode`. As in libpq,
+/// only `verify-full` checks the server's certificate, the other modes merely encrypt.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TlsMode {
+  Disable,
+  Prefer,
+  Require,
+  VerifyFull,
+}
+
+impl TlsMode {
+  pub fn parse(mode: &str) -> Result<Self, RuntimeError> {
+    match mode {
+      "disable" => Ok(TlsMode::Disable),
+      "prefer" => Ok(TlsMode::Prefer),
+      "require" => Ok(TlsMode::Require),
+      "verify-full" => Ok(TlsMode::VerifyFull),
+      _ => Err(RuntimeError::Error(format!(
+        "Unknown sslMode {}, expected disable, prefer, require or verify-full.",
+        mode
+      ))),
+    }
+  }
+
+  /// The mode of a connection string, which only knows the modes that don't verify.
+  pub fn from_config(config: &tokio_postgres::Config) -> Self {
+    match config.get_ssl_mode() {
+      SslMode::Disable => TlsMode::Disable,
+      SslMode::Require => TlsMode::Require,
+      _ => TlsMode::Prefer,
+    }
+  }
+
+  pub fn ssl_mode(self) -> SslMode {
+    match self {
+      TlsMode::Disable => SslMode::Disable,
+      TlsMode::Prefer => SslMode::Prefer,
+      TlsMode::Require | TlsMode::VerifyFull => SslMode::Require,
+    }
+  }
+}
+
+/// Builds the TLS connector for `mode`. `verify-full` trusts the certificate authorities of
+/// `root_cert`, a PEM file such as the RDS certificate bundle, or else those of the system.
+pub fn connector(mode: TlsMode, root_cert: Option<&str>) -> Result<MakeRustlsConnect, RuntimeError> {
+  let provider = Arc::new(ring::default_provider());
+  let builder = ClientConfig::builder_with_provider(provider.clone())
+    .with_safe_default_protocol_versions()
+    .map_err(|err| RuntimeError::Error(err.to_string()))?;
+
+  let config = match mode {
+    TlsMode::VerifyFull => builder.with_root_certificates(root_certificates(root_cert)?),
+    _ => builder
+      .dangerous()
+      .with_custom_certificate_verifier(Arc::new(UnverifiedCertificate(provider))),
+  };
+
+  Ok(MakeRustlsConnect::new(config.with_no_client_auth()))
+}
+
+fn root_certificates(root_cert: Option<&str>) -> Result<RootCertStore, RuntimeError> {
+  let certificates = match root_cert {
+    Some(path) => CertificateDer::pem_file_iter(path)
+      .and_then(|certificates| certificates.collect::<Result<Vec<_>, _>>())
+      .map_err(|err| RuntimeError::Error(format!("Can't read sslRootCert {}: {}", path, err)))?,
+    None => rustls_native_certs::load_native_certs().certs,
+  };
+
+  let mut roots = RootCertStore::empty();
+  roots.add_parsable_certificates(certificates);
+
+  if roots.is_empty() {
+    return Err(RuntimeError::Error(
+      "No trusted certificate authorities found for sslMode verify-full.".to_string(),
+    ));
+  }
+
+  Ok(roots)
+}
+
+/// Accepts any certificate, but still checks the handshake is signed by it.
+#[derive(Debug)]
+struct UnverifiedCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for UnverifiedCertificate {
+  fn verify_server_cert(
+    &self,
+    _end_entity: &CertificateDer<'_>,
+    _intermediates: &[CertificateDer<'_>],
+    _server_name: &ServerName<'_>,
+    _ocsp_response: &[u8],
+    _now: UnixTime,
+  ) -> Result<ServerCertVerified, rustls::Error> {
+    Ok(ServerCertVerified::assertion())
+  }
+
+  fn verify_tls12_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, rustls::Error> {
+    verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+  }
+
+  fn verify_tls13_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, rustls::Error> {
+    verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+  }
+
+  fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+    self.0.signature_verification_algorithms.supported_schemes()
+  }
+}
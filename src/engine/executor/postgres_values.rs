@@ -0,0 +1,369 @@
+use super::records::{self, TypedValue};
+use std::error::Error;
+use tokio_postgres::types::{FromSql, Kind, Type};
+
+type DecodeError = Box<dyn Error + Sync + Send>;
+
+/// A column value of a row read in Postgres' binary format, kept as the text Postgres prints for it
+/// so it is decoded by its column's type the same way the Data API's string values are.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValue {
+  Null,
+  Text(String),
+  Array(Vec<ColumnValue>),
+}
+
+impl<'a> FromSql<'a> for ColumnValue {
+  fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, DecodeError> {
+    decode(ty, raw)
+  }
+
+  fn from_sql_null(_ty: &Type) -> Result<Self, DecodeError> {
+    Ok(ColumnValue::Null)
+  }
+
+  fn accepts(_ty: &Type) -> bool {
+    true
+  }
+}
+
+impl ColumnValue {
+  /// Decodes the value by the type of its column, see [`records::decode_text`].
+  pub fn typed(self, ty: &Type) -> TypedValue {
+    match self {
+      ColumnValue::Null => TypedValue::Null,
+      ColumnValue::Text(text) => records::decode_text(ty.name(), &text),
+      ColumnValue::Array(values) => TypedValue::Array(values.into_iter().map(|value| value.typed(element_type(ty))).collect()),
+    }
+  }
+
+  /// Writes the value as `json_agg` would: numbers and booleans as JSON literals, `json` and `jsonb`
+  /// values nested, arrays as JSON arrays and everything else as strings.
+  pub fn write_json(&self, ty: &Type, json: &mut String) {
+    match self {
+      ColumnValue::Null => json.push_str("null"),
+      ColumnValue::Array(values) => {
+        json.push('[');
+        for (index, value) in values.iter().enumerate() {
+          if index > 0 {
+            json.push(',');
+          }
+          value.write_json(element_type(ty), json);
+        }
+        json.push(']');
+      }
+      ColumnValue::Text(text) => match ty.name() {
+        "bool" => json.push_str(if text == "t" { "true" } else { "false" }),
+        "json" | "jsonb" => json.push_str(text),
+        // NaN and the infinities have no JSON literal and stay strings.
+        "int2" | "int4" | "int8" | "oid" | "float4" | "float8" | "numeric"
+          if text.parse::<f64>().is_ok_and(|number| number.is_finite()) =>
+        {
+          json.push_str(text)
+        }
+        _ => json.push_str(&serde_json::Value::String(text.to_string()).to_string()),
+      },
+    }
+  }
+}
+
+/// Whether values of the type can be read from Postgres' binary format. Columns of other types are
+/// read in their text form instead.
+pub fn is_readable(ty: &Type) -> bool {
+  match ty.kind() {
+    Kind::Array(member) => is_readable(member),
+    Kind::Domain(base) => is_readable(base),
+    Kind::Enum(_) => true,
+    _ => SCALAR_TYPES.contains(&ty.name()),
+  }
+}
+
+/// Built-in types [`decode_scalar`] reads.
+const SCALAR_TYPES: &[&str] = &[
+  "bool", "char", "int2", "int4", "int8", "oid", "float4", "float8", "numeric", "text", "varchar", "bpchar", "name",
+  "unknown", "xml", "json", "citext", "jsonb", "bytea", "uuid", "date", "timestamp", "timestamptz", "time", "timetz",
+  "interval",
+];
+
+fn element_type(ty: &Type) -> &Type {
+  match ty.kind() {
+    Kind::Array(member) => member,
+    _ => ty,
+  }
+}
+
+fn decode(ty: &Type, raw: &[u8]) -> Result<ColumnValue, DecodeError> {
+  match ty.kind() {
+    Kind::Array(member) => decode_array(member, raw),
+    Kind::Domain(base) => decode(base, raw),
+    Kind::Enum(_) => Ok(ColumnValue::Text(String::from_utf8(raw.to_vec())?)),
+    _ => decode_scalar(ty, raw).map(ColumnValue::Text),
+  }
+}
+
+/// Prints a value of a built-in type the way Postgres does, except timestamps are written in ISO
+/// 8601 like `json_agg` writes them, with time zones in UTC.
+fn decode_scalar(ty: &Type, raw: &[u8]) -> Result<String, DecodeError> {
+  let text = match ty.name() {
+    "bool" => match bool::from_sql(ty, raw)? {
+      true => "t".to_string(),
+      false => "f".to_string(),
+    },
+    "char" => (i8::from_sql(ty, raw)? as u8 as char).to_string(),
+    "int2" => i16::from_sql(ty, raw)?.to_string(),
+    "int4" => i32::from_sql(ty, raw)?.to_string(),
+    "int8" => i64::from_sql(ty, raw)?.to_string(),
+    "oid" => u32::from_sql(ty, raw)?.to_string(),
+    "float4" => float(f32::from_sql(ty, raw)? as f64),
+    "float8" => float(f64::from_sql(ty, raw)?),
+    "numeric" => numeric(raw)?,
+    "text" | "varchar" | "bpchar" | "name" | "unknown" | "xml" | "json" | "citext" => String::from_utf8(raw.to_vec())?,
+    // `jsonb` starts with the version of its format.
+    "jsonb" => match raw.split_first() {
+      Some((1, json)) => String::from_utf8(json.to_vec())?,
+      _ => return Err("Unsupported jsonb version.".into()),
+    },
+    "bytea" => format!("\\x{}", hex(raw)),
+    "uuid" => {
+      let hex = hex(raw);
+      if hex.len() != 32 {
+        return Err("Invalid uuid.".into());
+      }
+      format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+      )
+    }
+    "date" => date(i32::from_sql(ty, raw)?),
+    "timestamp" => timestamp(i64::from_sql(ty, raw)?, ""),
+    "timestamptz" => timestamp(i64::from_sql(ty, raw)?, "+00:00"),
+    "time" => time(i64::from_sql(ty, raw)?),
+    "timetz" => {
+      let mut reader = Reader(raw);
+      let micros = reader.i64()?;
+      // The offset is stored in seconds west of UTC.
+      let offset = -reader.i32()?;
+      let sign = if offset < 0 { '-' } else { '+' };
+      let (hours, minutes) = (offset.abs() / 3600, offset.abs() % 3600 / 60);
+      match minutes {
+        0 => format!("{}{}{:02}", time(micros), sign, hours),
+        _ => format!("{}{}{:02}:{:02}", time(micros), sign, hours, minutes),
+      }
+    }
+    "interval" => {
+      let mut reader = Reader(raw);
+      interval(reader.i64()?, reader.i32()?, reader.i32()?)
+    }
+    // Queries returning other types are rewritten to read them as text, see `is_readable`.
+    name => return Err(format!("Columns of type {} can't be read, cast them to text.", name).into()),
+  };
+
+  Ok(text)
+}
+
+/// Reads the binary array format: the number of dimensions, a null flag, the element type, the
+/// length and lower bound of every dimension, and the elements, each prefixed with its length.
+fn decode_array(member: &Type, raw: &[u8]) -> Result<ColumnValue, DecodeError> {
+  let mut reader = Reader(raw);
+  let dimensions = reader.i32()?;
+  reader.i32()?;
+  reader.i32()?;
+
+  let mut lengths = Vec::new();
+  for _ in 0..dimensions {
+    lengths.push(reader.i32()?.max(0) as usize);
+    reader.i32()?;
+  }
+
+  let mut elements = Vec::new();
+  while !reader.0.is_empty() {
+    let element = match reader.i32()? {
+      -1 => ColumnValue::Null,
+      length => decode(member, reader.take(length.max(0) as usize)?)?,
+    };
+    elements.push(element);
+  }
+
+  // Multidimensional arrays are nested from the innermost dimension outwards.
+  for length in lengths.iter().skip(1).rev() {
+    elements = elements
+      .chunks(*length)
+      .map(|chunk| ColumnValue::Array(chunk.to_vec()))
+      .collect();
+  }
+
+  Ok(ColumnValue::Array(elements))
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+  fn take(&mut self, length: usize) -> Result<&'a [u8], DecodeError> {
+    if self.0.len() < length {
+      return Err("Unexpected end of value.".into());
+    }
+
+    let (bytes, rest) = self.0.split_at(length);
+    self.0 = rest;
+    Ok(bytes)
+  }
+
+  fn i16(&mut self) -> Result<i16, DecodeError> {
+    Ok(i16::from_be_bytes(self.take(2)?.try_into()?))
+  }
+
+  fn i32(&mut self) -> Result<i32, DecodeError> {
+    Ok(i32::from_be_bytes(self.take(4)?.try_into()?))
+  }
+
+  fn i64(&mut self) -> Result<i64, DecodeError> {
+    Ok(i64::from_be_bytes(self.take(8)?.try_into()?))
+  }
+}
+
+fn hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn float(value: f64) -> String {
+  match value {
+    value if value.is_nan() => "NaN".to_string(),
+    value if value.is_infinite() && value > 0.0 => "Infinity".to_string(),
+    value if value.is_infinite() => "-Infinity".to_string(),
+    value => value.to_string(),
+  }
+}
+
+/// Reads a `numeric`: its number of digits, the weight of the first digit, the sign and the
+/// number of decimals to print, followed by the digits in base 10000.
+fn numeric(raw: &[u8]) -> Result<String, DecodeError> {
+  let mut reader = Reader(raw);
+  let num_digits = reader.i16()?.max(0) as usize;
+  let weight = reader.i16()? as i32;
+  let sign = reader.i16()? as u16;
+  let scale = reader.i16()?.max(0) as usize;
+  let digits = (0..num_digits).map(|_| reader.i16()).collect::<Result<Vec<_>, _>>()?;
+
+  match sign {
+    0xC000 => return Ok("NaN".to_string()),
+    0xD000 => return Ok("Infinity".to_string()),
+    0xF000 => return Ok("-Infinity".to_string()),
+    _ => {}
+  }
+
+  let digit = |position: i32| match position {
+    position if position < 0 => 0,
+    position => digits.get(position as usize).copied().unwrap_or(0),
+  };
+
+  let mut integer = String::new();
+  for position in 0..=weight {
+    match position {
+      0 => integer.push_str(&digit(position).to_string()),
+      _ => integer.push_str(&format!("{:04}", digit(position))),
+    }
+  }
+  if integer.is_empty() {
+    integer.push('0');
+  }
+
+  let mut fraction = String::new();
+  let mut position = weight + 1;
+  while fraction.len() < scale {
+    fraction.push_str(&format!("{:04}", digit(position)));
+    position += 1;
+  }
+  fraction.truncate(scale);
+
+  let sign = if sign == 0x4000 { "-" } else { "" };
+  match fraction.is_empty() {
+    true => Ok(format!("{}{}", sign, integer)),
+    false => Ok(format!("{}{}.{}", sign, integer, fraction)),
+  }
+}
+
+/// Days between the Unix epoch and Postgres' epoch, 2000-01-01.
+const EPOCH_DAYS: i64 = 10_957;
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// Dates count days since 2000-01-01, with the largest and smallest value standing for infinity.
+fn date(days: i32) -> String {
+  match days {
+    i32::MAX => "infinity".to_string(),
+    i32::MIN => "-infinity".to_string(),
+    days => civil_date(days as i64 + EPOCH_DAYS),
+  }
+}
+
+/// Timestamps count microseconds since 2000-01-01, with the largest and smallest value standing
+/// for infinity.
+fn timestamp(micros: i64, offset: &str) -> String {
+  match micros {
+    i64::MAX => "infinity".to_string(),
+    i64::MIN => "-infinity".to_string(),
+    micros => format!(
+      "{}T{}{}",
+      civil_date(micros.div_euclid(MICROS_PER_DAY) + EPOCH_DAYS),
+      time(micros.rem_euclid(MICROS_PER_DAY)),
+      offset
+    ),
+  }
+}
+
+/// Prints microseconds since midnight as `HH:MM:SS`, with as many decimals as needed.
+fn time(micros: i64) -> String {
+  let seconds = micros / 1_000_000;
+  let text = format!("{:02}:{:02}:{:02}", seconds / 3600, seconds % 3600 / 60, seconds % 60);
+
+  match micros % 1_000_000 {
+    0 => text,
+    fraction => format!("{}.{}", text, format!("{:06}", fraction).trim_end_matches('0')),
+  }
+}
+
+fn interval(micros: i64, days: i32, months: i32) -> String {
+  let unit = |value: i64, name: &str| match value {
+    1 | -1 => format!("{} {}", value, name),
+    _ => format!("{} {}s", value, name),
+  };
+
+  let mut parts = Vec::new();
+  if months / 12 != 0 {
+    parts.push(unit((months / 12) as i64, "year"));
+  }
+  if months % 12 != 0 {
+    parts.push(unit((months % 12) as i64, "mon"));
+  }
+  if days != 0 {
+    parts.push(unit(days as i64, "day"));
+  }
+  if micros != 0 || parts.is_empty() {
+    let sign = if micros < 0 { "-" } else { "" };
+    parts.push(format!("{}{}", sign, time(micros.abs())));
+  }
+
+  parts.join(" ")
+}
+
+/// Prints days since the Unix epoch as a date of the proleptic Gregorian calendar, with years
+/// before the first one written as `BC` like Postgres does.
+fn civil_date(days: i64) -> String {
+  let days = days + 719_468;
+  let era = days.div_euclid(146_097);
+  let day_of_era = days.rem_euclid(146_097);
+  let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+  let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+  match year {
+    year if year <= 0 => format!("{:04}-{:02}-{:02} BC", 1 - year, month, day),
+    year => format!("{:04}-{:02}-{:02}", year, month, day),
+  }
+}
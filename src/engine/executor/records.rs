@@ -0,0 +1,277 @@
+use super::error::RuntimeError;
+use napi::{
+  bindgen_prelude::{i64n, Buffer, ToNapiValue},
+  check_status, sys,
+};
+use serde_json::Value;
+use std::ptr;
+
+/// A result value decoded by the type of its column, handed to JS as the matching native value:
+/// `BigInt` for `int8`, `Date` for dates and timestamps, `Buffer` for `bytea`, parsed JSON, arrays
+/// and objects. `numeric` values stay strings so they keep their precision.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedValue {
+  Null,
+  Boolean(bool),
+  Number(f64),
+  BigInt(i64),
+  String(String),
+  /// Milliseconds since the Unix epoch.
+  Date(f64),
+  Bytes(Vec<u8>),
+  Json(Value),
+  Array(Vec<TypedValue>),
+  Object(Vec<(String, TypedValue)>),
+}
+
+/// Rows of a result together with the labels of their columns.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TypedRecords {
+  pub columns: Vec<String>,
+  /// Type of every column as the database names it, e.g. `int8` or `timestamptz`.
+  pub types: Vec<String>,
+  pub rows: Vec<Vec<TypedValue>>,
+}
+
+/// Decodes a value the database returned as text by the name of its column's type, e.g. `int8`,
+/// `timestamptz` or `_text` for arrays. Arrays are read from Postgres array literals or JSON arrays.
+/// Values that don't parse are kept as strings.
+pub fn decode_text(type_name: &str, text: &str) -> TypedValue {
+  if let Some(element_type) = type_name.strip_prefix('_') {
+    let decoded = match text.starts_with('[') {
+      true => serde_json::from_str(text)
+        .ok()
+        .map(|values| decode_json_elements(element_type, values)),
+      false => parse_array_literal(text).map(|elements| decode_elements(element_type, elements)),
+    };
+
+    return decoded.unwrap_or_else(|| TypedValue::String(text.to_string()));
+  }
+
+  let decoded = match type_name {
+    "int8" | "bigserial" => text.parse().ok().map(TypedValue::BigInt),
+    "int2" | "int4" | "serial" | "smallserial" | "oid" | "float4" | "float8" => text.parse().ok().map(TypedValue::Number),
+    "bool" => match text {
+      "t" | "true" => Some(TypedValue::Boolean(true)),
+      "f" | "false" => Some(TypedValue::Boolean(false)),
+      _ => None,
+    },
+    "date" | "timestamp" | "timestamptz" => parse_timestamp(text).map(TypedValue::Date),
+    "json" | "jsonb" => serde_json::from_str(text).ok().map(TypedValue::Json),
+    "bytea" => text.strip_prefix("\\x").and_then(decode_hex).map(TypedValue::Bytes),
+    _ => None,
+  };
+
+  decoded.unwrap_or_else(|| TypedValue::String(text.to_string()))
+}
+
+/// Elements of a text array literal, `None` for `NULL` elements.
+enum Element {
+  Value(Option<String>),
+  Array(Vec<Element>),
+}
+
+fn decode_elements(element_type: &str, elements: Vec<Element>) -> TypedValue {
+  TypedValue::Array(
+    elements
+      .into_iter()
+      .map(|element| match element {
+        Element::Value(Some(text)) => decode_text(element_type, &text),
+        Element::Value(None) => TypedValue::Null,
+        Element::Array(elements) => decode_elements(element_type, elements),
+      })
+      .collect(),
+  )
+}
+
+fn decode_json_elements(element_type: &str, values: Vec<Value>) -> TypedValue {
+  TypedValue::Array(
+    values
+      .into_iter()
+      .map(|value| match value {
+        Value::Null => TypedValue::Null,
+        Value::Bool(boolean) => TypedValue::Boolean(boolean),
+        Value::String(text) => decode_text(element_type, &text),
+        Value::Array(values) => decode_json_elements(element_type, values),
+        value => decode_text(element_type, &value.to_string()),
+      })
+      .collect(),
+  )
+}
+
+/// Reads a Postgres array literal such as `{1,NULL,"a \"b\""}` or `{{1,2},{3,4}}`.
+fn parse_array_literal(text: &str) -> Option<Vec<Element>> {
+  let mut chars = text.chars().peekable();
+  let elements = parse_array(&mut chars)?;
+
+  match chars.next() {
+    Some(_) => None,
+    None => Some(elements),
+  }
+}
+
+fn parse_array(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<Vec<Element>> {
+  if chars.next()? != '{' {
+    return None;
+  }
+
+  let mut elements = Vec::new();
+  if chars.peek() == Some(&'}') {
+    chars.next();
+    return Some(elements);
+  }
+
+  loop {
+    let element = match chars.peek()? {
+      '{' => Element::Array(parse_array(chars)?),
+      '"' => {
+        chars.next();
+        let mut value = String::new();
+        loop {
+          match chars.next()? {
+            '\\' => value.push(chars.next()?),
+            '"' => break,
+            char => value.push(char),
+          }
+        }
+        Element::Value(Some(value))
+      }
+      _ => {
+        let mut value = String::new();
+        while !matches!(chars.peek()?, ',' | '}') {
+          value.push(chars.next()?);
+        }
+        match value.as_str() {
+          "NULL" => Element::Value(None),
+          _ => Element::Value(Some(value)),
+        }
+      }
+    };
+    elements.push(element);
+
+    match chars.next()? {
+      ',' => continue,
+      '}' => return Some(elements),
+      _ => return None,
+    }
+  }
+}
+
+/// Reads pairs of hex digits, `None` when a digit is invalid or missing.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+  (0..hex.len())
+    .step_by(2)
+    .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+    .collect()
+}
+
+/// Reads `YYYY-MM-DD`, `YYYY-MM-DD HH:MM:SS[.FFF]` and ISO 8601 timestamps, with an optional `Z`,
+/// `+HH` or `+HH:MM` offset, into milliseconds since the Unix epoch. Timestamps without an offset
+/// are read as UTC, which is what the Data API returns them in.
+pub fn parse_timestamp(text: &str) -> Option<f64> {
+  let number = |from: usize, to: usize| -> Option<i64> {
+    let digits = text.get(from..to)?;
+    match digits.chars().all(|char| char.is_ascii_digit()) {
+      true => digits.parse().ok(),
+      false => None,
+    }
+  };
+
+  if text.get(4..5)? != "-" || text.get(7..8)? != "-" {
+    return None;
+  }
+  let days = days_from_civil(number(0, 4)?, number(5, 7)?, number(8, 10)?);
+
+  if text.len() == 10 {
+    return Some((days * 86_400_000) as f64);
+  }
+  if !matches!(text.get(10..11)?, " " | "T") || text.get(13..14)? != ":" || text.get(16..17)? != ":" {
+    return None;
+  }
+  let seconds = number(11, 13)? * 3600 + number(14, 16)? * 60 + number(17, 19)?;
+
+  let mut rest = &text[19..];
+  let mut millis = 0.0;
+  if let Some(fraction) = rest.strip_prefix('.') {
+    let digits = fraction.chars().take_while(char::is_ascii_digit).count();
+    millis = format!("0.{}", &fraction[..digits]).parse::<f64>().ok()? * 1000.0;
+    rest = &fraction[digits..];
+  }
+
+  let offset = match rest {
+    "" | "Z" => 0,
+    _ => {
+      let sign = match rest.get(..1)? {
+        "+" => 1,
+        "-" => -1,
+        _ => return None,
+      };
+      let offset = rest[1..].replace(':', "");
+      let hours = offset.get(..2)?.parse::<i64>().ok()?;
+      let minutes = match offset.get(2..4) {
+        Some(minutes) => minutes.parse::<i64>().ok()?,
+        None => 0,
+      };
+      sign * (hours * 3600 + minutes * 60)
+    }
+  };
+
+  Some(((days * 86_400 + seconds - offset) * 1000) as f64 + millis)
+}
+
+/// Days between the Unix epoch and a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = (if year >= 0 { year } else { year - 399 }) / 400;
+  let year_of_era = year - era * 400;
+  let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+  era * 146_097 + day_of_era - 719_468
+}
+
+impl ToNapiValue for TypedValue {
+  unsafe fn to_napi_value(env: sys::napi_env, val: Self) -> napi::Result<sys::napi_value> {
+    match val {
+      TypedValue::Null => {
+        let mut null = ptr::null_mut();
+        check_status!(sys::napi_get_null(env, &mut null))?;
+        Ok(null)
+      }
+      TypedValue::Boolean(boolean) => bool::to_napi_value(env, boolean),
+      TypedValue::Number(number) => f64::to_napi_value(env, number),
+      TypedValue::BigInt(number) => i64n::to_napi_value(env, i64n(number)),
+      TypedValue::String(text) => String::to_napi_value(env, text),
+      TypedValue::Date(millis) => {
+        let mut date = ptr::null_mut();
+        check_status!(sys::napi_create_date(env, millis, &mut date))?;
+        Ok(date)
+      }
+      TypedValue::Bytes(bytes) => Buffer::to_napi_value(env, Buffer::from(bytes)),
+      TypedValue::Json(json) => Value::to_napi_value(env, json),
+      TypedValue::Array(values) => {
+        let mut array = ptr::null_mut();
+        check_status!(sys::napi_create_array_with_length(env, values.len(), &mut array))?;
+        for (index, value) in values.into_iter().enumerate() {
+          let value = TypedValue::to_napi_value(env, value)?;
+          check_status!(sys::napi_set_element(env, array, index as u32, value))?;
+        }
+        Ok(array)
+      }
+      TypedValue::Object(entries) => {
+        let mut object = ptr::null_mut();
+        check_status!(sys::napi_create_object(env, &mut object))?;
+        for (key, value) in entries {
+          let key = String::to_napi_value(env, key)?;
+          let value = TypedValue::to_napi_value(env, value)?;
+          check_status!(sys::napi_set_property(env, object, key, value))?;
+        }
+        Ok(object)
+      }
+    }
+  }
+}
+
+pub fn invalid_field(column: &str, type_name: &str) -> RuntimeError {
+  RuntimeError::Error(format!("Can't decode column {} of type {}.", column, type_name))
+}
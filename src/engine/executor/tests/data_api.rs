@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod data_api_tests {
+  use crate::engine::executor::{
+    data_api::{credentials, sdk_config},
+    error::RuntimeError,
+    ConnectionOptions,
+  };
+
+  #[test]
+  fn test_credentials() {
+    let options = ConnectionOptions {
+      access_key_id: Some("key".to_string()),
+      secret_access_key: Some("secret".to_string()),
+      session_token: Some("token".to_string()),
+      ..Default::default()
+    };
+
+    let static_credentials = credentials(&options).unwrap().unwrap();
+    assert_eq!(static_credentials.access_key_id(), "key");
+    assert_eq!(static_credentials.secret_access_key(), "secret");
+    assert_eq!(static_credentials.session_token(), Some("token"));
+
+    assert!(credentials(&ConnectionOptions::default()).unwrap().is_none());
+  }
+
+  #[test]
+  fn test_partial_credentials() {
+    for options in [
+      ConnectionOptions {
+        access_key_id: Some("key".to_string()),
+        ..Default::default()
+      },
+      ConnectionOptions {
+        secret_access_key: Some("secret".to_string()),
+        ..Default::default()
+      },
+    ] {
+      assert!(matches!(credentials(&options), Err(RuntimeError::InvalidCredentials(_))));
+    }
+  }
+
+  #[tokio::test]
+  async fn test_sdk_config() {
+    let options = ConnectionOptions {
+      region: Some("us-east-1".to_string()),
+      endpoint: Some("http://localhost:8080".to_string()),
+      access_key_id: Some("key".to_string()),
+      secret_access_key: Some("secret".to_string()),
+      ..Default::default()
+    };
+
+    let config = sdk_config(&options).await.unwrap();
+    assert_eq!(config.region().map(|region| region.as_ref()), Some("us-east-1"));
+    assert_eq!(config.endpoint_url(), Some("http://localhost:8080"));
+    assert!(config.credentials_provider().is_some());
+
+    let options = ConnectionOptions {
+      secret_access_key: None,
+      ..options
+    };
+    assert!(matches!(sdk_config(&options).await, Err(RuntimeError::InvalidCredentials(_))));
+  }
+}
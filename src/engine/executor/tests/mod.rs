@@ -0,0 +1,6 @@
+pub mod clients;
+pub mod data_api;
+pub mod postgres;
+pub mod postgres_tls;
+pub mod postgres_values;
+pub mod records;
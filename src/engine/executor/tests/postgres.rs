@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod postgres_tests {
+  use crate::engine::executor::postgres::{to_positional_placeholders, with_text_columns};
+  use tokio_postgres::types::Type;
+
+  #[test]
+  fn test_to_positional_placeholders() {
+    assert_eq!(
+      to_positional_placeholders("SELECT * FROM users WHERE id = :p1 AND name = CAST(:p2 AS text)", 2),
+      "SELECT * FROM users WHERE id = $1 AND name = CAST($2 AS text)"
+    );
+    assert_eq!(
+      to_positional_placeholders("SELECT :p10, :p1 FROM users", 10),
+      "SELECT $10, $1 FROM users"
+    );
+  }
+
+  #[test]
+  fn test_to_positional_placeholders_skips_literals() {
+    assert_eq!(
+      to_positional_placeholders("SELECT ':p1', 'it''s :p1', E'\\' :p1', \":p1\" FROM users WHERE id = :p1", 1),
+      "SELECT ':p1', 'it''s :p1', E'\\' :p1', \":p1\" FROM users WHERE id = $1"
+    );
+    assert_eq!(
+      to_positional_placeholders(
+        "SELECT $$ :p1 $$, $tag$ :p1 $tag$ -- :p1\nFROM users /* :p1 */ WHERE id = :p1",
+        1
+      ),
+      "SELECT $$ :p1 $$, $tag$ :p1 $tag$ -- :p1\nFROM users /* :p1 */ WHERE id = $1"
+    );
+  }
+
+  #[test]
+  fn test_to_positional_placeholders_skips_other_colons() {
+    assert_eq!(
+      to_positional_placeholders("SELECT id::text, :p2, :p1x, :p3 FROM users WHERE id = :p1", 2),
+      "SELECT id::text, $2, :p1x, :p3 FROM users WHERE id = $1"
+    );
+    assert_eq!(to_positional_placeholders("SELECT ':p1'", 0), "SELECT ':p1'");
+  }
+
+  #[test]
+  fn test_with_text_columns() {
+    assert_eq!(
+      with_text_columns(
+        "SELECT u.id, s.id, u.address, u.range FROM users u JOIN sessions s ON s.user_id = u.id",
+        &["id", "id", "address", "range"],
+        &[Type::INT8, Type::UUID, Type::INET, Type::INT4_RANGE],
+      ),
+      "WITH auro_rows(auro_1, auro_2, auro_3, auro_4) AS (\
+       SELECT u.id, s.id, u.address, u.range FROM users u JOIN sessions s ON s.user_id = u.id) \
+       SELECT auro_1 AS \"id\", auro_2 AS \"id\", abbrev(auro_3) AS \"address\", auro_4::text AS \"range\" FROM auro_rows"
+    );
+  }
+}
@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod postgres_tls_tests {
+  use crate::engine::executor::{
+    error::RuntimeError,
+    postgres_tls::{connector, TlsMode},
+  };
+  use tokio_postgres::config::SslMode;
+
+  #[test]
+  fn test_parse_tls_mode() {
+    assert_eq!(TlsMode::parse("verify-full").unwrap(), TlsMode::VerifyFull);
+    assert_eq!(TlsMode::parse("require").unwrap().ssl_mode(), SslMode::Require);
+    assert_eq!(TlsMode::VerifyFull.ssl_mode(), SslMode::Require);
+
+    match TlsMode::parse("verify-ca") {
+      Err(RuntimeError::Error(message)) => assert_eq!(
+        message,
+        "Unknown sslMode verify-ca, expected disable, prefer, require or verify-full."
+      ),
+      result => panic!("Unexpected result {:?}", result),
+    }
+  }
+
+  #[test]
+  fn test_tls_mode_from_url() {
+    let mode = |url: &str| TlsMode::from_config(&url.parse().unwrap());
+
+    assert_eq!(mode("postgres://user@localhost/db"), TlsMode::Prefer);
+    assert_eq!(mode("postgres://user@localhost/db?sslmode=disable"), TlsMode::Disable);
+    assert_eq!(mode("postgres://user@localhost/db?sslmode=require"), TlsMode::Require);
+  }
+
+  #[test]
+  fn test_connector_with_missing_root_cert() {
+    assert!(connector(TlsMode::Require, Some("/missing/root.crt")).is_ok());
+    assert!(matches!(
+      connector(TlsMode::VerifyFull, Some("/missing/root.crt")),
+      Err(RuntimeError::Error(_))
+    ));
+  }
+}
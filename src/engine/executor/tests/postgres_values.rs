@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod postgres_values_tests {
+  use crate::engine::executor::{
+    postgres_values::{is_readable, ColumnValue},
+    records::TypedValue,
+  };
+  use tokio_postgres::types::{FromSql, Type};
+
+  fn decode(ty: &Type, raw: &[u8]) -> ColumnValue {
+    ColumnValue::from_sql(ty, raw).unwrap()
+  }
+
+  fn text(value: &str) -> ColumnValue {
+    ColumnValue::Text(value.to_string())
+  }
+
+  fn json(value: &ColumnValue, ty: &Type) -> String {
+    let mut json = String::new();
+    value.write_json(ty, &mut json);
+    json
+  }
+
+  /// Builds a `numeric` from its digits in base 10000.
+  fn numeric(weight: i16, sign: u16, scale: i16, digits: &[i16]) -> Vec<u8> {
+    let mut raw = Vec::new();
+    for part in [digits.len() as i16, weight, sign as i16, scale].iter().chain(digits) {
+      raw.extend_from_slice(&part.to_be_bytes());
+    }
+    raw
+  }
+
+  #[test]
+  fn test_decode_scalars() {
+    assert_eq!(decode(&Type::BOOL, &[1]), text("t"));
+    assert_eq!(decode(&Type::INT4, &42i32.to_be_bytes()), text("42"));
+    assert_eq!(decode(&Type::INT8, &(-7i64).to_be_bytes()), text("-7"));
+    assert_eq!(decode(&Type::FLOAT8, &1.5f64.to_be_bytes()), text("1.5"));
+    assert_eq!(decode(&Type::FLOAT8, &f64::NAN.to_be_bytes()), text("NaN"));
+    assert_eq!(decode(&Type::TEXT, b"hello"), text("hello"));
+    assert_eq!(decode(&Type::JSONB, b"\x01{\"a\":1}"), text("{\"a\":1}"));
+    assert_eq!(decode(&Type::BYTEA, &[0xde, 0xad]), text("\\xdead"));
+    assert_eq!(
+      decode(&Type::UUID, &(0..16).collect::<Vec<u8>>()),
+      text("00010203-0405-0607-0809-0a0b0c0d0e0f")
+    );
+  }
+
+  #[test]
+  fn test_decode_numeric() {
+    assert_eq!(
+      decode(&Type::NUMERIC, &numeric(1, 0, 2, &[12, 3456, 7800])),
+      text("123456.78")
+    );
+    assert_eq!(decode(&Type::NUMERIC, &numeric(-1, 0x4000, 3, &[50])), text("-0.005"));
+    assert_eq!(decode(&Type::NUMERIC, &numeric(2, 0, 0, &[1])), text("100000000"));
+    assert_eq!(decode(&Type::NUMERIC, &numeric(0, 0, 0, &[])), text("0"));
+    assert_eq!(decode(&Type::NUMERIC, &numeric(0, 0xC000, 0, &[])), text("NaN"));
+  }
+
+  #[test]
+  fn test_decode_dates_and_times() {
+    assert_eq!(decode(&Type::DATE, &8401i32.to_be_bytes()), text("2023-01-01"));
+    assert_eq!(decode(&Type::DATE, &(-730_120i32).to_be_bytes()), text("0001-12-31 BC"));
+
+    let micros = 8401i64 * 86_400_000_000 + 36_000_500_000;
+    assert_eq!(decode(&Type::TIMESTAMP, &micros.to_be_bytes()), text("2023-01-01T10:00:00.5"));
+    assert_eq!(
+      decode(&Type::TIMESTAMPTZ, &micros.to_be_bytes()),
+      text("2023-01-01T10:00:00.5+00:00")
+    );
+    assert_eq!(decode(&Type::TIMESTAMPTZ, &i64::MAX.to_be_bytes()), text("infinity"));
+
+    let mut timetz = 3_723_000_000i64.to_be_bytes().to_vec();
+    timetz.extend_from_slice(&(-19_800i32).to_be_bytes());
+    assert_eq!(decode(&Type::TIMETZ, &timetz), text("01:02:03+05:30"));
+
+    let mut interval = (-3_723_000_000i64).to_be_bytes().to_vec();
+    interval.extend_from_slice(&3i32.to_be_bytes());
+    interval.extend_from_slice(&14i32.to_be_bytes());
+    assert_eq!(decode(&Type::INTERVAL, &interval), text("1 year 2 mons 3 days -01:02:03"));
+  }
+
+  #[test]
+  fn test_decode_arrays() {
+    let mut raw = Vec::new();
+    for part in [2i32, 1, Type::INT4.oid() as i32, 2, 1, 2, 1] {
+      raw.extend_from_slice(&part.to_be_bytes());
+    }
+    for value in [Some(1i32), None, Some(3), Some(4)] {
+      match value {
+        Some(value) => {
+          raw.extend_from_slice(&4i32.to_be_bytes());
+          raw.extend_from_slice(&value.to_be_bytes());
+        }
+        None => raw.extend_from_slice(&(-1i32).to_be_bytes()),
+      }
+    }
+
+    let value = decode(&Type::INT4_ARRAY, &raw);
+    assert_eq!(
+      value,
+      ColumnValue::Array(vec![
+        ColumnValue::Array(vec![text("1"), ColumnValue::Null]),
+        ColumnValue::Array(vec![text("3"), text("4")]),
+      ])
+    );
+    assert_eq!(json(&value, &Type::INT4_ARRAY), "[[1,null],[3,4]]");
+    assert_eq!(
+      value.typed(&Type::INT4_ARRAY),
+      TypedValue::Array(vec![
+        TypedValue::Array(vec![TypedValue::Number(1.0), TypedValue::Null]),
+        TypedValue::Array(vec![TypedValue::Number(3.0), TypedValue::Number(4.0)]),
+      ])
+    );
+  }
+
+  #[test]
+  fn test_decode_unsupported_type() {
+    assert!(ColumnValue::from_sql(&Type::POINT, &[0; 16]).is_err());
+  }
+
+  #[test]
+  fn test_is_readable() {
+    assert!(is_readable(&Type::INT8));
+    assert!(is_readable(&Type::TIMESTAMPTZ_ARRAY));
+    assert!(!is_readable(&Type::INET));
+    assert!(!is_readable(&Type::MONEY));
+    assert!(!is_readable(&Type::INT4_RANGE));
+    assert!(!is_readable(&Type::POINT_ARRAY));
+  }
+
+  #[test]
+  fn test_write_json() {
+    assert_eq!(json(&text("t"), &Type::BOOL), "true");
+    assert_eq!(
+      json(&text("12345678901234567890.12"), &Type::NUMERIC),
+      "12345678901234567890.12"
+    );
+    assert_eq!(json(&text("NaN"), &Type::NUMERIC), "\"NaN\"");
+    assert_eq!(json(&text("{\"a\":1}"), &Type::JSONB), "{\"a\":1}");
+    assert_eq!(json(&text("say \"hi\""), &Type::TEXT), "\"say \\\"hi\\\"\"");
+    assert_eq!(json(&ColumnValue::Null, &Type::TEXT), "null");
+  }
+}
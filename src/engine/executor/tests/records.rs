@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod records_tests {
+  use crate::engine::executor::records::{decode_text, parse_timestamp, TypedValue};
+  use serde_json::json;
+
+  #[test]
+  fn test_decode_text() {
+    assert_eq!(decode_text("int8", "9007199254740993"), TypedValue::BigInt(9007199254740993));
+    assert_eq!(decode_text("int4", "42"), TypedValue::Number(42.0));
+    assert_eq!(
+      decode_text("numeric", "12345678901234567890.12"),
+      TypedValue::String("12345678901234567890.12".to_string())
+    );
+    assert_eq!(decode_text("bool", "t"), TypedValue::Boolean(true));
+    assert_eq!(
+      decode_text("jsonb", r#"{"a":[1,2]}"#),
+      TypedValue::Json(json!({ "a": [1, 2] }))
+    );
+    assert_eq!(
+      decode_text("bytea", "\\xdeadbeef"),
+      TypedValue::Bytes(vec![0xde, 0xad, 0xbe, 0xef])
+    );
+    assert_eq!(decode_text("date", "2023-01-01"), TypedValue::Date(1672531200000.0));
+    assert_eq!(decode_text("order_status", "paid"), TypedValue::String("paid".to_string()));
+
+    // Values that don't parse are kept as they are.
+    assert_eq!(
+      decode_text("timestamptz", "infinity"),
+      TypedValue::String("infinity".to_string())
+    );
+    assert_eq!(decode_text("int8", "many"), TypedValue::String("many".to_string()));
+    assert_eq!(decode_text("bytea", "\\xabc"), TypedValue::String("\\xabc".to_string()));
+  }
+
+  #[test]
+  fn test_decode_text_arrays() {
+    assert_eq!(
+      decode_text("_text", r#"{plain,"with \"quotes\", and comma",NULL}"#),
+      TypedValue::Array(vec![
+        TypedValue::String("plain".to_string()),
+        TypedValue::String("with \"quotes\", and comma".to_string()),
+        TypedValue::Null,
+      ])
+    );
+    assert_eq!(
+      decode_text("_int8", "{{1,2},{3,4}}"),
+      TypedValue::Array(vec![
+        TypedValue::Array(vec![TypedValue::BigInt(1), TypedValue::BigInt(2)]),
+        TypedValue::Array(vec![TypedValue::BigInt(3), TypedValue::BigInt(4)]),
+      ])
+    );
+    assert_eq!(
+      decode_text("_date", r#"["2023-01-01", null]"#),
+      TypedValue::Array(vec![TypedValue::Date(1672531200000.0), TypedValue::Null])
+    );
+    assert_eq!(decode_text("_int4", "{}"), TypedValue::Array(vec![]));
+    assert_eq!(decode_text("_int4", "{1,2"), TypedValue::String("{1,2".to_string()));
+  }
+
+  #[test]
+  fn test_parse_timestamp() {
+    assert_eq!(parse_timestamp("1970-01-01 00:00:00"), Some(0.0));
+    assert_eq!(parse_timestamp("1969-12-31"), Some(-86400000.0));
+    assert_eq!(parse_timestamp("2023-01-01T10:00:00.5+02"), Some(1672560000500.0));
+    assert_eq!(parse_timestamp("2023-01-01 10:00:00.250Z"), Some(1672567200250.0));
+    assert_eq!(parse_timestamp("2023-01-01T05:30:00-05:30"), Some(1672570800000.0));
+    assert_eq!(parse_timestamp("10:00:00"), None);
+  }
+}
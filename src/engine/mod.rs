@@ -1,28 +1,183 @@
-use self::executor::error::RuntimeError;
+use self::executor::{
+  driver::Driver,
+  error::RuntimeError,
+  records::{TypedRecords, TypedValue},
+  utils::format_error_message,
+};
 use crate::{
-  engine::{builder::build, executor::execute_statement},
+  engine::builder::build,
   parser::{self, parsed},
 };
-use aws_sdk_rdsdata::client::fluent_builders::ExecuteStatement;
+use std::future::Future;
 
+mod aggregate;
 pub mod builder;
 pub mod executor;
+mod pagination;
+mod relations;
+mod tests;
+
+pub(crate) use pagination::decode_page_cursor;
+
+pub(crate) async fn execute(
+  statement: &parsed::ParsedStatement,
+  driver: &dyn Driver,
+  transaction_id: Option<String>,
+) -> Result<Option<String>, RuntimeError> {
+  let statement = &relations::resolve(statement, driver).await?;
+
+  if statement.query.options.paginate {
+    return pagination::paginate(statement, driver, transaction_id).await;
+  }
 
-pub(crate) async fn execute(statement: &parsed::ParsedStatement, client: &ExecuteStatement) -> Result<Option<String>, RuntimeError> {
-  let query_string = match build(&statement) {
-    Ok(query_string) => query_string,
+  let query = match build(statement) {
+    Ok(query) => query,
     Err(err) => return Err(RuntimeError::Error(err.to_string())),
   };
 
-  let result = match execute_statement(query_string, client).await {
-    Ok(result) => result,
+  let formatted_records = match driver.execute(&query, transaction_id).await {
+    Ok(formatted_records) => formatted_records,
+    Err(err) => return Err(RuntimeError::Error(err.to_string())),
+  };
+
+  formatted_records.map(|formatted_records| format_records(statement, &formatted_records)).transpose()
+}
+
+/// Camelcases the returned rows, or nests them by aggregate for `aggregate` and `groupBy` statements.
+fn format_records(statement: &parsed::ParsedStatement, formatted_records: &str) -> Result<String, RuntimeError> {
+  match statement.operation {
+    parsed::ParsedOperation::Aggregate => aggregate::nest(formatted_records),
+    parsed::ParsedOperation::GroupBy => aggregate::nest_groups(formatted_records),
+    _ => Ok(parser::utils::snake_to_camel_case(&relations::decode(statement, formatted_records)?)),
+  }
+}
+
+/// Executes the statement like [`execute`], but returns the rows decoded by the types of their
+/// columns instead of a JSON string.
+pub(crate) async fn execute_typed(
+  statement: &parsed::ParsedStatement,
+  driver: &dyn Driver,
+  transaction_id: Option<String>,
+) -> Result<Option<TypedValue>, RuntimeError> {
+  let statement = &relations::resolve(statement, driver).await?;
+
+  if statement.query.options.paginate {
+    return Err(RuntimeError::Error("Typed results aren't available for paginated queries.".to_string()));
+  }
+
+  let query = match build(statement) {
+    Ok(query) => query,
     Err(err) => return Err(RuntimeError::Error(err.to_string())),
   };
 
-  let formatted_records = match result.formatted_records() {
-    Some(formatted_records) => Some(parser::utils::snake_to_camel_case(formatted_records)),
-    None => None,
+  let records = driver.execute_typed(&query, transaction_id).await?;
+
+  records.map(|records| shape_records(statement, records)).transpose()
+}
+
+/// Shapes typed rows the way [`format_records`] shapes JSON ones. Included relations are selected as
+/// text, so they are parsed by their label like [`relations::decode`] does, and related rows keep the
+/// types JSON gives them.
+fn shape_records(statement: &parsed::ParsedStatement, records: TypedRecords) -> Result<TypedValue, RuntimeError> {
+  let relations: Vec<&str> = match &statement.query.options.include {
+    Some(include) => include.relations.iter().map(|relation| relation.name.as_str()).collect(),
+    None => vec![],
   };
+  let nested = matches!(
+    statement.operation,
+    parsed::ParsedOperation::Aggregate | parsed::ParsedOperation::GroupBy
+  );
+
+  let mut rows = Vec::with_capacity(records.rows.len());
+  for row in records.rows {
+    let mut object = Vec::with_capacity(row.len());
+
+    for (label, value) in records.columns.iter().zip(row) {
+      if nested {
+        aggregate::nest_typed(&mut object, label, value);
+        continue;
+      }
+
+      let relation = relations.contains(&label.as_str());
+      let value = match value {
+        TypedValue::String(text) if relation => match serde_json::from_str(&text) {
+          Ok(related) => TypedValue::Json(relations::camel_case_keys(related)),
+          Err(err) => return Err(RuntimeError::Error(err.to_string())),
+        },
+        TypedValue::Json(json) if relation => TypedValue::Json(relations::camel_case_keys(json)),
+        value => value,
+      };
+
+      object.push((parser::utils::snake_to_camel_case(label), value));
+    }
+
+    rows.push(TypedValue::Object(object));
+  }
+
+  match statement.operation {
+    parsed::ParsedOperation::Aggregate => Ok(rows.into_iter().next().unwrap_or(TypedValue::Object(vec![]))),
+    _ => Ok(TypedValue::Array(rows)),
+  }
+}
+
+/// Executes every statement in order inside a single transaction. The first failing statement
+/// rolls the whole transaction back and its index is reported in the returned error.
+pub(crate) async fn execute_batch(
+  statements: &[parsed::ParsedStatement],
+  driver: &dyn Driver,
+) -> Result<Vec<Option<String>>, RuntimeError> {
+  let mut resolved = Vec::with_capacity(statements.len());
+  let mut queries = Vec::with_capacity(statements.len());
+
+  for (index, statement) in statements.iter().enumerate() {
+    let statement = match relations::resolve(statement, driver).await {
+      Ok(statement) => statement,
+      Err(err) => return Err(RuntimeError::StatementFailed(index, err.to_string())),
+    };
+
+    match build(&statement) {
+      Ok(query) => queries.push(query),
+      Err(err) => return Err(RuntimeError::StatementFailed(index, err.to_string())),
+    }
+
+    resolved.push(statement);
+  }
+
+  let results = driver.execute_batch(&queries).await?;
+
+  results
+    .into_iter()
+    .zip(&resolved)
+    .map(|(formatted_records, statement)| {
+      formatted_records
+        .map(|formatted_records| format_records(statement, &formatted_records))
+        .transpose()
+    })
+    .collect()
+}
+
+/// Runs `callback` with the id of a transaction opened for it. The transaction is committed when the
+/// callback succeeds and rolled back when it fails, keeping the callback's error even when rolling back
+/// fails as well.
+pub(crate) async fn interactive_transaction<T, F, Fut>(driver: &dyn Driver, callback: F) -> Result<T, RuntimeError>
+where
+  F: FnOnce(String) -> Fut,
+  Fut: Future<Output = Result<T, String>>,
+{
+  let transaction_id = driver.begin_transaction().await?;
 
-  Ok(formatted_records)
+  match callback(transaction_id.clone()).await {
+    Ok(value) => {
+      driver.commit_transaction(transaction_id).await?;
+      Ok(value)
+    }
+    Err(err) => match driver.rollback_transaction(transaction_id).await {
+      Ok(_) => Err(RuntimeError::Error(err)),
+      Err(rollback_err) => Err(RuntimeError::Error(format!(
+        "{} Rolling back failed as well: {}",
+        err,
+        format_error_message(&rollback_err.to_string())
+      ))),
+    },
+  }
 }
@@ -0,0 +1,356 @@
+use super::{
+  builder::{
+    build,
+    options::{is_backwards, ordering_columns, output_column},
+    parameters::BuiltQuery,
+  },
+  executor::{driver::Driver, error::RuntimeError},
+  relations,
+};
+use crate::parser::{
+  self,
+  parsed::{ParsedArgument, ParsedOperation, ParsedStatement, ParsedValue, QueryOptions},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Runs a paginated `findMany` and wraps its rows as `{ data, nextCursor, prevCursor, hasMore }`.
+///
+/// One row more than requested is fetched to learn whether another page follows. Cursors are
+/// opaque tokens holding the ordering columns of the first or last row with their types, the
+/// ordering they were created for and the paging direction. Columns whose type isn't known from the
+/// cursor the query already had are looked up in the database, once there are rows to page from.
+pub(crate) async fn paginate(
+  statement: &ParsedStatement,
+  driver: &dyn Driver,
+  transaction_id: Option<String>,
+) -> Result<Option<String>, RuntimeError> {
+  let statement = prepare(statement)?;
+
+  let query = match build(&statement) {
+    Ok(query) => query,
+    Err(err) => return Err(RuntimeError::Error(err.to_string())),
+  };
+
+  let formatted_records = driver.execute(&query, transaction_id.clone()).await?;
+  let formatted_records = relations::decode(&statement, formatted_records.as_deref().unwrap_or("[]"))?;
+
+  let options = &statement.query.options;
+  let untyped = ordering_columns(options)
+    .iter()
+    .any(|(column, _)| cursor_type(options, column).is_none());
+
+  let types = match untyped && formatted_records != "[]" {
+    true => column_types(&query, driver, transaction_id).await?,
+    false => HashMap::new(),
+  };
+
+  page(&statement, &formatted_records, &types).map(Some)
+}
+
+/// Looks up the database type of every column the query returns by running it again for no rows.
+/// JSON records hold timestamps, uuids and numerics as plain strings, which can't be compared with
+/// their columns again unless they are cast back.
+async fn column_types(
+  query: &BuiltQuery,
+  driver: &dyn Driver,
+  transaction_id: Option<String>,
+) -> Result<HashMap<String, String>, RuntimeError> {
+  let query = BuiltQuery::new(
+    format!("SELECT * FROM ({}) AS auro_types LIMIT 0", query.sql.trim().trim_end_matches(';')),
+    query.parameters.clone(),
+  );
+
+  let records = driver.execute_typed(&query, transaction_id).await?.unwrap_or_default();
+
+  Ok(records.columns.into_iter().zip(records.types).collect())
+}
+
+/// Replaces the page cursor of the options with the keyset it encodes and points `take` in the
+/// direction the cursor pages in. Runs before a statement is validated and typed against models,
+/// so the cursor values get the types of their columns like any other value.
+pub(crate) fn decode_page_cursor(options: &mut QueryOptions) -> Result<(), RuntimeError> {
+  let token = match options.page_cursor.take() {
+    Some(token) => token,
+    None => return Ok(()),
+  };
+
+  let page_cursor = decode_cursor(&token)?;
+  options.cursor = Some(page_cursor.cursor);
+
+  if ordering_columns(options) != page_cursor.columns {
+    return Err(RuntimeError::Error(
+      "The page cursor was created for a different orderBy.".to_string(),
+    ));
+  }
+
+  options.take = options.take.map(|take| directed(take.unsigned_abs(), page_cursor.backwards));
+
+  Ok(())
+}
+
+/// Replaces the page cursor with the keyset it encodes and asks for one extra row.
+pub(crate) fn prepare(statement: &ParsedStatement) -> Result<ParsedStatement, RuntimeError> {
+  if statement.operation != ParsedOperation::FindMany {
+    return Err(RuntimeError::Error("Pagination is only supported by findMany.".to_string()));
+  }
+
+  let mut statement = statement.clone();
+  let options = &mut statement.query.options;
+  decode_page_cursor(options)?;
+
+  let take = match options.take {
+    Some(take) if take != 0 => take,
+    _ => return Err(RuntimeError::Error("Pagination needs a non-zero take.".to_string())),
+  };
+
+  options.take = Some(directed(take.unsigned_abs().saturating_add(1), take < 0));
+
+  if ordering_columns(options).is_empty() {
+    return Err(RuntimeError::Error("Pagination needs an orderBy or a cursor.".to_string()));
+  }
+
+  Ok(statement)
+}
+
+/// Number of rows to take, counting backwards when `backwards` is set.
+fn directed(rows: u64, backwards: bool) -> i64 {
+  let rows = rows.min(i64::MAX as u64) as i64;
+
+  match backwards {
+    true => -rows,
+    false => rows,
+  }
+}
+
+/// Drops the extra row fetched by [`prepare`] and derives the cursors of the neighbouring pages.
+/// `types` maps output columns to their database types, see [`encode_cursor`].
+pub(crate) fn page(
+  statement: &ParsedStatement,
+  formatted_records: &str,
+  types: &HashMap<String, String>,
+) -> Result<String, RuntimeError> {
+  let options = &statement.query.options;
+  let backwards = is_backwards(options);
+  let take = options.take.unwrap_or_default().unsigned_abs().saturating_sub(1) as usize;
+
+  let mut rows = match serde_json::from_str::<Value>(formatted_records) {
+    Ok(Value::Array(rows)) => rows,
+    _ => return Err(RuntimeError::Error("Records are not a JSON array.".to_string())),
+  };
+
+  // Backwards pages come back in the requested order, so the extra row is the first one.
+  let has_more = rows.len() > take;
+  if has_more {
+    match backwards {
+      true => drop(rows.drain(..rows.len() - take)),
+      false => rows.truncate(take),
+    }
+  }
+
+  let from_cursor = options.cursor.is_some();
+
+  let (next_row, prev_row) = match backwards {
+    true => (rows.last().filter(|_| from_cursor), rows.first().filter(|_| has_more)),
+    false => (rows.last().filter(|_| has_more), rows.first().filter(|_| from_cursor)),
+  };
+
+  let next_cursor = next_row.map(|row| encode_cursor(row, options, false, types)).transpose()?;
+  let prev_cursor = prev_row.map(|row| encode_cursor(row, options, true, types)).transpose()?;
+
+  let data = parser::utils::snake_to_camel_case(&Value::Array(rows).to_string());
+
+  Ok(format!(
+    r#"{{"data":{},"nextCursor":{},"prevCursor":{},"hasMore":{}}}"#,
+    data,
+    json!(next_cursor),
+    json!(prev_cursor),
+    has_more
+  ))
+}
+
+/// The keyset a page cursor token holds, together with the columns and directions of the ordering
+/// it was created for.
+#[derive(Debug, PartialEq)]
+pub(crate) struct PageCursor {
+  pub cursor: Vec<ParsedArgument>,
+  pub columns: Vec<(String, bool)>,
+  pub backwards: bool,
+}
+
+/// Encodes the ordering columns of `row` into a cursor token. Every value keeps its type: the type
+/// of the cursor value the query already had for its column, or else the one its JSON value has.
+/// Strings of columns found in `types` are cast back to the column's database type.
+pub(crate) fn encode_cursor(
+  row: &Value,
+  options: &QueryOptions,
+  backwards: bool,
+  types: &HashMap<String, String>,
+) -> Result<String, RuntimeError> {
+  let cursor = ordering_columns(options)
+    .into_iter()
+    .map(|(column, descending)| {
+      let value = match row.get(output_column(&column)) {
+        Some(value) => value,
+        None => {
+          return Err(RuntimeError::Error(format!(
+            "Column {} is needed for the page cursor but was not selected.",
+            column
+          )))
+        }
+      };
+
+      let current_type = cursor_type(options, &column);
+      let column_type = types
+        .get(output_column(&column))
+        .filter(|column_type| is_cast(column_type))
+        .map(|column_type| format!("string::{}", column_type));
+
+      let (value, value_type) = match value {
+        Value::Null => (Value::Null, "null".to_string()),
+        Value::String(text) => (
+          json!(text),
+          current_type.or(column_type).unwrap_or_else(|| "string".to_string()),
+        ),
+        value => (json!(value.to_string()), current_type.unwrap_or_else(|| json_type(value).to_string())),
+      };
+
+      Ok(json!({ "column": column, "descending": descending, "value": value, "type": value_type }))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let token = json!({ "backwards": backwards, "cursor": cursor });
+
+  Ok(URL_SAFE_NO_PAD.encode(token.to_string()))
+}
+
+/// Type of the cursor value the query already has for `column`.
+fn cursor_type(options: &QueryOptions, column: &str) -> Option<String> {
+  options
+    .cursor
+    .iter()
+    .flatten()
+    .find(|argument| argument.name == column)
+    .and_then(|argument| argument.value.as_ref())
+    .and_then(value_type)
+}
+
+pub(crate) fn decode_cursor(token: &str) -> Result<PageCursor, RuntimeError> {
+  let invalid = || RuntimeError::Error("Invalid page cursor.".to_string());
+
+  let bytes = URL_SAFE_NO_PAD.decode(token).map_err(|_| invalid())?;
+  let token = serde_json::from_slice::<Value>(&bytes).map_err(|_| invalid())?;
+
+  let backwards = token["backwards"].as_bool().ok_or_else(invalid)?;
+  let mut cursor = Vec::new();
+  let mut columns = Vec::new();
+
+  for entry in token["cursor"].as_array().ok_or_else(invalid)? {
+    // Column names end up in the SQL text, so a tampered token must not smuggle anything else in.
+    let name = match entry["column"].as_str() {
+      Some(name) if is_column_name(name) => name,
+      _ => return Err(invalid()),
+    };
+
+    let value = match (&entry["value"], entry["type"].as_str()) {
+      (Value::Null, Some("null")) => ParsedValue::Null,
+      (Value::String(text), Some(value_type)) => typed_value(value_type, text.to_string()).ok_or_else(invalid)?,
+      _ => return Err(invalid()),
+    };
+
+    columns.push((name.to_string(), entry["descending"].as_bool().ok_or_else(invalid)?));
+    cursor.push(ParsedArgument {
+      name: name.to_string(),
+      value: Some(value),
+      ..Default::default()
+    });
+  }
+
+  Ok(PageCursor {
+    cursor,
+    columns,
+    backwards,
+  })
+}
+
+/// A column, optionally qualified by its table.
+fn is_column_name(name: &str) -> bool {
+  let parts = name.split('.').collect::<Vec<_>>();
+
+  parts.len() <= 2
+    && parts
+      .iter()
+      .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+}
+
+fn json_type(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "boolean",
+    Value::Number(number) if number.is_f64() => "float",
+    Value::Number(_) => "number",
+    Value::String(_) => "string",
+    Value::Array(_) | Value::Object(_) => "json",
+  }
+}
+
+/// Names the type of a cursor value in a token, `None` for values a row can't hold as one.
+fn value_type(value: &ParsedValue) -> Option<String> {
+  let name = match value {
+    ParsedValue::String(_) => "string",
+    ParsedValue::Number(_) => "number",
+    ParsedValue::Boolean(_) => "boolean",
+    ParsedValue::Float(_) => "float",
+    ParsedValue::Date(_) => "date",
+    ParsedValue::DateTime(_) => "datetime",
+    ParsedValue::TimestampTz(_) => "timestamptz",
+    ParsedValue::Uuid(_) => "uuid",
+    ParsedValue::Json(_) => "json",
+    ParsedValue::Jsonb(_) => "jsonb",
+    ParsedValue::Decimal(_) => "decimal",
+    ParsedValue::Bytes(_) => "bytes",
+    ParsedValue::Enum(_) => "enum",
+    ParsedValue::Cast(value, cast) => return value_type(value).map(|value_type| format!("{}::{}", value_type, cast)),
+    ParsedValue::Array(_) | ParsedValue::Custom(_) | ParsedValue::Null => return None,
+  };
+
+  Some(name.to_string())
+}
+
+/// A type a value can be cast to. Casts end up in the SQL text as well.
+fn is_cast(cast: &str) -> bool {
+  !cast.is_empty()
+    && cast
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '[' | ']' | ' '))
+}
+
+/// Reads a value back by the type [`value_type`] named.
+fn typed_value(value_type: &str, text: String) -> Option<ParsedValue> {
+  if let Some((value_type, cast)) = value_type.split_once("::") {
+    return match is_cast(cast) {
+      true => Some(ParsedValue::Cast(Box::new(typed_value(value_type, text)?), cast.to_string())),
+      false => None,
+    };
+  }
+
+  let value = match value_type {
+    "string" => ParsedValue::String(text),
+    "number" => ParsedValue::Number(text),
+    "boolean" => ParsedValue::Boolean(text),
+    "float" => ParsedValue::Float(text),
+    "date" => ParsedValue::Date(text),
+    "datetime" => ParsedValue::DateTime(text),
+    "timestamptz" => ParsedValue::TimestampTz(text),
+    "uuid" => ParsedValue::Uuid(text),
+    "json" => ParsedValue::Json(text),
+    "jsonb" => ParsedValue::Jsonb(text),
+    "decimal" => ParsedValue::Decimal(text),
+    "bytes" => ParsedValue::Bytes(text),
+    "enum" => ParsedValue::Enum(text),
+    _ => return None,
+  };
+
+  Some(value)
+}
@@ -0,0 +1,184 @@
+use super::executor::{driver::Driver, error::RuntimeError};
+use crate::parser::{
+  self,
+  parsed::{FieldName, ParsedArgument, ParsedFilter, ParsedStatement},
+};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Replaces the text of every included relation column with the JSON it holds, so each row
+/// carries its related rows as nested arrays and objects. Rows are returned unchanged when the
+/// statement includes no relations.
+pub(crate) fn decode(statement: &ParsedStatement, formatted_records: &str) -> Result<String, RuntimeError> {
+  let relations = match &statement.query.options.include {
+    Some(include) if !include.relations.is_empty() => &include.relations,
+    _ => return Ok(formatted_records.to_string()),
+  };
+
+  let mut rows: Vec<Value> = match serde_json::from_str(formatted_records) {
+    Ok(rows) => rows,
+    Err(err) => return Err(RuntimeError::Error(err.to_string())),
+  };
+
+  for row in rows.iter_mut().filter_map(Value::as_object_mut) {
+    for relation in relations {
+      if let Some(Value::String(text)) = row.get(&relation.name) {
+        let related = match serde_json::from_str(text) {
+          Ok(related) => related,
+          Err(err) => return Err(RuntimeError::Error(err.to_string())),
+        };
+
+        row.insert(relation.name.clone(), related);
+      }
+    }
+  }
+
+  Ok(Value::Array(rows).to_string())
+}
+
+/// Camelcases the keys of related rows returned in a typed result, as [`decode`] does for JSON
+/// results.
+pub(crate) fn camel_case_keys(value: Value) -> Value {
+  match value {
+    Value::Object(row) => Value::Object(
+      row
+        .into_iter()
+        .map(|(key, value)| (parser::utils::snake_to_camel_case(&key), camel_case_keys(value)))
+        .collect(),
+    ),
+    Value::Array(rows) => Value::Array(rows.into_iter().map(camel_case_keys).collect()),
+    value => value,
+  }
+}
+
+/// Resolves the relation filters the statement's `include` left open from the foreign keys between
+/// each filter's parent table and the table named like its relation. Keys of the related table
+/// pointing at the parent are preferred over keys of the parent pointing at the related table.
+pub(crate) async fn resolve(statement: &ParsedStatement, driver: &dyn Driver) -> Result<ParsedStatement, RuntimeError> {
+  let mut statement = statement.clone();
+  let schema = statement.query.schema.clone();
+  let table = statement.query.table.clone();
+  let mut foreign_keys: HashMap<String, Value> = HashMap::new();
+
+  while let Some((parent, name)) = statement
+    .query
+    .fields
+    .iter()
+    .filter(|field| field.name == FieldName::Where)
+    .find_map(|field| unresolved(&field.arguments, &table))
+  {
+    for foreign_table in [&name, &parent] {
+      if !foreign_keys.contains_key(foreign_table) {
+        let records = driver.foreign_keys(&schema, foreign_table).await?;
+        let rows = match serde_json::from_str(if records.is_empty() { "[]" } else { &records }) {
+          Ok(rows) => rows,
+          Err(err) => return Err(RuntimeError::Error(err.to_string())),
+        };
+
+        foreign_keys.insert(foreign_table.to_string(), rows);
+      }
+    }
+
+    let keys = match relation_keys(&foreign_keys[&name], &foreign_keys[&parent], &parent, &name) {
+      Some(keys) => keys,
+      None => return Err(RuntimeError::Error(format!("Unknown relation {} of {}.", name, parent))),
+    };
+
+    let related_table = format!("{}.{}", schema, name);
+    for field in statement
+      .query
+      .fields
+      .iter_mut()
+      .filter(|field| field.name == FieldName::Where)
+    {
+      link(&mut field.arguments, &table, (&parent, &name), &related_table, &keys);
+    }
+  }
+
+  Ok(statement)
+}
+
+/// Finds the parent table and name of the first relation filter without a related table. Filters
+/// nested in an unresolved one are skipped, as their parent table isn't known yet.
+pub(crate) fn unresolved(arguments: &[ParsedArgument], parent: &str) -> Option<(String, String)> {
+  arguments.iter().find_map(|argument| match &argument.filter {
+    Some(ParsedFilter::Relation(relation)) => match &relation.table {
+      Some(table) => unresolved(&relation.arguments, unqualified(table)),
+      None => Some((parent.to_string(), argument.name.clone())),
+    },
+    Some(ParsedFilter::And(nested)) | Some(ParsedFilter::Or(nested)) | Some(ParsedFilter::Negate(nested)) => {
+      unresolved(nested, parent)
+    }
+    _ => None,
+  })
+}
+
+/// Sets the related table and keys of every unresolved filter on relation `name` of `parent`.
+pub(crate) fn link(
+  arguments: &mut [ParsedArgument],
+  parent: &str,
+  relation: (&str, &str),
+  related_table: &str,
+  keys: &[(String, String)],
+) {
+  for argument in arguments.iter_mut() {
+    match &mut argument.filter {
+      Some(ParsedFilter::Relation(filter)) => {
+        if filter.table.is_none() && (parent, argument.name.as_str()) == relation {
+          filter.table = Some(related_table.to_string());
+          filter.keys = keys.to_vec();
+        }
+
+        if let Some(table) = filter.table.clone() {
+          link(&mut filter.arguments, unqualified(&table), relation, related_table, keys);
+        }
+      }
+      Some(ParsedFilter::And(nested)) | Some(ParsedFilter::Or(nested)) | Some(ParsedFilter::Negate(nested)) => {
+        link(nested, parent, relation, related_table, keys)
+      }
+      _ => {}
+    }
+  }
+}
+
+/// Pairs each column of the related table with the parent column it matches, from the foreign keys
+/// of the related table and of the parent table as returned by `get_foreign_keys`.
+pub(crate) fn relation_keys(
+  related_foreign_keys: &Value,
+  parent_foreign_keys: &Value,
+  parent: &str,
+  name: &str,
+) -> Option<Vec<(String, String)>> {
+  if let Some(keys) = constraint_keys(related_foreign_keys, parent) {
+    return Some(keys);
+  }
+
+  constraint_keys(parent_foreign_keys, name).map(|keys| keys.into_iter().map(|(key, references)| (references, key)).collect())
+}
+
+/// Columns and the columns they reference of the first foreign key pointing at `foreign_table`, in
+/// the order of the key's columns.
+fn constraint_keys(foreign_keys: &Value, foreign_table: &str) -> Option<Vec<(String, String)>> {
+  let rows = foreign_keys.as_array()?;
+  let text = |row: &Value, column: &str| row.get(column).and_then(Value::as_str).map(str::to_string);
+
+  let constraint = rows
+    .iter()
+    .find(|row| text(row, "foreign_table_name").as_deref() == Some(foreign_table))
+    .and_then(|row| text(row, "constraint_name"))?;
+
+  let mut columns = rows
+    .iter()
+    .filter(|row| text(row, "constraint_name").as_deref() == Some(constraint.as_str()))
+    .collect::<Vec<_>>();
+  columns.sort_by_key(|row| row.get("ordinal_position").and_then(Value::as_i64));
+
+  columns
+    .into_iter()
+    .map(|row| Some((text(row, "column_name")?, text(row, "foreign_column_name")?)))
+    .collect()
+}
+
+fn unqualified(table: &str) -> &str {
+  table.rsplit('.').next().unwrap_or(table)
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod aggregate_tests {
+  use crate::engine::{
+    aggregate::{nest, nest_groups, nest_typed},
+    executor::{error::RuntimeError, records::TypedValue},
+  };
+  use serde_json::{json, Value};
+
+  #[test]
+  fn test_nest() {
+    let records = json!([{
+      "_sum.total_amount": 120,
+      "_avg.total_amount": 40.5,
+      "_count._all": 3,
+      "_countDistinct.customer_id": 2
+    }]);
+
+    let nested: Value = serde_json::from_str(&nest(&records.to_string()).unwrap()).unwrap();
+    assert_eq!(
+      nested,
+      json!({
+        "_sum": { "totalAmount": 120 },
+        "_avg": { "totalAmount": 40.5 },
+        "_count": { "_all": 3 },
+        "_countDistinct": { "customerId": 2 }
+      })
+    );
+  }
+
+  #[test]
+  fn test_nest_typed() {
+    let mut row = vec![];
+    nest_typed(&mut row, "customer_id", TypedValue::Number(7.0));
+    nest_typed(&mut row, "_sum.total_amount", TypedValue::String("120.50".to_string()));
+    nest_typed(&mut row, "_count._all", TypedValue::BigInt(3));
+    nest_typed(&mut row, "_sum.line_count", TypedValue::BigInt(9));
+
+    assert_eq!(
+      row,
+      vec![
+        ("customerId".to_string(), TypedValue::Number(7.0)),
+        (
+          "_sum".to_string(),
+          TypedValue::Object(vec![
+            ("totalAmount".to_string(), TypedValue::String("120.50".to_string())),
+            ("lineCount".to_string(), TypedValue::BigInt(9)),
+          ])
+        ),
+        (
+          "_count".to_string(),
+          TypedValue::Object(vec![("_all".to_string(), TypedValue::BigInt(3))])
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_nest_without_rows() {
+    assert_eq!(nest("[]").unwrap(), "{}");
+  }
+
+  #[test]
+  fn test_nest_with_invalid_records() {
+    assert!(matches!(nest("{}"), Err(RuntimeError::Error(_))));
+  }
+
+  #[test]
+  fn test_nest_groups() {
+    let records = json!([
+      { "customer_id": 1, "_sum.total_amount": 80, "_count._all": 2 },
+      { "customer_id": 2, "_sum.total_amount": 40, "_count._all": 1 }
+    ]);
+
+    let nested: Value = serde_json::from_str(&nest_groups(&records.to_string()).unwrap()).unwrap();
+    assert_eq!(
+      nested,
+      json!([
+        { "customerId": 1, "_sum": { "totalAmount": 80 }, "_count": { "_all": 2 } },
+        { "customerId": 2, "_sum": { "totalAmount": 40 }, "_count": { "_all": 1 } }
+      ])
+    );
+  }
+}
@@ -0,0 +1,4 @@
+pub mod aggregate;
+pub mod pagination;
+pub mod relations;
+pub mod transaction;
@@ -0,0 +1,197 @@
+#[cfg(test)]
+mod pagination_tests {
+  use crate::{
+    engine::{
+      executor::error::RuntimeError,
+      pagination::{decode_cursor, encode_cursor, page, prepare, PageCursor},
+    },
+    parser::parsed::{
+      ParsedArgument, ParsedOperation, ParsedOrderBy, ParsedQuery, ParsedStatement, ParsedValue, QueryOptions,
+    },
+  };
+  use serde_json::{json, Value};
+  use std::collections::HashMap;
+
+  fn statement(take: i64, page_cursor: Option<String>) -> ParsedStatement {
+    ParsedStatement {
+      query: ParsedQuery {
+        table: "events".to_string(),
+        schema: "my_schema".to_string(),
+        fields: vec![],
+        options: QueryOptions {
+          order_by: Some(vec![ParsedOrderBy {
+            field: "createdAt".to_string(),
+            order: "desc".to_string(),
+            nulls: None,
+            table: None,
+            relation: None,
+            aggregate: None,
+          }]),
+          limit: None,
+          offset: None,
+          num_of_rows: None,
+          include: None,
+          group_by: None,
+          take: Some(take),
+          paginate: true,
+          page_cursor,
+          ..Default::default()
+        },
+      },
+      operation: ParsedOperation::FindMany,
+    }
+  }
+
+  fn argument(name: &str, value: ParsedValue) -> ParsedArgument {
+    ParsedArgument {
+      name: name.to_string(),
+      value: Some(value),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn test_cursor_round_trip() {
+    let mut options = statement(2, None).query.options;
+    options.cursor = Some(vec![
+      argument("created_at", ParsedValue::DateTime("2023-05-02 10:00:00".to_string())),
+      argument("id", ParsedValue::Number("7".to_string())),
+    ]);
+
+    // Values keep the types of the cursor the page was fetched with.
+    let row = json!({ "created_at": "2023-05-01 10:00:00.123", "id": 42, "name": "a" });
+    let token = encode_cursor(&row, &options, true, &HashMap::new()).unwrap();
+
+    assert_eq!(
+      decode_cursor(&token).unwrap(),
+      PageCursor {
+        cursor: vec![
+          argument("created_at", ParsedValue::DateTime("2023-05-01 10:00:00.123".to_string())),
+          argument("id", ParsedValue::Number("42".to_string())),
+        ],
+        columns: vec![("created_at".to_string(), true), ("id".to_string(), false)],
+        backwards: true,
+      }
+    );
+  }
+
+  #[test]
+  fn test_cursor_keeps_json_types() {
+    let options = statement(2, None).query.options;
+
+    // Strings are never typed by what they look like.
+    let row = json!({ "created_at": "2023-05-01" });
+    let cursor = decode_cursor(&encode_cursor(&row, &options, false, &HashMap::new()).unwrap()).unwrap().cursor;
+    assert_eq!(cursor, vec![argument("created_at", ParsedValue::String("2023-05-01".to_string()))]);
+
+    let row = json!({ "created_at": null });
+    let cursor = decode_cursor(&encode_cursor(&row, &options, false, &HashMap::new()).unwrap()).unwrap().cursor;
+    assert_eq!(cursor, vec![argument("created_at", ParsedValue::Null)]);
+  }
+
+  #[test]
+  fn test_cursor_casts_strings_to_column_type() {
+    let options = statement(2, None).query.options;
+    let types = HashMap::from([("created_at".to_string(), "timestamptz".to_string())]);
+
+    let row = json!({ "created_at": "2023-05-01 10:00:00+00" });
+    let cursor = decode_cursor(&encode_cursor(&row, &options, false, &types).unwrap()).unwrap().cursor;
+    assert_eq!(
+      cursor,
+      vec![argument(
+        "created_at",
+        ParsedValue::Cast(
+          Box::new(ParsedValue::String("2023-05-01 10:00:00+00".to_string())),
+          "timestamptz".to_string()
+        )
+      )]
+    );
+
+    // Types that can't be cast to are left out rather than breaking the token.
+    let types = HashMap::from([("created_at".to_string(), "\"Odd Type\"".to_string())]);
+    let cursor = decode_cursor(&encode_cursor(&row, &options, false, &types).unwrap()).unwrap().cursor;
+    assert_eq!(cursor, vec![argument("created_at", ParsedValue::String("2023-05-01 10:00:00+00".to_string()))]);
+  }
+
+  #[test]
+  fn test_cursor_with_joined_column() {
+    let mut options = statement(2, None).query.options;
+    options.order_by.as_mut().unwrap()[0].table = Some("authors".to_string());
+
+    // Joined columns come back under their own name.
+    let row = json!({ "created_at": "2023-05-01" });
+    let cursor = decode_cursor(&encode_cursor(&row, &options, false, &HashMap::new()).unwrap()).unwrap();
+    assert_eq!(cursor.columns, vec![("authors.created_at".to_string(), true)]);
+  }
+
+  #[test]
+  fn test_decode_cursor_rejects_tampered_columns() {
+    let mut options = statement(2, None).query.options;
+    options.order_by = None;
+    options.cursor = Some(vec![argument("id; DROP TABLE events", ParsedValue::Number("1".to_string()))]);
+
+    let row = json!({ "id; DROP TABLE events": 1 });
+    let token = encode_cursor(&row, &options, false, &HashMap::new()).unwrap();
+
+    assert!(matches!(decode_cursor(&token), Err(RuntimeError::Error(_))));
+    assert!(matches!(decode_cursor("not a token"), Err(RuntimeError::Error(_))));
+  }
+
+  #[test]
+  fn test_page_cursor_of_other_order() {
+    let row = json!({ "created_at": "2023-05-02" });
+    let token = encode_cursor(&row, &statement(2, None).query.options, false, &HashMap::new()).unwrap();
+
+    let mut statement = statement(2, Some(token));
+    statement.query.options.order_by.as_mut().unwrap()[0].order = "asc".to_string();
+
+    match prepare(&statement) {
+      Err(RuntimeError::Error(message)) => assert_eq!(message, "The page cursor was created for a different orderBy."),
+      result => panic!("Unexpected result {:?}", result),
+    }
+  }
+
+  #[test]
+  fn test_first_page() {
+    let statement = prepare(&statement(2, None)).unwrap();
+    assert_eq!(statement.query.options.take, Some(3));
+
+    let records = r#"[{"created_at":"2023-05-03","id":3},{"created_at":"2023-05-02","id":2},{"created_at":"2023-05-01","id":1}]"#;
+    let result = serde_json::from_str::<Value>(&page(&statement, records, &HashMap::new()).unwrap()).unwrap();
+
+    assert_eq!(result["data"], json!([{"createdAt":"2023-05-03","id":3},{"createdAt":"2023-05-02","id":2}]));
+    assert_eq!(result["hasMore"], json!(true));
+    assert_eq!(result["prevCursor"], Value::Null);
+
+    let page_cursor = decode_cursor(result["nextCursor"].as_str().unwrap()).unwrap();
+    assert!(!page_cursor.backwards);
+    assert_eq!(page_cursor.cursor, vec![argument("created_at", ParsedValue::String("2023-05-02".to_string()))]);
+  }
+
+  #[test]
+  fn test_previous_page() {
+    let row = json!({ "created_at": "2023-05-02" });
+    let token = encode_cursor(&row, &statement(2, None).query.options, true, &HashMap::new()).unwrap();
+
+    let statement = prepare(&statement(2, Some(token))).unwrap();
+    assert_eq!(statement.query.options.take, Some(-3));
+    assert!(statement.query.options.cursor.is_some());
+
+    // Only one row precedes the cursor, so there is no page before this one.
+    let records = r#"[{"created_at":"2023-05-03"}]"#;
+    let result = serde_json::from_str::<Value>(&page(&statement, records, &HashMap::new()).unwrap()).unwrap();
+
+    assert_eq!(result["data"], json!([{"createdAt":"2023-05-03"}]));
+    assert_eq!(result["hasMore"], json!(false));
+    assert_eq!(result["prevCursor"], Value::Null);
+    assert!(result["nextCursor"].is_string());
+  }
+
+  #[test]
+  fn test_prepare_without_take() {
+    let mut statement = statement(0, None);
+    statement.query.options.take = None;
+
+    assert!(prepare(&statement).is_err());
+  }
+}
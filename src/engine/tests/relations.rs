@@ -0,0 +1,203 @@
+#[cfg(test)]
+mod relations_tests {
+  use crate::{
+    engine::{
+      executor::records::{TypedRecords, TypedValue},
+      relations::{decode, link, relation_keys, unresolved},
+      shape_records,
+    },
+    parser::parsed::{
+      ParsedArgument, ParsedFilter, ParsedInclude, ParsedOperation, ParsedQuantifier, ParsedQuery, ParsedRelation,
+      ParsedRelationFilter, ParsedStatement, QueryOptions,
+    },
+  };
+  use serde_json::{json, Value};
+
+  fn statement(include: Option<ParsedInclude>) -> ParsedStatement {
+    ParsedStatement {
+      query: ParsedQuery {
+        table: "users".to_string(),
+        schema: "blog".to_string(),
+        fields: vec![],
+        options: QueryOptions {
+          order_by: None,
+          limit: None,
+          offset: None,
+          num_of_rows: None,
+          include,
+          group_by: None,
+          ..Default::default()
+        },
+      },
+      operation: ParsedOperation::FindMany,
+    }
+  }
+
+  #[test]
+  fn test_decode() {
+    let include = ParsedInclude {
+      joins: vec![],
+      relations: vec![ParsedRelation {
+        name: "posts".to_string(),
+        table: "posts".to_string(),
+        key: "user_id".to_string(),
+        references: "id".to_string(),
+        one: false,
+        include: vec![],
+        ..Default::default()
+      }],
+    };
+
+    let records = json!([
+      { "id": 1, "posts": "[{\"id\":10,\"title\":\"Hello\"}]" },
+      { "id": 2, "posts": "[]" }
+    ]);
+
+    let decoded: Value = serde_json::from_str(&decode(&statement(Some(include)), &records.to_string()).unwrap()).unwrap();
+    assert_eq!(
+      decoded,
+      json!([
+        { "id": 1, "posts": [{ "id": 10, "title": "Hello" }] },
+        { "id": 2, "posts": [] }
+      ])
+    );
+  }
+
+  #[test]
+  fn test_decode_without_relations() {
+    let records = r#"[{"id":1,"posts":"[]"}]"#;
+
+    assert_eq!(decode(&statement(None), records).unwrap(), records);
+  }
+
+  #[test]
+  fn test_shape_records_with_relation() {
+    let include = ParsedInclude {
+      joins: vec![],
+      relations: vec![ParsedRelation {
+        name: "posts".to_string(),
+        table: "posts".to_string(),
+        key: "user_id".to_string(),
+        references: "id".to_string(),
+        ..Default::default()
+      }],
+    };
+
+    // Drivers report relation columns as text, since they are selected as `::text`.
+    let records = TypedRecords {
+      columns: vec!["id".to_string(), "posts".to_string()],
+      types: vec!["int8".to_string(), "text".to_string()],
+      rows: vec![
+        vec![
+          TypedValue::BigInt(1),
+          TypedValue::String("[{\"id\":10,\"published_at\":\"2023-01-01T10:00:00\"}]".to_string()),
+        ],
+        vec![TypedValue::BigInt(2), TypedValue::Null],
+      ],
+    };
+
+    assert_eq!(
+      shape_records(&statement(Some(include)), records).unwrap(),
+      TypedValue::Array(vec![
+        TypedValue::Object(vec![
+          ("id".to_string(), TypedValue::BigInt(1)),
+          (
+            "posts".to_string(),
+            TypedValue::Json(json!([{ "id": 10, "publishedAt": "2023-01-01T10:00:00" }]))
+          ),
+        ]),
+        TypedValue::Object(vec![
+          ("id".to_string(), TypedValue::BigInt(2)),
+          ("posts".to_string(), TypedValue::Null),
+        ]),
+      ])
+    );
+  }
+
+  fn relation_filter(name: &str, table: Option<&str>, arguments: Vec<ParsedArgument>) -> ParsedArgument {
+    ParsedArgument {
+      name: name.to_string(),
+      value: None,
+      filter: Some(ParsedFilter::Relation(ParsedRelationFilter {
+        quantifier: ParsedQuantifier::Some,
+        table: table.map(str::to_string),
+        keys: vec![],
+        alias: format!("auro_{}_1", name),
+        parent: "blog.users".to_string(),
+        arguments,
+      })),
+      update: None,
+      aggregate: None,
+    }
+  }
+
+  #[test]
+  fn test_relation_keys() {
+    let comments = json!([
+      { "constraint_name": "comments_post_id_fkey", "column_name": "post_id", "foreign_table_name": "posts", "foreign_column_name": "id" },
+      { "constraint_name": "comments_author_fkey", "column_name": "author_tenant", "foreign_table_name": "users", "foreign_column_name": "tenant_id" },
+      { "constraint_name": "comments_author_fkey", "column_name": "author_id", "foreign_table_name": "users", "foreign_column_name": "id" }
+    ]);
+    let users = json!([
+      { "constraint_name": "users_team_id_fkey", "column_name": "team_id", "foreign_table_name": "teams", "foreign_column_name": "id" }
+    ]);
+
+    // Keys of the related table pointing at the parent, as in `users` having many `comments`.
+    assert_eq!(
+      relation_keys(&comments, &users, "users", "comments"),
+      Some(vec![
+        ("author_tenant".to_string(), "tenant_id".to_string()),
+        ("author_id".to_string(), "id".to_string()),
+      ])
+    );
+    // Keys of the parent pointing at the related table, as in `users` belonging to `teams`.
+    assert_eq!(
+      relation_keys(&json!([]), &users, "users", "teams"),
+      Some(vec![("id".to_string(), "team_id".to_string())])
+    );
+    assert_eq!(relation_keys(&json!([]), &users, "users", "tags"), None);
+  }
+
+  #[test]
+  fn test_relation_keys_of_composite_foreign_key() {
+    let memberships = json!([
+      { "constraint_name": "memberships_team_fkey", "column_name": "team_id", "ordinal_position": 2, "foreign_table_name": "teams", "foreign_column_name": "id" },
+      { "constraint_name": "memberships_user_id_fkey", "column_name": "user_id", "ordinal_position": 1, "foreign_table_name": "users", "foreign_column_name": "id" },
+      { "constraint_name": "memberships_team_fkey", "column_name": "team_tenant", "ordinal_position": 1, "foreign_table_name": "teams", "foreign_column_name": "tenant_id" }
+    ]);
+
+    // One pair per column of the key, in the key's order, without pairing columns across positions.
+    assert_eq!(
+      relation_keys(&memberships, &json!([]), "teams", "memberships"),
+      Some(vec![
+        ("team_tenant".to_string(), "tenant_id".to_string()),
+        ("team_id".to_string(), "id".to_string()),
+      ])
+    );
+  }
+
+  #[test]
+  fn test_link_unresolved_relations() {
+    let mut arguments = vec![ParsedArgument {
+      name: "or".to_string(),
+      value: None,
+      filter: Some(ParsedFilter::Or(vec![relation_filter(
+        "posts",
+        None,
+        vec![relation_filter("comments", None, vec![])],
+      )])),
+      ..Default::default()
+    }];
+
+    assert_eq!(unresolved(&arguments, "users"), Some(("users".to_string(), "posts".to_string())));
+
+    let keys = vec![("user_id".to_string(), "id".to_string())];
+    link(&mut arguments, "users", ("users", "posts"), "blog.posts", &keys);
+
+    // Filters nested in a resolved one take its table as their parent.
+    assert_eq!(unresolved(&arguments, "users"), Some(("posts".to_string(), "comments".to_string())));
+
+    link(&mut arguments, "users", ("posts", "comments"), "blog.comments", &keys);
+    assert_eq!(unresolved(&arguments, "users"), None);
+  }
+}
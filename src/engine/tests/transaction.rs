@@ -0,0 +1,197 @@
+#[cfg(test)]
+mod transaction_tests {
+  use crate::{
+    engine::{
+      builder::parameters::BuiltQuery,
+      execute, execute_batch,
+      executor::{driver::Driver, error::RuntimeError, records::TypedRecords},
+      interactive_transaction,
+    },
+    parser::parsed::{
+      FieldName, ParsedArgument, ParsedField, ParsedOperation, ParsedQuery, ParsedStatement, ParsedValue, QueryOptions,
+    },
+  };
+  use async_trait::async_trait;
+  use std::sync::Mutex;
+
+  /// Records every call it receives and fails queries whose SQL contains `fail`.
+  #[derive(Default)]
+  struct MockDriver {
+    calls: Mutex<Vec<String>>,
+    fail_rollback: bool,
+  }
+
+  impl MockDriver {
+    fn calls(&self) -> Vec<String> {
+      self.calls.lock().unwrap().clone()
+    }
+
+    fn record(&self, call: String) {
+      self.calls.lock().unwrap().push(call);
+    }
+  }
+
+  #[async_trait]
+  impl Driver for MockDriver {
+    async fn execute(&self, query: &BuiltQuery, transaction_id: Option<String>) -> Result<Option<String>, RuntimeError> {
+      self.record(format!("{} in {:?}", query.sql.trim(), transaction_id));
+
+      match query.sql.contains("fail") {
+        true => Err(RuntimeError::Error("Query failed.".to_string())),
+        false => Ok(Some("[]".to_string())),
+      }
+    }
+
+    async fn execute_typed(
+      &self,
+      _query: &BuiltQuery,
+      _transaction_id: Option<String>,
+    ) -> Result<Option<TypedRecords>, RuntimeError> {
+      Ok(None)
+    }
+
+    async fn begin_transaction(&self) -> Result<String, RuntimeError> {
+      self.record("BEGIN".to_string());
+      Ok("tx-1".to_string())
+    }
+
+    async fn commit_transaction(&self, transaction_id: String) -> Result<(), RuntimeError> {
+      self.record(format!("COMMIT {}", transaction_id));
+      Ok(())
+    }
+
+    async fn rollback_transaction(&self, transaction_id: String) -> Result<(), RuntimeError> {
+      self.record(format!("ROLLBACK {}", transaction_id));
+
+      match self.fail_rollback {
+        true => Err(RuntimeError::Error("Connection lost.".to_string())),
+        false => Ok(()),
+      }
+    }
+  }
+
+  fn find_many(table: &str) -> ParsedStatement {
+    ParsedStatement {
+      query: ParsedQuery::new(
+        table.to_string(),
+        "my_schema".to_string(),
+        vec![ParsedField {
+          name: FieldName::Where,
+          arguments: vec![ParsedArgument {
+            name: "id".to_string(),
+            value: Some(ParsedValue::Number("1".to_string())),
+            ..Default::default()
+          }],
+        }],
+        QueryOptions::default(),
+      ),
+      operation: ParsedOperation::FindMany,
+    }
+  }
+
+  #[tokio::test]
+  async fn test_execute_in_transaction() {
+    let driver = MockDriver::default();
+
+    let result = execute(&find_many("users"), &driver, Some("tx-1".to_string())).await;
+    assert_eq!(result.unwrap(), Some("[]".to_string()));
+
+    let result = execute(&find_many("users"), &driver, None).await;
+    assert_eq!(result.unwrap(), Some("[]".to_string()));
+
+    assert_eq!(
+      driver.calls(),
+      vec![
+        "SELECT * FROM my_schema.users WHERE id = :p1 in Some(\"tx-1\")".to_string(),
+        "SELECT * FROM my_schema.users WHERE id = :p1 in None".to_string(),
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn test_execute_batch_commits() {
+    let driver = MockDriver::default();
+
+    let results = execute_batch(&[find_many("users"), find_many("posts")], &driver).await;
+    assert_eq!(results.unwrap(), vec![Some("[]".to_string()), Some("[]".to_string())]);
+
+    assert_eq!(
+      driver.calls(),
+      vec![
+        "BEGIN".to_string(),
+        "SELECT * FROM my_schema.users WHERE id = :p1 in Some(\"tx-1\")".to_string(),
+        "SELECT * FROM my_schema.posts WHERE id = :p1 in Some(\"tx-1\")".to_string(),
+        "COMMIT tx-1".to_string(),
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn test_execute_batch_rolls_back_failed_statement() {
+    let driver = MockDriver::default();
+
+    let statements = [find_many("users"), find_many("fail"), find_many("posts")];
+    match execute_batch(&statements, &driver).await {
+      Err(RuntimeError::StatementFailed(1, message)) => assert_eq!(message, "\"Query failed.\""),
+      result => panic!("Unexpected result {:?}", result),
+    }
+
+    // Statements after the failing one never run.
+    let calls = driver.calls();
+    assert_eq!(calls.last(), Some(&"ROLLBACK tx-1".to_string()));
+    assert!(!calls.iter().any(|call| call.contains("posts") || call.starts_with("COMMIT")));
+  }
+
+  #[tokio::test]
+  async fn test_execute_batch_keeps_index_when_rollback_fails() {
+    let driver = MockDriver {
+      fail_rollback: true,
+      ..Default::default()
+    };
+
+    match execute_batch(&[find_many("fail")], &driver).await {
+      Err(RuntimeError::StatementFailed(0, message)) => {
+        assert_eq!(message, "\"Query failed.\" Rolling back failed as well: \"Connection lost.\"")
+      }
+      result => panic!("Unexpected result {:?}", result),
+    }
+  }
+
+  #[tokio::test]
+  async fn test_interactive_transaction_commits() {
+    let driver = MockDriver::default();
+
+    let result = interactive_transaction(&driver, |transaction_id| async move { Ok::<_, String>(transaction_id) }).await;
+
+    assert_eq!(result.unwrap(), "tx-1");
+    assert_eq!(driver.calls(), vec!["BEGIN".to_string(), "COMMIT tx-1".to_string()]);
+  }
+
+  #[tokio::test]
+  async fn test_interactive_transaction_rolls_back_when_callback_throws() {
+    let driver = MockDriver::default();
+
+    let result = interactive_transaction(&driver, |_| async { Err::<(), _>("Error: Order not found".to_string()) }).await;
+
+    match result {
+      Err(RuntimeError::Error(message)) => assert_eq!(message, "Error: Order not found"),
+      result => panic!("Unexpected result {:?}", result),
+    }
+    assert_eq!(driver.calls(), vec!["BEGIN".to_string(), "ROLLBACK tx-1".to_string()]);
+  }
+
+  #[tokio::test]
+  async fn test_interactive_transaction_keeps_callback_error_when_rollback_fails() {
+    let driver = MockDriver {
+      fail_rollback: true,
+      ..Default::default()
+    };
+
+    match interactive_transaction(&driver, |_| async { Err::<(), _>("Callback failed.".to_string()) }).await {
+      Err(RuntimeError::Error(message)) => {
+        assert_eq!(message, "Callback failed. Rolling back failed as well: Connection lost.")
+      }
+      result => panic!("Unexpected result {:?}", result),
+    }
+  }
+}
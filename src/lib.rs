@@ -1,32 +1,112 @@
 #![deny(clippy::all)]
-use aws_sdk_rdsdata::client::fluent_builders::ExecuteStatement;
 use engine::{
-  execute,
-  executor::{execute_statement, initialize_client, introspect_schema, utils::format_error_message, get_foreign_keys},
+  builder::parameters::BuiltQuery,
+  decode_page_cursor, execute, execute_batch, execute_typed,
+  executor::{
+    clients::Clients, error::RuntimeError, get_foreign_keys, introspect_database, introspect_schema,
+    introspection::schema::DatabaseSchema, records::TypedValue, utils::format_error_message, Connection, ConnectionOptions,
+  },
 };
 use lazy_static::lazy_static;
+use napi::{
+  bindgen_prelude::{FromNapiValue, Promise},
+  sys, Env, JsFunction, JsUnknown, NapiRaw,
+  threadsafe_function::{ErrorStrategy, ThreadsafeFunction},
+};
 use napi_derive::napi;
-use parser::statement::{create_statement, Field, Metadata, Options};
-use tokio::sync::OnceCell;
+use parser::{
+  casting::apply_column_types,
+  models::ModelRegistry,
+  parsed::ParsedStatement,
+  statement::{create_statement, Field, Metadata, Options, Statement},
+  validation::validate,
+};
 
 pub mod constants;
 pub mod engine;
 pub mod parser;
 
+const DEFAULT_CLIENT: &str = "default";
+
 lazy_static! {
-  static ref CLIENT: OnceCell<ExecuteStatement> = OnceCell::new();
+  static ref CLIENTS: Clients = Clients::default();
 }
 
+fn to_napi_error(err: RuntimeError) -> napi::Error {
+  napi::Error::from_reason(format_error_message(&err.to_string()))
+}
+
+async fn get_client(name: Option<String>) -> napi::Result<Connection> {
+  let name = name.unwrap_or_else(|| DEFAULT_CLIENT.to_string());
+
+  let connection = match name.as_str() {
+    // The default client can still be configured purely through environment variables.
+    DEFAULT_CLIENT => CLIENTS.get_or_connect(&name, ConnectionOptions::from_env).await,
+    _ => CLIENTS.get(&name).await,
+  };
+
+  connection.map_err(to_napi_error)
+}
+
+/// Parses a statement and, when the client has models, validates it and types its values by the
+/// columns they belong to. Page cursors are decoded first so their values are typed as well.
+fn parse_statement(
+  client: &Connection,
+  metadata: Metadata,
+  fields: Vec<Field>,
+  options: Options,
+) -> napi::Result<ParsedStatement> {
+  let mut statement = match create_statement(metadata, fields, options) {
+    Ok(statement) => statement,
+    Err(err) => return Err(napi::Error::from_reason(err.to_string())),
+  };
+
+  decode_page_cursor(&mut statement.query.options).map_err(to_napi_error)?;
+
+  if let Some(models) = &client.models {
+    if let Err(err) = validate(&statement, models) {
+      return Err(napi::Error::from_reason(err.to_string()));
+    }
+    apply_column_types(&mut statement, models);
+  }
+
+  Ok(statement)
+}
+
+async fn register_models(name: Option<String>, database: &DatabaseSchema) -> napi::Result<()> {
+  let name = name.unwrap_or_else(|| DEFAULT_CLIENT.to_string());
+
+  CLIENTS
+    .set_models(&name, ModelRegistry::new(database))
+    .await
+    .map_err(to_napi_error)
+}
+
+/// Connects the client `name`, `default` when missing. Fails when a client with the same name is
+/// already connected.
 #[napi]
-pub async fn connect() {
-  let _client = CLIENT.get_or_init(initialize_client).await;
+pub async fn connect(options: Option<ConnectionOptions>, name: Option<String>) -> napi::Result<()> {
+  let name = name.unwrap_or_else(|| DEFAULT_CLIENT.to_string());
+  let options = options.unwrap_or_else(ConnectionOptions::from_env);
+
+  CLIENTS.connect(&name, options).await.map_err(to_napi_error)?;
+
+  Ok(())
 }
 
+/// Closes the client so its name can be connected again.
 #[napi]
-pub async fn introspect() -> napi::Result<String> {
-  let client = CLIENT.get_or_init(initialize_client).await;
+pub async fn disconnect(name: Option<String>) -> napi::Result<()> {
+  let name = name.unwrap_or_else(|| DEFAULT_CLIENT.to_string());
+
+  CLIENTS.disconnect(&name).await.map_err(to_napi_error)
+}
 
-  let result = match introspect_schema(client).await {
+#[napi]
+pub async fn introspect(client: Option<String>) -> napi::Result<String> {
+  let client = get_client(client).await?;
+
+  let result = match introspect_schema(&client).await {
     Ok(result) => result,
     Err(err) => return Err(napi::Error::from_reason(err.to_string())),
   };
@@ -34,11 +114,12 @@ pub async fn introspect() -> napi::Result<String> {
   Ok(result)
 }
 
+/// Introspects tables, views and enums of `schemas`, the client's schema by default.
 #[napi]
-pub async fn get_foreign_keys_data(table: String) -> napi::Result<String> {
-  let client = CLIENT.get_or_init(initialize_client).await;
+pub async fn introspect_database_schema(schemas: Option<Vec<String>>, client: Option<String>) -> napi::Result<DatabaseSchema> {
+  let client = get_client(client).await?;
 
-  let result = match get_foreign_keys(client, &table).await {
+  let result = match introspect_database(&client, schemas).await {
     Ok(result) => result,
     Err(err) => return Err(napi::Error::from_reason(err.to_string())),
   };
@@ -46,16 +127,87 @@ pub async fn get_foreign_keys_data(table: String) -> napi::Result<String> {
   Ok(result)
 }
 
+/// Validates the statements of the client against `schema` from now on, e.g. a schema returned
+/// by `introspectDatabaseSchema` earlier.
 #[napi]
-pub async fn query(fields: Vec<Field>, options: Options, metadata: Metadata) -> napi::Result<Option<String>> {
-  let client = CLIENT.get_or_init(initialize_client).await;
+pub async fn set_models(schema: DatabaseSchema, client: Option<String>) -> napi::Result<()> {
+  get_client(client.clone()).await?;
 
-  let statement = match create_statement(metadata, fields, options) {
-    Ok(statement) => statement,
+  register_models(client, &schema).await
+}
+
+/// Introspects `schemas` and validates the statements of the client against them from now on.
+#[napi]
+pub async fn load_models(schemas: Option<Vec<String>>, client: Option<String>) -> napi::Result<DatabaseSchema> {
+  let connection = get_client(client.clone()).await?;
+
+  let schema = match introspect_database(&connection, schemas).await {
+    Ok(schema) => schema,
+    Err(err) => return Err(napi::Error::from_reason(err.to_string())),
+  };
+
+  register_models(client, &schema).await?;
+
+  Ok(schema)
+}
+
+#[napi]
+pub async fn get_foreign_keys_data(table: String, client: Option<String>) -> napi::Result<String> {
+  let client = get_client(client).await?;
+
+  let result = match get_foreign_keys(&client, &table).await {
+    Ok(result) => result,
     Err(err) => return Err(napi::Error::from_reason(err.to_string())),
   };
 
-  let formatted_records = match execute(&statement, client).await {
+  Ok(result)
+}
+
+#[napi]
+pub async fn begin_transaction(client: Option<String>) -> napi::Result<String> {
+  let client = get_client(client).await?;
+
+  let transaction_id = match client.driver.begin_transaction().await {
+    Ok(transaction_id) => transaction_id,
+    Err(err) => return Err(napi::Error::from_reason(format_error_message(&err.to_string()))),
+  };
+
+  Ok(transaction_id)
+}
+
+#[napi]
+pub async fn commit_transaction(transaction_id: String, client: Option<String>) -> napi::Result<()> {
+  let client = get_client(client).await?;
+
+  match client.driver.commit_transaction(transaction_id).await {
+    Ok(_) => Ok(()),
+    Err(err) => Err(napi::Error::from_reason(format_error_message(&err.to_string()))),
+  }
+}
+
+#[napi]
+pub async fn rollback_transaction(transaction_id: String, client: Option<String>) -> napi::Result<()> {
+  let client = get_client(client).await?;
+
+  match client.driver.rollback_transaction(transaction_id).await {
+    Ok(_) => Ok(()),
+    Err(err) => Err(napi::Error::from_reason(format_error_message(&err.to_string()))),
+  }
+}
+
+#[napi]
+pub async fn query(
+  fields: Vec<Field>,
+  options: Options,
+  metadata: Metadata,
+  transaction_id: Option<String>,
+  client: Option<String>,
+) -> napi::Result<Option<String>> {
+  let client = get_client(client).await?;
+
+  let statement = parse_statement(&client, metadata, fields, options)?;
+
+  let formatted_records = match execute(&statement, client.driver.as_ref(), transaction_id).await {
     Ok(formatted_records) => formatted_records,
     Err(err) => return Err(napi::Error::from_reason(format_error_message(&err.to_string()))),
   };
@@ -64,18 +216,159 @@ pub async fn query(fields: Vec<Field>, options: Options, metadata: Metadata) ->
 }
 
 #[napi]
-pub async fn query_raw(query_string: String) -> napi::Result<Option<String>> {
-  let client = CLIENT.get_or_init(initialize_client).await;
+pub async fn query_raw(
+  query_string: String,
+  transaction_id: Option<String>,
+  client: Option<String>,
+) -> napi::Result<Option<String>> {
+  let client = get_client(client).await?;
 
-  let result = match execute_statement(query_string, &client).await {
+  let query = BuiltQuery::new(query_string, Vec::new());
+
+  let result = match client.driver.execute(&query, transaction_id).await {
     Ok(result) => result,
     Err(err) => return Err(napi::Error::from_reason(format_error_message(&err.to_string()))),
   };
 
-  let formatted_records = match result.formatted_records() {
-    Some(formatted_records) => Some(parser::utils::snake_to_camel_case(formatted_records)),
+  let formatted_records = match result {
+    Some(formatted_records) => Some(parser::utils::snake_to_camel_case(&formatted_records)),
     None => None,
   };
 
   Ok(formatted_records)
 }
+
+/// Runs a statement like `query`, but resolves with the rows decoded by the types of their
+/// columns: `BigInt` for `int8`, `Date` for dates and timestamps, `Buffer` for `bytea`, arrays and
+/// parsed JSON. `numeric` values are returned as strings to keep their precision.
+#[napi(ts_return_type = "Promise<Array<Record<string, unknown>> | Record<string, unknown> | null>")]
+pub async fn query_typed(
+  fields: Vec<Field>,
+  options: Options,
+  metadata: Metadata,
+  transaction_id: Option<String>,
+  client: Option<String>,
+) -> napi::Result<Option<TypedValue>> {
+  let client = get_client(client).await?;
+
+  let statement = parse_statement(&client, metadata, fields, options)?;
+
+  match execute_typed(&statement, client.driver.as_ref(), transaction_id).await {
+    Ok(records) => Ok(records),
+    Err(err) => Err(napi::Error::from_reason(format_error_message(&err.to_string()))),
+  }
+}
+
+/// Runs raw SQL like `queryRaw`, resolving with rows decoded like the rows of `queryTyped`.
+#[napi(ts_return_type = "Promise<Array<Record<string, unknown>> | null>")]
+pub async fn query_raw_typed(
+  query_string: String,
+  transaction_id: Option<String>,
+  client: Option<String>,
+) -> napi::Result<Option<TypedValue>> {
+  let client = get_client(client).await?;
+
+  let query = BuiltQuery::new(query_string, Vec::new());
+
+  let records = match client.driver.execute_typed(&query, transaction_id).await {
+    Ok(records) => records,
+    Err(err) => return Err(napi::Error::from_reason(format_error_message(&err.to_string()))),
+  };
+
+  Ok(records.map(|records| {
+    let columns = records
+      .columns
+      .iter()
+      .map(|column| parser::utils::snake_to_camel_case(column))
+      .collect::<Vec<_>>();
+
+    TypedValue::Array(
+      records
+        .rows
+        .into_iter()
+        .map(|row| TypedValue::Object(columns.iter().cloned().zip(row).collect()))
+        .collect(),
+    )
+  }))
+}
+
+#[napi]
+pub async fn transaction(statements: Vec<Statement>, client: Option<String>) -> napi::Result<Vec<Option<String>>> {
+  let client = get_client(client).await?;
+
+  let mut parsed_statements = Vec::with_capacity(statements.len());
+
+  for (index, statement) in statements.into_iter().enumerate() {
+    match parse_statement(&client, statement.metadata, statement.fields, statement.options) {
+      Ok(statement) => parsed_statements.push(statement),
+      Err(err) => {
+        return Err(napi::Error::from_reason(format!(
+          "Statement at index {} is invalid: {}",
+          index, err.reason
+        )))
+      }
+    }
+  }
+
+  let results = match execute_batch(&parsed_statements, client.driver.as_ref()).await {
+    Ok(results) => results,
+    Err(err) => return Err(napi::Error::from_reason(format_error_message(&err.to_string()))),
+  };
+
+  Ok(results)
+}
+
+/// The value the callback of an interactive transaction resolved with. Values JSON can't represent,
+/// such as a `BigInt` or a `Buffer`, are returned as `null` rather than failing the transaction.
+struct CallbackResult(Option<serde_json::Value>);
+
+impl FromNapiValue for CallbackResult {
+  unsafe fn from_napi_value(env: sys::napi_env, napi_val: sys::napi_value) -> napi::Result<Self> {
+    let value = Option::<serde_json::Value>::from_napi_value(env, napi_val).ok().flatten();
+
+    Ok(CallbackResult(value))
+  }
+}
+
+/// The callback of an interactive transaction. napi calls callbacks error first and aborts the process
+/// when a call throws or returns something that isn't a promise, so the callback is wrapped into an async
+/// function taking only the transaction id: whatever it throws or returns then settles as a promise.
+pub struct TransactionCallback(ThreadsafeFunction<String, ErrorStrategy::CalleeHandled>);
+
+impl FromNapiValue for TransactionCallback {
+  unsafe fn from_napi_value(env: sys::napi_env, napi_val: sys::napi_value) -> napi::Result<Self> {
+    let env = Env::from_raw(env);
+    let wrap = env.run_script::<_, JsFunction>("(callback) => async (_, transactionId) => callback(transactionId)")?;
+    let callback = wrap.call(None, &[JsUnknown::from_napi_value(env.raw(), napi_val)?])?;
+
+    ThreadsafeFunction::from_napi_value(env.raw(), callback.raw()).map(TransactionCallback)
+  }
+}
+
+/// Opens a transaction and hands its id to `callback`. The transaction is committed when the
+/// returned promise resolves and rolled back when it rejects or the callback throws. Resolves with
+/// the callback's result when it is JSON, `null` otherwise.
+#[napi(
+  ts_args_type = "callback: (transactionId: string) => Promise<unknown>, client?: string | undefined | null",
+  ts_return_type = "Promise<unknown>"
+)]
+pub async fn interactive_transaction(
+  callback: TransactionCallback,
+  client: Option<String>,
+) -> napi::Result<Option<serde_json::Value>> {
+  let client = get_client(client).await?;
+
+  let run = |transaction_id: String| async move {
+    let promise = callback
+      .0
+      .call_async::<Promise<CallbackResult>>(Ok(transaction_id))
+      .await
+      .map_err(|err| err.reason)?;
+
+    promise.await.map(|CallbackResult(value)| value).map_err(|err| err.reason)
+  };
+
+  engine::interactive_transaction(client.driver.as_ref(), run)
+    .await
+    .map_err(to_napi_error)
+}
@@ -0,0 +1,214 @@
+use super::{
+  models::ModelRegistry,
+  parsed::{FieldName, ParsedArgument, ParsedFilter, ParsedRelation, ParsedStatement, ParsedUpdate, ParsedValue},
+};
+use crate::engine::executor::introspection::schema::ColumnSchema;
+
+/// Types the values of a validated statement by the columns they are compared with or written to,
+/// so enums, arrays, `jsonb`, `uuid`, `numeric` and timestamps reach the database with the right
+/// cast or Data API type hint. Values of unknown tables and columns are left as they were parsed.
+pub fn apply_column_types(statement: &mut ParsedStatement, models: &ModelRegistry) {
+  let query = &mut statement.query;
+  let columns = match models.columns(&query.schema, &query.table) {
+    Some(columns) => columns,
+    None => return,
+  };
+  let model = Model {
+    models,
+    schema: query.schema.clone(),
+    alias: None,
+    columns,
+  };
+
+  for field in &mut query.fields {
+    match field.name {
+      FieldName::Where | FieldName::Having => model.conditions(&mut field.arguments),
+      FieldName::Set | FieldName::Data => {
+        for argument in &mut field.arguments {
+          model.assignment(argument);
+        }
+      }
+      _ => {}
+    }
+  }
+
+  for argument in query.options.cursor.iter_mut().flatten() {
+    if let (Some(column), Some(value)) = (model.column(&argument.name), argument.value.as_mut()) {
+      model.convert(column, value);
+    }
+  }
+
+  if let Some(bucket) = query.options.time_bucket.as_mut() {
+    bucket.column_type = model.column(&bucket.column).map(|column| column.udt_name.clone());
+  }
+
+  if let Some(include) = query.options.include.as_mut() {
+    model.relations(&mut include.relations);
+  }
+}
+
+struct Model<'a> {
+  models: &'a ModelRegistry,
+  schema: String,
+  /// Alias qualifying the column names inside a relation filter.
+  alias: Option<String>,
+  columns: &'a [ColumnSchema],
+}
+
+impl<'a> Model<'a> {
+  fn related(&self, schema: &str, table: &str, alias: Option<String>) -> Option<Model<'a>> {
+    self.models.columns(schema, table).map(|columns| Model {
+      models: self.models,
+      schema: schema.to_string(),
+      alias,
+      columns,
+    })
+  }
+
+  fn column(&self, name: &str) -> Option<&'a ColumnSchema> {
+    let name = match self.alias.as_deref() {
+      Some(alias) => name
+        .strip_prefix(alias)
+        .and_then(|name| name.strip_prefix('.'))
+        .unwrap_or(name),
+      None => name,
+    };
+
+    self.columns.iter().find(|column| column.name == name)
+  }
+
+  fn conditions(&self, arguments: &mut [ParsedArgument]) {
+    for argument in arguments {
+      match argument.filter.as_mut() {
+        Some(ParsedFilter::And(nested)) | Some(ParsedFilter::Or(nested)) | Some(ParsedFilter::Negate(nested)) => {
+          self.conditions(nested)
+        }
+        Some(ParsedFilter::Relation(relation)) => {
+          let related = relation
+            .table
+            .as_deref()
+            .and_then(|table| table.split_once('.'))
+            .and_then(|(schema, table)| self.related(schema, table, Some(relation.alias.clone())));
+
+          if let Some(related) = related {
+            related.conditions(&mut relation.arguments);
+          }
+        }
+        filter => {
+          // Aggregates compare counts and sums rather than the column's own values.
+          let column = match self.column(&argument.name) {
+            Some(column) if argument.aggregate.is_none() => column,
+            _ => continue,
+          };
+
+          for value in compared_values(filter, &mut argument.value) {
+            self.convert(column, value);
+          }
+        }
+      }
+    }
+  }
+
+  fn assignment(&self, argument: &mut ParsedArgument) {
+    let column = match self.column(&argument.name) {
+      Some(column) => column,
+      None => return,
+    };
+
+    match argument.update.as_mut() {
+      Some(ParsedUpdate::Increment(value))
+      | Some(ParsedUpdate::Decrement(value))
+      | Some(ParsedUpdate::Multiply(value))
+      | Some(ParsedUpdate::Divide(value)) => self.convert(column, value),
+      // Pushed and removed values are elements of the array column.
+      Some(ParsedUpdate::Push(value)) | Some(ParsedUpdate::Remove(value)) => self.convert_element(column, value),
+      Some(ParsedUpdate::Append(_)) => {}
+      None => {
+        if let Some(value) = argument.value.as_mut() {
+          self.convert(column, value);
+        }
+      }
+    }
+  }
+
+  fn relations(&self, relations: &mut [ParsedRelation]) {
+    for relation in relations {
+      if let Some(related) = self.related(&self.schema, &relation.table, None) {
+        related.conditions(&mut relation.filter);
+        related.relations(&mut relation.include);
+      }
+    }
+  }
+
+  fn convert(&self, column: &ColumnSchema, value: &mut ParsedValue) {
+    if let Some(converted) = self.converted(column, value) {
+      *value = converted;
+    }
+  }
+
+  /// Casts an element written to an array column to the array's element type.
+  fn convert_element(&self, column: &ColumnSchema, value: &mut ParsedValue) {
+    let element_type = match column.data_type.strip_suffix("[]") {
+      Some(element_type) if column.udt_name.starts_with('_') => element_type,
+      _ => return,
+    };
+
+    if matches!(value, ParsedValue::String(_) | ParsedValue::Enum(_)) {
+      *value = ParsedValue::Cast(Box::new(value.clone()), element_type.to_string());
+    }
+  }
+
+  /// The value typed for the column, `None` when it is kept as parsed.
+  fn converted(&self, column: &ColumnSchema, value: &ParsedValue) -> Option<ParsedValue> {
+    if matches!(value, ParsedValue::Null | ParsedValue::Custom(_) | ParsedValue::Cast(_, _)) {
+      return None;
+    }
+
+    if self.models.is_enum(column) {
+      return match value {
+        ParsedValue::String(_) | ParsedValue::Enum(_) => {
+          Some(ParsedValue::Cast(Box::new(value.clone()), column.data_type.to_string()))
+        }
+        _ => None,
+      };
+    }
+
+    if column.udt_name.starts_with('_') {
+      return match value {
+        ParsedValue::Array(_) => Some(ParsedValue::Cast(Box::new(value.clone()), column.data_type.to_string())),
+        _ => None,
+      };
+    }
+
+    match (column.udt_name.as_str(), value) {
+      ("jsonb", ParsedValue::String(s) | ParsedValue::Json(s)) => Some(ParsedValue::Jsonb(s.to_string())),
+      ("json", ParsedValue::String(s)) => Some(ParsedValue::Json(s.to_string())),
+      ("uuid", ParsedValue::String(s)) => Some(ParsedValue::Uuid(s.to_string())),
+      ("numeric", ParsedValue::Number(s) | ParsedValue::Float(s) | ParsedValue::String(s)) => {
+        Some(ParsedValue::Decimal(s.to_string()))
+      }
+      ("money", ParsedValue::String(_)) => Some(ParsedValue::Cast(Box::new(value.clone()), "money".to_string())),
+      ("timestamptz", ParsedValue::DateTime(s) | ParsedValue::String(s)) => Some(ParsedValue::TimestampTz(s.to_string())),
+      ("timestamp", ParsedValue::String(s)) => Some(ParsedValue::DateTime(s.to_string())),
+      ("date", ParsedValue::String(s)) => Some(ParsedValue::Date(s.to_string())),
+      _ => None,
+    }
+  }
+}
+
+/// Values a filter compares its column to. Pattern filters bind strings of their own and are left
+/// out.
+fn compared_values<'a>(filter: Option<&'a mut ParsedFilter>, value: &'a mut Option<ParsedValue>) -> Vec<&'a mut ParsedValue> {
+  match filter {
+    Some(ParsedFilter::Equals(value))
+    | Some(ParsedFilter::Not(value))
+    | Some(ParsedFilter::Gt(value))
+    | Some(ParsedFilter::Gte(value))
+    | Some(ParsedFilter::Lt(value))
+    | Some(ParsedFilter::Lte(value)) => vec![value],
+    Some(ParsedFilter::In(values)) | Some(ParsedFilter::NotIn(values)) => values.iter_mut().collect(),
+    Some(ParsedFilter::Between(from, to)) => vec![from, to],
+    Some(_) => vec![],
+    None => value.iter_mut().collect(),
+  }
+}
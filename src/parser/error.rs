@@ -19,4 +19,7 @@ pub enum ParserError {
 
   #[error("{:?}", _0)]
   NoArguments(String),
+
+  #[error("{:?}", _0)]
+  ValidationError(String),
 }
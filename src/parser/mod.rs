@@ -3,3 +3,6 @@ mod tests;
 pub mod statement;
 pub mod parsed;
 pub mod utils;
+pub mod models;
+pub mod validation;
+pub mod casting;
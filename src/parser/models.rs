@@ -0,0 +1,40 @@
+use crate::engine::executor::introspection::schema::{ColumnSchema, DatabaseSchema};
+use std::collections::{HashMap, HashSet};
+
+/// Tables and views statements are validated against, keyed by their schema qualified names.
+#[derive(Debug, Clone, Default)]
+pub struct ModelRegistry {
+  models: HashMap<String, Vec<ColumnSchema>>,
+  enums: HashSet<String>,
+}
+
+impl ModelRegistry {
+  pub fn new(database: &DatabaseSchema) -> Self {
+    let tables = database
+      .tables
+      .iter()
+      .map(|table| (format!("{}.{}", table.schema, table.name), table.columns.clone()));
+    let views = database
+      .views
+      .iter()
+      .map(|view| (format!("{}.{}", view.schema, view.name), view.columns.clone()));
+
+    ModelRegistry {
+      models: tables.chain(views).collect(),
+      enums: database
+        .enums
+        .iter()
+        .map(|enum_schema| format!("{}.{}", enum_schema.schema, enum_schema.name))
+        .collect(),
+    }
+  }
+
+  /// Columns of the table or view, `None` when the registry doesn't know it.
+  pub fn columns(&self, schema: &str, table: &str) -> Option<&[ColumnSchema]> {
+    self.models.get(&format!("{}.{}", schema, table)).map(Vec::as_slice)
+  }
+
+  pub fn is_enum(&self, column: &ColumnSchema) -> bool {
+    self.enums.contains(&format!("{}.{}", column.udt_schema, column.udt_name))
+  }
+}
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 #[derive(Debug, Clone, PartialEq)]
 pub struct ParsedQuery {
   pub table: String,
@@ -33,7 +31,9 @@ pub enum FieldName {
   Return,
   NoReturn,
   Aggs,
-  From
+  From,
+  Conflict,
+  Having,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -46,10 +46,100 @@ pub(crate) trait FieldListLookup {
   fn lookup(&mut self, name: &str) -> Option<ParsedField>;
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct ParsedArgument {
   pub name: String,
   pub value: Option<ParsedValue>,
+  pub filter: Option<ParsedFilter>,
+  pub update: Option<ParsedUpdate>,
+  pub aggregate: Option<ParsedAggregate>,
+}
+
+/// A comparison applied to a column in a `where` field. Arguments without a filter keep
+/// comparing the column to their `value` for equality.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedFilter {
+  Equals(ParsedValue),
+  Not(ParsedValue),
+  Gt(ParsedValue),
+  Gte(ParsedValue),
+  Lt(ParsedValue),
+  Lte(ParsedValue),
+  In(Vec<ParsedValue>),
+  NotIn(Vec<ParsedValue>),
+  Contains(ParsedValue),
+  StartsWith(ParsedValue),
+  EndsWith(ParsedValue),
+  Between(ParsedValue, ParsedValue),
+  IsNull,
+  IsNotNull,
+  /// Nested conditions, rendered in parentheses.
+  And(Vec<ParsedArgument>),
+  Or(Vec<ParsedArgument>),
+  Negate(Vec<ParsedArgument>),
+  /// Conditions on the rows of a related table, rendered as a correlated `EXISTS` subquery.
+  Relation(ParsedRelationFilter),
+}
+
+/// Whether `some`, `every` or `none` of the related rows must match the nested conditions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedQuantifier {
+  Some,
+  Every,
+  None,
+}
+
+/// A `some`, `every` or `none` filter on the relation named by its argument. The related table and
+/// keys come from the statement's `include`, or from the foreign keys when the statement runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedRelationFilter {
+  pub quantifier: ParsedQuantifier,
+  /// Schema qualified related table, `None` until the relation is resolved.
+  pub table: Option<String>,
+  /// Pairs of a column of the related table and the parent column it references.
+  pub keys: Vec<(String, String)>,
+  /// Name the subquery gives the related table.
+  pub alias: String,
+  /// Table or alias the parent columns belong to.
+  pub parent: String,
+  pub arguments: Vec<ParsedArgument>,
+}
+
+/// An update computed from the current column value in a `set` field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedUpdate {
+  Increment(ParsedValue),
+  Decrement(ParsedValue),
+  Multiply(ParsedValue),
+  Divide(ParsedValue),
+  Push(ParsedValue),
+  Remove(ParsedValue),
+  Append(ParsedValue),
+}
+
+/// An aggregate computed over a column in an `aggs` field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedAggregate {
+  Sum,
+  Min,
+  Max,
+  Avg,
+  Count,
+  CountDistinct,
+}
+
+impl ParsedAggregate {
+  /// Key the aggregate's columns are grouped under in the result.
+  pub fn key(&self) -> &'static str {
+    match self {
+      ParsedAggregate::Sum => "_sum",
+      ParsedAggregate::Min => "_min",
+      ParsedAggregate::Max => "_max",
+      ParsedAggregate::Avg => "_avg",
+      ParsedAggregate::Count => "_count",
+      ParsedAggregate::CountDistinct => "_countDistinct",
+    }
+  }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -60,23 +150,170 @@ pub enum ParsedValue {
   Float(String),
   Date(String),
   DateTime(String),
+  /// A timestamp keeping its time zone offset, e.g. `2023-01-01T10:00:00+02:00`.
+  TimestampTz(String),
+  Uuid(String),
+  Json(String),
+  Jsonb(String),
+  Decimal(String),
+  /// Base64 encoded binary data.
+  Bytes(String),
+  /// A label of an enum type, cast to the type once the column it is written to is known.
+  Enum(String),
+  Array(Vec<ParsedValue>),
+  /// A value cast to the type of the column it is compared with or written to.
+  Cast(Box<ParsedValue>, String),
   Custom(String),
   Null,
 }
 
+impl ParsedValue {
+  /// Type the bound placeholder is cast to, for values the drivers can't send with a matching type.
+  pub fn cast(&self) -> Option<String> {
+    match self {
+      ParsedValue::TimestampTz(_) => Some("timestamptz".to_string()),
+      ParsedValue::Jsonb(_) => Some("jsonb".to_string()),
+      ParsedValue::Array(values) => Some(format!("{}[]", element_type(values))),
+      ParsedValue::Cast(_, cast) => Some(cast.to_string()),
+      _ => None,
+    }
+  }
+
+  /// Writes an array as a Postgres array literal, e.g. `{"a","b",NULL}`. Every driver sends arrays
+  /// as text, as the Data API can't bind array parameters.
+  pub fn array_literal(values: &[ParsedValue]) -> String {
+    let elements = values
+      .iter()
+      .map(|value| match value {
+        ParsedValue::Null => "NULL".to_string(),
+        ParsedValue::Array(values) => ParsedValue::array_literal(values),
+        value => format!("\"{}\"", value.text().replace('\\', "\\\\").replace('"', "\\\"")),
+      })
+      .collect::<Vec<_>>();
+
+    format!("{{{}}}", elements.join(","))
+  }
+
+  /// The value as Postgres reads it in text format.
+  pub fn text(&self) -> String {
+    match self {
+      ParsedValue::String(s)
+      | ParsedValue::Number(s)
+      | ParsedValue::Boolean(s)
+      | ParsedValue::Float(s)
+      | ParsedValue::Date(s)
+      | ParsedValue::DateTime(s)
+      | ParsedValue::TimestampTz(s)
+      | ParsedValue::Uuid(s)
+      | ParsedValue::Json(s)
+      | ParsedValue::Jsonb(s)
+      | ParsedValue::Decimal(s)
+      | ParsedValue::Bytes(s)
+      | ParsedValue::Enum(s)
+      | ParsedValue::Custom(s) => s.to_string(),
+      ParsedValue::Array(values) => ParsedValue::array_literal(values),
+      ParsedValue::Cast(value, _) => value.text(),
+      ParsedValue::Null => "NULL".to_string(),
+    }
+  }
+}
+
+/// Element type of an array without a column type to cast it to, taken from its first element.
+fn element_type(values: &[ParsedValue]) -> &'static str {
+  match values.iter().find(|value| **value != ParsedValue::Null) {
+    Some(ParsedValue::Number(_)) => "bigint",
+    Some(ParsedValue::Float(_)) => "double precision",
+    Some(ParsedValue::Decimal(_)) => "numeric",
+    Some(ParsedValue::Boolean(_)) => "boolean",
+    Some(ParsedValue::Date(_)) => "date",
+    Some(ParsedValue::DateTime(_)) => "timestamp",
+    Some(ParsedValue::TimestampTz(_)) => "timestamptz",
+    Some(ParsedValue::Uuid(_)) => "uuid",
+    Some(ParsedValue::Json(_)) => "json",
+    Some(ParsedValue::Jsonb(_)) => "jsonb",
+    Some(ParsedValue::Bytes(_)) => "bytea",
+    _ => "text",
+  }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ParsedInclude {
-  pub joins: Vec<(String, String, String, String)>
+  pub joins: Vec<ParsedJoin>,
+  pub relations: Vec<ParsedRelation>,
+}
+
+/// One `JOIN` of `joining_table`, matching each `(key, joining_key)` pair of columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedJoin {
+  /// `INNER`, `LEFT`, `RIGHT` or `FULL`.
+  pub kind: String,
+  pub table: String,
+  pub joining_table: String,
+  pub alias: Option<String>,
+  pub keys: Vec<(String, String)>,
+}
+
+/// Related rows returned nested under `name`, as an array or as a single object when `one` is set.
+/// An empty `select` returns every column of the related rows.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParsedRelation {
+  pub name: String,
+  pub table: String,
+  pub key: String,
+  pub references: String,
+  pub one: bool,
+  pub include: Vec<ParsedRelation>,
+  pub select: Vec<String>,
+  pub filter: Vec<ParsedArgument>,
+  pub order_by: Option<Vec<ParsedOrderBy>>,
+  pub take: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParsedOrderBy {
+  pub field: String,
+  pub order: String,
+  pub nulls: Option<String>,
+  pub table: Option<String>,
+  pub relation: Option<ParsedRelationOrder>,
+  pub aggregate: Option<ParsedAggregate>,
 }
 
+/// Orders by an aggregate over the rows of a related table, e.g. the number of comments of a post.
 #[derive(Debug, Clone, PartialEq)]
+pub struct ParsedRelationOrder {
+  pub table: String,
+  pub key: String,
+  pub references: String,
+  pub function: String,
+}
+
+/// Groups rows into fixed time intervals of a timestamp column, e.g. one bucket per day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedTimeBucket {
+  pub column: String,
+  pub unit: String,
+  pub timezone: Option<String>,
+  /// Type of the column, `timestamp`, `timestamptz` or `date`, once models are registered.
+  pub column_type: Option<String>,
+  /// First and last bucket of the series when empty buckets are filled.
+  pub fill: Option<(ParsedValue, ParsedValue)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct QueryOptions {
-  pub order_by: Option<HashMap<String, String>>,
+  pub order_by: Option<Vec<ParsedOrderBy>>,
   pub limit: Option<String>,
   pub offset: Option<String>,
   pub num_of_rows: Option<usize>,
   pub include: Option<ParsedInclude>,
   pub group_by: Option<Vec<String>>,
+  pub cursor: Option<Vec<ParsedArgument>>,
+  pub take: Option<i64>,
+  pub skip: Option<i64>,
+  pub paginate: bool,
+  pub page_cursor: Option<String>,
+  pub time_bucket: Option<ParsedTimeBucket>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -89,8 +326,12 @@ pub enum ParsedOperation {
   DeleteMany,
   InsertOne,
   InsertMany,
+  UpsertOne,
+  UpsertMany,
   Count,
   Average,
+  Aggregate,
+  GroupBy,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1,46 +1,165 @@
 use super::{
   error::ParserError,
   parsed::{
-    FieldName, ParsedArgument, ParsedField, ParsedInclude, ParsedOperation, ParsedQuery, ParsedStatement, ParsedValue,
-    QueryOptions,
+    FieldName, ParsedAggregate, ParsedArgument, ParsedField, ParsedFilter, ParsedInclude, ParsedJoin, ParsedOperation, ParsedOrderBy, ParsedQuery,
+    ParsedQuantifier, ParsedRelation, ParsedRelationFilter, ParsedRelationOrder, ParsedStatement, ParsedTimeBucket, ParsedUpdate, ParsedValue, QueryOptions,
   },
   utils,
 };
 use napi::bindgen_prelude::{FromNapiValue, ToNapiValue};
 use napi_derive::napi;
-use std::{collections::HashMap, str::FromStr};
+use std::str::FromStr;
 
 #[napi(object)]
 #[derive(Debug, Clone, PartialEq)]
 pub struct IncludeField {
   pub joins: Vec<Join>,
+  /// Related rows returned as nested arrays or objects next to each row's columns.
+  pub relations: Option<Vec<Relation>>,
 }
 
 #[napi(object)]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct Join {
   pub table: String,
   pub key: String,
   pub joining_table: String,
   pub joining_key: String,
+  /// `inner` (default), `left`, `right` or `full`.
+  pub kind: Option<String>,
+  /// Name the joined table is referenced by, e.g. `manager` when joining `employees` to itself.
+  pub alias: Option<String>,
+  /// Further columns of `table` for composite keys, matched in order with `joining_keys`.
+  pub keys: Option<Vec<String>>,
+  /// Further columns of `joining_table` for composite keys, matched in order with `keys`.
+  pub joining_keys: Option<Vec<String>>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Relation {
+  /// Key the related rows are returned under.
+  pub name: String,
+  pub table: String,
+  /// Column of the related table pointing at the parent row.
+  pub key: String,
+  /// Column of the parent table the key points at, `id` by default.
+  pub references: Option<String>,
+  /// Returns the related row as an object instead of an array, e.g. for a post's author.
+  pub one: Option<bool>,
+  /// Relations included for every related row.
+  pub include: Option<Vec<Relation>>,
+  /// Columns of the related rows to return, all of them by default.
+  pub select: Option<Vec<String>>,
+  /// Conditions the related rows must match, written like a `where` field's arguments.
+  #[napi(js_name = "where")]
+  pub filter: Option<Vec<Argument>>,
+  pub order_by: Option<Vec<OrderBy>>,
+  /// Maximum number of related rows returned for each parent row.
+  pub take: Option<i64>,
 }
 
 impl IncludeField {
-  pub fn parse(&self) -> ParsedInclude {
+  pub fn parse(&self) -> Result<ParsedInclude, ParserError> {
     let joins = self
       .joins
       .iter()
-      .map(|join| {
-        (
-          join.table.clone(),
-          join.key.clone(),
-          join.joining_table.clone(),
-          join.joining_key.clone(),
-        )
-      })
+      .map(|join| join.parse())
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let relations = self
+      .relations
+      .iter()
+      .flatten()
+      .map(|relation| relation.parse())
+      .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ParsedInclude { joins, relations })
+  }
+}
+
+impl Join {
+  pub fn parse(&self) -> Result<ParsedJoin, ParserError> {
+    let kind = match self.kind.as_deref().map(|kind| kind.to_lowercase()).as_deref() {
+      Some("inner") | None => "INNER",
+      Some("left") => "LEFT",
+      Some("right") => "RIGHT",
+      Some("full") => "FULL",
+      Some(_) => return Err(ParserError::ParseError("Join kind must be inner, left, right or full!".to_string())),
+    };
+
+    if let Some(alias) = &self.alias {
+      if alias.is_empty() || !alias.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(ParserError::InputError("Invalid join alias!".to_string()));
+      }
+    }
+
+    let keys = self.keys.clone().unwrap_or_default();
+    let joining_keys = self.joining_keys.clone().unwrap_or_default();
+    if keys.len() != joining_keys.len() {
+      return Err(ParserError::InputError("Join keys and joining keys must have the same length!".to_string()));
+    }
+
+    let keys = std::iter::once((self.key.clone(), self.joining_key.clone()))
+      .chain(keys.into_iter().zip(joining_keys))
       .collect();
 
-    ParsedInclude { joins }
+    Ok(ParsedJoin {
+      kind: kind.to_string(),
+      table: self.table.clone(),
+      joining_table: self.joining_table.clone(),
+      alias: self.alias.clone(),
+      keys,
+    })
+  }
+}
+
+impl Relation {
+  pub fn parse(&self) -> Result<ParsedRelation, ParserError> {
+    // The name becomes a column alias in the SQL text, so it has to be a plain identifier.
+    if self.name.is_empty() || !self.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+      return Err(ParserError::InputError("Invalid relation name!".to_string()));
+    }
+
+    if self.take.is_some_and(|take| take < 0) {
+      return Err(ParserError::InputError("Relation take can't be negative!".to_string()));
+    }
+
+    let include = self
+      .include
+      .iter()
+      .flatten()
+      .map(|relation| relation.parse())
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let filter = self
+      .filter
+      .iter()
+      .flatten()
+      .map(|argument| argument.parse())
+      .collect::<Result<Vec<_>, _>>()?;
+
+    if filter.iter().any(|argument| argument.update.is_some() || argument.aggregate.is_some()) {
+      return Err(ParserError::InputError("Relation filters only take filter operators!".to_string()));
+    }
+
+    Ok(ParsedRelation {
+      name: utils::camel_to_snake_case(&self.name),
+      table: self.table.clone(),
+      key: utils::camel_to_snake_case(&self.key),
+      references: utils::camel_to_snake_case(self.references.as_deref().unwrap_or("id")),
+      one: self.one.unwrap_or(false),
+      include,
+      select: self
+        .select
+        .iter()
+        .flatten()
+        .map(|column| utils::camel_to_snake_case(column))
+        .collect(),
+      filter,
+      order_by: OrderBy::parse(self.order_by.clone())?,
+      take: self.take,
+    })
   }
 }
 #[napi(object)]
@@ -55,6 +174,14 @@ impl Field {
     let name = self.parse_field_name()?;
     let arguments = self.parse_arguments()?;
 
+    if name != FieldName::Set && arguments.iter().any(|argument| argument.update.is_some()) {
+      return Err(ParserError::InputError("Update operators are only allowed in set!".to_string()));
+    }
+
+    if name != FieldName::Aggs && name != FieldName::Having && arguments.iter().any(|argument| argument.aggregate.is_some()) {
+      return Err(ParserError::InputError("Aggregate operators are only allowed in aggs and having!".to_string()));
+    }
+
     Ok(ParsedField { name, arguments })
   }
 
@@ -84,32 +211,107 @@ impl FromStr for FieldName {
       "return" => Ok(FieldName::Return),
       "no_return" => Ok(FieldName::NoReturn),
       "aggs" => Ok(FieldName::Aggs),
+      "conflict" => Ok(FieldName::Conflict),
+      "having" => Ok(FieldName::Having),
       _ => Err(ParserError::InputError("Invalid field name!".to_string())),
     }
   }
 }
 
 #[napi(object)]
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct Argument {
   pub name: String,
+  pub value: Option<String>,
+  /// `string`, `number`, `float`, `decimal`, `boolean`, `date`, `datetime`, `timestamptz`, `uuid`,
+  /// `json`, `jsonb`, `bytes` (base64), `enum`, `custom`, `null`, or `array` for the elements in
+  /// `values`. Values are cast to their column's type once models are registered.
+  pub value_type: Option<String>,
+  /// Filter applied in a `where` field: `equals`, `not`, `gt`, `gte`, `lt`, `lte`, `in`, `notIn`,
+  /// `contains`, `startsWith`, `endsWith`, `between`, `isNull`, `isNotNull`, or `some`, `every` and
+  /// `none` to filter by the related rows of the relation named by `name`. In a `set` field:
+  /// `increment`, `decrement`, `multiply`, `divide`, `push`, `remove` or `append`. In an `aggs`
+  /// field: `_sum`, `_min`, `_max`, `_avg`, `_count` or `_countDistinct`.
+  pub operator: Option<String>,
+  /// Values for the `in`, `notIn` and `between` operators, or the elements of an `array` value.
+  pub values: Option<Vec<ArgumentValue>>,
+  /// Nested conditions of an `AND`, `OR` or `NOT` group, of an aggregate group such as `_sum`
+  /// in a `having` field, or of a `some`, `every` or `none` relation filter.
+  pub arguments: Option<Vec<Argument>>,
+}
+
+/// One element of a list argument. Elements without their own `value_type` use the type of the
+/// argument they belong to.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgumentValue {
   pub value: Option<String>,
   pub value_type: Option<String>,
 }
 
 impl Argument {
   pub fn parse(&self) -> Result<ParsedArgument, ParserError> {
-    let value = match self.parse_value() {
-      Ok(value) => value,
-      Err(err) => return Err(err),
-    };
+    if let Some(relation) = self.parse_relation_filter()? {
+      return Ok(ParsedArgument {
+        name: utils::camel_to_snake_case(&self.name),
+        value: None,
+        filter: Some(relation),
+        update: None,
+        aggregate: None,
+      });
+    }
+
+    if let Some(group) = self.parse_group()? {
+      return Ok(ParsedArgument {
+        name: self.name.to_lowercase(),
+        value: None,
+        filter: Some(group),
+        update: None,
+        aggregate: parse_aggregate_key(&self.name),
+      });
+    }
 
     let name = utils::camel_to_snake_case(&self.name);
 
-    Ok(ParsedArgument { name, value })
+    if let Some(aggregate) = self.parse_aggregate() {
+      return Ok(ParsedArgument {
+        name,
+        value: None,
+        filter: None,
+        update: None,
+        aggregate: Some(aggregate),
+      });
+    }
+
+    let update = self.parse_update()?;
+    let filter = match update {
+      Some(_) => None,
+      None => self.parse_filter()?,
+    };
+
+    let value = match (&filter, &update) {
+      (None, None) => self.parse_value()?,
+      _ => None,
+    };
+
+    Ok(ParsedArgument {
+      name,
+      value,
+      filter,
+      update,
+      aggregate: None,
+    })
+  }
+
+  fn parse_aggregate(&self) -> Option<ParsedAggregate> {
+    self.operator.as_deref().and_then(parse_aggregate_key)
   }
 
   fn parse_value(&self) -> Result<Option<ParsedValue>, ParserError> {
+    if self.value_type.as_deref() == Some("array") {
+      return self.parse_array().map(Some);
+    }
+
     let value = match &self.value {
       Some(str) => str.to_string(),
       None => return Ok(None),
@@ -120,39 +322,268 @@ impl Argument {
       None => return Ok(None),
     };
 
-    match value_type {
-      "number" => return Ok(Some(ParsedValue::Number(value))),
-      "string" => return Ok(Some(ParsedValue::String(value))),
-      "boolean" => return Ok(Some(ParsedValue::Boolean(value))),
-      "date" => return Ok(Some(ParsedValue::Date(value))),
-      "datetime" => return Ok(Some(ParsedValue::DateTime(value))),
-      "custom" => return Ok(Some(ParsedValue::Custom(value))),
-      "null" => return Ok(Some(ParsedValue::Null)),
-      _ => Err(ParserError::ParseError("Invalid value type!".to_string())),
+    parse_typed_value(value, value_type).map(Some)
+  }
+
+  /// Parses a `some`, `every` or `none` filter on a relation. The relation is resolved once the
+  /// whole statement is known.
+  fn parse_relation_filter(&self) -> Result<Option<ParsedFilter>, ParserError> {
+    let quantifier = match self.operator.as_deref() {
+      Some("some") => ParsedQuantifier::Some,
+      Some("every") => ParsedQuantifier::Every,
+      Some("none") => ParsedQuantifier::None,
+      _ => return Ok(None),
+    };
+
+    let arguments = match &self.arguments {
+      Some(arguments) => arguments
+        .iter()
+        .map(|argument| argument.parse())
+        .collect::<Result<Vec<_>, _>>()?,
+      None => vec![],
+    };
+
+    Ok(Some(ParsedFilter::Relation(ParsedRelationFilter {
+      quantifier,
+      table: None,
+      keys: vec![],
+      alias: String::new(),
+      parent: String::new(),
+      arguments,
+    })))
+  }
+
+  fn parse_group(&self) -> Result<Option<ParsedFilter>, ParserError> {
+    let arguments = match &self.arguments {
+      Some(arguments) => arguments
+        .iter()
+        .map(|argument| argument.parse())
+        .collect::<Result<Vec<_>, _>>()?,
+      None => return Ok(None),
+    };
+
+    // An aggregate group such as `_sum` applies its aggregate to every condition inside it.
+    match (self.name.as_str(), parse_aggregate_key(&self.name)) {
+      ("AND", _) => Ok(Some(ParsedFilter::And(arguments))),
+      ("OR", _) => Ok(Some(ParsedFilter::Or(arguments))),
+      ("NOT", _) => Ok(Some(ParsedFilter::Negate(arguments))),
+      (_, Some(aggregate)) => Ok(Some(ParsedFilter::And(
+        arguments
+          .into_iter()
+          .map(|argument| ParsedArgument {
+            aggregate: Some(aggregate.clone()),
+            ..argument
+          })
+          .collect(),
+      ))),
+      _ => Err(ParserError::ParseError(
+        "Nested arguments are only allowed in AND, OR, NOT and aggregate groups!".to_string(),
+      )),
     }
   }
+
+  fn parse_update(&self) -> Result<Option<ParsedUpdate>, ParserError> {
+    let update = match self.operator.as_deref() {
+      Some("increment") => ParsedUpdate::Increment(self.parse_operand()?),
+      Some("decrement") => ParsedUpdate::Decrement(self.parse_operand()?),
+      Some("multiply") => ParsedUpdate::Multiply(self.parse_operand()?),
+      Some("divide") => ParsedUpdate::Divide(self.parse_operand()?),
+      Some("push") => ParsedUpdate::Push(self.parse_operand()?),
+      Some("remove") => ParsedUpdate::Remove(self.parse_operand()?),
+      Some("append") => ParsedUpdate::Append(self.parse_operand()?),
+      _ => return Ok(None),
+    };
+
+    Ok(Some(update))
+  }
+
+  fn parse_filter(&self) -> Result<Option<ParsedFilter>, ParserError> {
+    let operator = match &self.operator {
+      Some(operator) => operator.as_str(),
+      None => return Ok(None),
+    };
+
+    let filter = match operator {
+      "equals" => ParsedFilter::Equals(self.parse_operand()?),
+      "not" => ParsedFilter::Not(self.parse_operand()?),
+      "gt" => ParsedFilter::Gt(self.parse_operand()?),
+      "gte" => ParsedFilter::Gte(self.parse_operand()?),
+      "lt" => ParsedFilter::Lt(self.parse_operand()?),
+      "lte" => ParsedFilter::Lte(self.parse_operand()?),
+      "in" => ParsedFilter::In(self.parse_filter_values()?),
+      "notIn" => ParsedFilter::NotIn(self.parse_filter_values()?),
+      "contains" => ParsedFilter::Contains(self.parse_operand()?),
+      "startsWith" => ParsedFilter::StartsWith(self.parse_operand()?),
+      "endsWith" => ParsedFilter::EndsWith(self.parse_operand()?),
+      "between" => match self.parse_filter_values()?.as_slice() {
+        [from, to] => ParsedFilter::Between(from.clone(), to.clone()),
+        _ => return Err(ParserError::ParseError("Between expects exactly two values!".to_string())),
+      },
+      "isNull" => ParsedFilter::IsNull,
+      "isNotNull" => ParsedFilter::IsNotNull,
+      _ => return Err(ParserError::ParseError("Invalid filter operator!".to_string())),
+    };
+
+    Ok(Some(filter))
+  }
+
+  fn parse_operand(&self) -> Result<ParsedValue, ParserError> {
+    if self.value_type.as_deref() == Some("array") {
+      return self.parse_array();
+    }
+
+    parse_element(&self.value, self.value_type.as_deref())
+  }
+
+  /// Parses the `values` of an `array` argument. Elements are strings unless they have their own
+  /// `value_type`.
+  fn parse_array(&self) -> Result<ParsedValue, ParserError> {
+    let values = match &self.values {
+      Some(values) => values,
+      None => return Err(ParserError::ParseError("Array values are missing!".to_string())),
+    };
+
+    values
+      .iter()
+      .map(|element| parse_element(&element.value, Some(element.value_type.as_deref().unwrap_or("string"))))
+      .collect::<Result<_, _>>()
+      .map(ParsedValue::Array)
+  }
+
+  fn parse_filter_values(&self) -> Result<Vec<ParsedValue>, ParserError> {
+    let values = match &self.values {
+      Some(values) => values,
+      None => return Err(ParserError::ParseError("Filter values are missing!".to_string())),
+    };
+
+    values
+      .iter()
+      .map(|element| parse_element(&element.value, element.value_type.as_deref().or(self.value_type.as_deref())))
+      .collect()
+  }
+}
+
+fn parse_element(value: &Option<String>, value_type: Option<&str>) -> Result<ParsedValue, ParserError> {
+  match (value, value_type) {
+    (_, Some("null")) => Ok(ParsedValue::Null),
+    (Some(value), Some(value_type)) => parse_typed_value(value.to_string(), value_type),
+    _ => Err(ParserError::ParseError("Filter value is missing!".to_string())),
+  }
+}
+
+fn parse_typed_value(value: String, value_type: &str) -> Result<ParsedValue, ParserError> {
+  match value_type {
+    "number" => Ok(ParsedValue::Number(value)),
+    "float" => Ok(ParsedValue::Float(value)),
+    "string" => Ok(ParsedValue::String(value)),
+    "boolean" => Ok(ParsedValue::Boolean(value)),
+    "date" => Ok(ParsedValue::Date(value)),
+    "datetime" => Ok(ParsedValue::DateTime(value)),
+    "timestamptz" => Ok(ParsedValue::TimestampTz(value)),
+    "uuid" => Ok(ParsedValue::Uuid(value)),
+    "json" => Ok(ParsedValue::Json(value)),
+    "jsonb" => Ok(ParsedValue::Jsonb(value)),
+    "decimal" => Ok(ParsedValue::Decimal(value)),
+    "bytes" => Ok(ParsedValue::Bytes(value)),
+    "enum" => Ok(ParsedValue::Enum(value)),
+    "custom" => Ok(ParsedValue::Custom(value)),
+    "null" => Ok(ParsedValue::Null),
+    _ => Err(ParserError::ParseError("Invalid value type!".to_string())),
+  }
 }
 
 #[napi(object)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct OrderBy {
   pub field: String,
   pub order: Order,
+  /// `first` or `last`, defaults to the database's placement for the direction.
+  pub nulls: Option<String>,
+  /// Joined table the field belongs to.
+  pub table: Option<String>,
+  /// Orders by an aggregate of a related table instead of a column.
+  pub relation: Option<RelationOrder>,
+  /// Orders groups by an aggregate of the field: `_sum`, `_min`, `_max`, `_avg`, `_count` or
+  /// `_countDistinct`.
+  pub aggregate: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelationOrder {
+  pub table: String,
+  /// Column of the related table pointing at this table.
+  pub key: String,
+  /// Column of this table the key points at, `id` by default.
+  pub references: Option<String>,
+  /// `count` (default), `sum`, `avg`, `min` or `max` of `field`.
+  pub function: Option<String>,
 }
 
 impl OrderBy {
-  pub fn parse(order_by: Option<Vec<OrderBy>>) -> Option<HashMap<String, String>> {
-    order_by.map(|vec| {
-      vec.into_iter().fold(HashMap::new(), |mut acc, order_by| {
-        acc.insert(order_by.field.clone(), order_by.order.parse().to_string());
-        acc
-      })
+  pub fn parse(order_by: Option<Vec<OrderBy>>) -> Result<Option<Vec<ParsedOrderBy>>, ParserError> {
+    let order_by = match order_by {
+      Some(order_by) => order_by,
+      None => return Ok(None),
+    };
+
+    order_by
+      .iter()
+      .map(|order_by| order_by.parse_one())
+      .collect::<Result<Vec<_>, _>>()
+      .map(Some)
+  }
+
+  fn parse_one(&self) -> Result<ParsedOrderBy, ParserError> {
+    let nulls = match self.nulls.as_deref() {
+      Some(nulls @ ("first" | "last")) => Some(nulls.to_string()),
+      Some(_) => return Err(ParserError::ParseError("Nulls must be first or last!".to_string())),
+      None => None,
+    };
+
+    let relation = match &self.relation {
+      Some(relation) => Some(relation.parse()?),
+      None => None,
+    };
+
+    let aggregate = match self.aggregate.as_deref() {
+      Some(key) => match parse_aggregate_key(key) {
+        Some(aggregate) => Some(aggregate),
+        None => return Err(ParserError::ParseError("Invalid aggregate!".to_string())),
+      },
+      None => None,
+    };
+
+    Ok(ParsedOrderBy {
+      field: self.field.clone(),
+      order: self.order.parse().to_string(),
+      nulls,
+      table: self.table.clone(),
+      relation,
+      aggregate,
+    })
+  }
+}
+
+impl RelationOrder {
+  pub fn parse(&self) -> Result<ParsedRelationOrder, ParserError> {
+    let function = match self.function.as_deref() {
+      Some(function @ ("count" | "sum" | "avg" | "min" | "max")) => function.to_string(),
+      Some(_) => return Err(ParserError::ParseError("Invalid relation aggregate!".to_string())),
+      None => "count".to_string(),
+    };
+
+    Ok(ParsedRelationOrder {
+      table: self.table.clone(),
+      key: utils::camel_to_snake_case(&self.key),
+      references: utils::camel_to_snake_case(self.references.as_deref().unwrap_or("id")),
+      function,
     })
   }
 }
 
 #[napi]
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum Order {
   Asc,
   Desc,
@@ -168,7 +599,7 @@ impl Order {
 }
 
 #[napi(object)]
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Options {
   pub order_by: Option<Vec<OrderBy>>,
   pub limit: Option<i64>,
@@ -176,16 +607,97 @@ pub struct Options {
   pub num_of_rows: Option<i64>,
   pub include: Option<IncludeField>,
   pub group_by: Option<Vec<String>>,
+  /// Unique columns and values of the row pagination starts from.
+  pub cursor: Option<Vec<Argument>>,
+  /// Number of rows to return, counting backwards from the cursor when negative.
+  pub take: Option<i64>,
+  /// Number of rows to skip, e.g. `1` to leave out the cursor row itself.
+  pub skip: Option<i64>,
+  /// Returns `{ data, nextCursor, prevCursor, hasMore }` instead of the bare rows.
+  pub paginate: Option<bool>,
+  /// `nextCursor` or `prevCursor` of a previously returned page.
+  pub page_cursor: Option<String>,
+  /// Groups a `groupBy` by time intervals of a timestamp column, returned as `bucket`.
+  pub time_bucket: Option<TimeBucket>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct TimeBucket {
+  pub field: String,
+  /// `minute`, `hour`, `day`, `week`, `month`, `quarter` or `year`.
+  pub unit: String,
+  /// Time zone the buckets are aligned to, e.g. `Europe/Berlin`. The column's own zone by default.
+  /// Columns without a time zone hold UTC times once models are registered, and session times before.
+  pub timezone: Option<String>,
+  /// First bucket of a series without gaps. Buckets without rows are returned with null aggregates.
+  pub from: Option<String>,
+  /// Last bucket of a series without gaps, required together with `from`.
+  pub to: Option<String>,
+}
+
+impl TimeBucket {
+  pub fn parse(&self) -> Result<ParsedTimeBucket, ParserError> {
+    let unit = match self.unit.as_str() {
+      unit @ ("minute" | "hour" | "day" | "week" | "month" | "quarter" | "year") => unit.to_string(),
+      _ => return Err(ParserError::ParseError("Invalid time bucket unit!".to_string())),
+    };
+
+    // The time zone is written into the SQL text so the bucket expression is identical wherever it appears.
+    let timezone = match &self.timezone {
+      Some(timezone)
+        if timezone.is_empty()
+          || !timezone
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '+' | '-' | ':')) =>
+      {
+        return Err(ParserError::ParseError("Invalid time zone!".to_string()))
+      }
+      timezone => timezone.clone(),
+    };
+
+    let fill = match (&self.from, &self.to) {
+      (Some(from), Some(to)) => Some((ParsedValue::DateTime(from.clone()), ParsedValue::DateTime(to.clone()))),
+      (None, None) => None,
+      _ => return Err(ParserError::InputError("Filling time buckets needs both from and to!".to_string())),
+    };
+
+    Ok(ParsedTimeBucket {
+      column: utils::camel_to_snake_case(&self.field),
+      unit,
+      timezone,
+      column_type: None,
+      fill,
+    })
+  }
 }
 
 impl Options {
   pub fn parse(&self) -> Result<QueryOptions, ParserError> {
+    if self.skip.is_some_and(|skip| skip < 0) {
+      return Err(ParserError::InputError("Skip can't be negative!".to_string()));
+    }
+
     let limit = self.limit.map(|limit_str| limit_str.to_string());
     let offset = self.offset.map(|offset_str| offset_str.to_string());
     let num_of_rows = self.num_of_rows.map(|num_of_rows| num_of_rows as usize);
-    let order_by = OrderBy::parse(self.order_by.clone());
-    let include = self.include.clone().map(|include| include.parse());
-    let group_by = self.group_by.clone();
+    let order_by = OrderBy::parse(self.order_by.clone())?;
+    let include = match &self.include {
+      Some(include) => Some(include.parse()?),
+      None => None,
+    };
+    let group_by = self
+      .group_by
+      .as_ref()
+      .map(|group_by| group_by.iter().map(|column| utils::camel_to_snake_case(column)).collect());
+    let cursor = match &self.cursor {
+      Some(cursor) => Some(cursor.iter().map(|argument| argument.parse()).collect::<Result<Vec<_>, _>>()?),
+      None => None,
+    };
+    let time_bucket = match &self.time_bucket {
+      Some(time_bucket) => Some(time_bucket.parse()?),
+      None => None,
+    };
 
     Ok(QueryOptions {
       order_by,
@@ -194,6 +706,12 @@ impl Options {
       num_of_rows,
       include,
       group_by,
+      cursor,
+      take: self.take,
+      skip: self.skip,
+      paginate: self.paginate.unwrap_or(false),
+      page_cursor: self.page_cursor.clone(),
+      time_bucket,
     })
   }
 }
@@ -236,11 +754,22 @@ fn create_query(metadata: Metadata, fields: Vec<Field>, options: Options) -> Res
 
   let table = &metadata.table;
   let schema = &metadata.schema;
-  let fields = match fields.iter().map(|field| field.parse()).collect::<Result<Vec<_>, _>>() {
+  let mut fields = match fields.iter().map(|field| field.parse()).collect::<Result<Vec<_>, _>>() {
     Ok(fields) => fields,
     Err(err) => return Err(err),
   };
 
+  let (relations, joins) = match &options.include {
+    Some(include) => (include.relations.as_slice(), include.joins.as_slice()),
+    None => (&[][..], &[][..]),
+  };
+  let joins = joins.iter().filter(|join| &join.table == table).cloned().collect::<Vec<_>>();
+  let parent = format!("{}.{}", schema, table);
+
+  for field in fields.iter_mut().filter(|field| field.name == FieldName::Where) {
+    resolve_relation_filters(&mut field.arguments, relations, &joins, schema, &parent, 1, None);
+  }
+
   Ok(ParsedQuery {
     table: table.to_string(),
     schema: schema.to_string(),
@@ -249,6 +778,70 @@ fn create_query(metadata: Metadata, fields: Vec<Field>, options: Options) -> Res
   })
 }
 
+/// Names the subqueries of the relation filters in `arguments` and resolves their relations from the
+/// included relations, or from the joins of the parent table. Columns compared inside a relation
+/// filter are qualified by its alias so they never resolve to a parent table. Relations found in
+/// neither are left for the engine to resolve from the foreign keys.
+fn resolve_relation_filters(
+  arguments: &mut [ParsedArgument],
+  relations: &[ParsedRelation],
+  joins: &[ParsedJoin],
+  schema: &str,
+  parent: &str,
+  depth: usize,
+  qualifier: Option<&str>,
+) {
+  for argument in arguments.iter_mut() {
+    match &mut argument.filter {
+      Some(ParsedFilter::Relation(relation)) => {
+        relation.alias = format!("auro_{}_{}", argument.name, depth);
+        relation.parent = parent.to_string();
+
+        let included = relations.iter().find(|included| included.name == argument.name);
+        let join = joins
+          .iter()
+          .find(|join| join.alias.as_deref().unwrap_or(&join.joining_table) == argument.name);
+
+        if let Some(included) = included {
+          relation.table = Some(format!("{}.{}", schema, included.table));
+          relation.keys = vec![(included.key.clone(), included.references.clone())];
+        } else if let Some(join) = join {
+          relation.table = Some(format!("{}.{}", schema, join.joining_table));
+          relation.keys = join
+            .keys
+            .iter()
+            .map(|(key, joining_key)| (joining_key.clone(), key.clone()))
+            .collect();
+        }
+
+        let nested = included.map(|included| included.include.as_slice()).unwrap_or(&[]);
+        let alias = relation.alias.clone();
+        resolve_relation_filters(&mut relation.arguments, nested, &[], schema, &alias, depth + 1, Some(&alias));
+      }
+      Some(ParsedFilter::And(nested)) | Some(ParsedFilter::Or(nested)) | Some(ParsedFilter::Negate(nested)) => {
+        resolve_relation_filters(nested, relations, joins, schema, parent, depth, qualifier);
+      }
+      _ => {
+        if let Some(qualifier) = qualifier {
+          argument.name = format!("{}.{}", qualifier, argument.name);
+        }
+      }
+    }
+  }
+}
+
+fn parse_aggregate_key(key: &str) -> Option<ParsedAggregate> {
+  match key {
+    "_sum" => Some(ParsedAggregate::Sum),
+    "_min" => Some(ParsedAggregate::Min),
+    "_max" => Some(ParsedAggregate::Max),
+    "_avg" => Some(ParsedAggregate::Avg),
+    "_count" => Some(ParsedAggregate::Count),
+    "_countDistinct" => Some(ParsedAggregate::CountDistinct),
+    _ => None,
+  }
+}
+
 fn create_operation(metadata: Metadata) -> Result<ParsedOperation, ParserError> {
   match metadata.command.to_lowercase().as_str() {
     "findfirst" => Ok(ParsedOperation::FindFirst),
@@ -259,8 +852,12 @@ fn create_operation(metadata: Metadata) -> Result<ParsedOperation, ParserError>
     "deletemany" => Ok(ParsedOperation::DeleteMany),
     "insertone" => Ok(ParsedOperation::InsertOne),
     "insertmany" => Ok(ParsedOperation::InsertMany),
+    "upsertone" => Ok(ParsedOperation::UpsertOne),
+    "upsertmany" => Ok(ParsedOperation::UpsertMany),
     "count" => Ok(ParsedOperation::Count),
     "average" => Ok(ParsedOperation::Average),
+    "aggregate" => Ok(ParsedOperation::Aggregate),
+    "groupby" => Ok(ParsedOperation::GroupBy),
     _ => Err(ParserError::InputError("Invalid operation!".to_string())),
   }
 }
\ No newline at end of file
@@ -0,0 +1,235 @@
+#[cfg(test)]
+mod casting_tests {
+  use crate::{
+    engine::executor::introspection::schema::{ColumnSchema, DatabaseSchema, EnumSchema, TableSchema},
+    parser::{
+      casting::apply_column_types,
+      models::ModelRegistry,
+      validation::validate,
+      parsed::{ParsedFilter, ParsedStatement, ParsedUpdate, ParsedValue},
+      statement::{create_statement, Argument, ArgumentValue, Field, Metadata, Options, TimeBucket},
+    },
+  };
+
+  fn column(name: &str, data_type: &str, udt_name: &str) -> ColumnSchema {
+    ColumnSchema {
+      name: name.to_string(),
+      data_type: data_type.to_string(),
+      udt_name: udt_name.to_string(),
+      udt_schema: "pg_catalog".to_string(),
+      nullable: true,
+      ..Default::default()
+    }
+  }
+
+  fn models() -> ModelRegistry {
+    ModelRegistry::new(&DatabaseSchema {
+      schemas: vec!["shop".to_string()],
+      tables: vec![TableSchema {
+        schema: "shop".to_string(),
+        name: "orders".to_string(),
+        columns: vec![
+          column("id", "uuid", "uuid"),
+          column("total", "numeric(10,2)", "numeric"),
+          column("shipping", "money", "money"),
+          column("details", "jsonb", "jsonb"),
+          column("placed_at", "timestamp with time zone", "timestamptz"),
+          column("shipped_at", "timestamp without time zone", "timestamp"),
+          column("tags", "text[]", "_text"),
+          column("note", "text", "text"),
+          ColumnSchema {
+            udt_schema: "shop".to_string(),
+            ..column("status", "shop.order_status", "order_status")
+          },
+        ],
+        ..Default::default()
+      }],
+      views: vec![],
+      enums: vec![EnumSchema {
+        schema: "shop".to_string(),
+        name: "order_status".to_string(),
+        values: vec!["open".to_string(), "paid".to_string()],
+      }],
+    })
+  }
+
+  fn argument(name: &str, value: &str, value_type: &str, operator: Option<&str>) -> Argument {
+    Argument {
+      name: name.to_string(),
+      value: Some(value.to_string()),
+      value_type: Some(value_type.to_string()),
+      operator: operator.map(str::to_string),
+      values: None,
+      arguments: None,
+    }
+  }
+
+  fn typed(command: &str, fields: Vec<(&str, Vec<Argument>)>) -> ParsedStatement {
+    let metadata = Metadata {
+      command: command.to_string(),
+      table: "orders".to_string(),
+      schema: "shop".to_string(),
+    };
+    let fields = fields
+      .into_iter()
+      .map(|(name, arguments)| Field {
+        name: name.to_string(),
+        arguments,
+      })
+      .collect();
+
+    let mut statement = create_statement(metadata, fields, Options::default()).unwrap();
+    apply_column_types(&mut statement, &models());
+    statement
+  }
+
+  fn cast(value: ParsedValue, cast: &str) -> ParsedValue {
+    ParsedValue::Cast(Box::new(value), cast.to_string())
+  }
+
+  #[test]
+  fn test_apply_column_types_to_data() {
+    let tags = Argument {
+      values: Some(vec![ArgumentValue {
+        value: Some("gift".to_string()),
+        value_type: None,
+      }]),
+      ..argument("tags", "", "array", None)
+    };
+    let statement = typed(
+      "insertone",
+      vec![(
+        "data",
+        vec![
+          argument("id", "8c1e0a44-5f1b-4a36-9d8a-2b6f3c1d9e70", "string", None),
+          argument("total", "19.99", "float", None),
+          argument("details", "{\"gift\":true}", "json", None),
+          argument("placedAt", "2023-01-01T10:00:00.000Z", "datetime", None),
+          argument("status", "paid", "string", None),
+          argument("note", "paid", "string", None),
+          tags,
+        ],
+      )],
+    );
+
+    let values = statement.query.fields[0]
+      .arguments
+      .iter()
+      .map(|argument| argument.value.clone().unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      values,
+      vec![
+        ParsedValue::Uuid("8c1e0a44-5f1b-4a36-9d8a-2b6f3c1d9e70".to_string()),
+        ParsedValue::Decimal("19.99".to_string()),
+        ParsedValue::Jsonb("{\"gift\":true}".to_string()),
+        ParsedValue::TimestampTz("2023-01-01T10:00:00.000Z".to_string()),
+        cast(ParsedValue::String("paid".to_string()), "shop.order_status"),
+        ParsedValue::String("paid".to_string()),
+        cast(ParsedValue::Array(vec![ParsedValue::String("gift".to_string())]), "text[]"),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_apply_column_types_to_conditions() {
+    let statuses = Argument {
+      values: Some(vec![
+        ArgumentValue {
+          value: Some("open".to_string()),
+          value_type: None,
+        },
+        ArgumentValue {
+          value: Some("paid".to_string()),
+          value_type: None,
+        },
+      ]),
+      ..argument("status", "", "enum", Some("in"))
+    };
+    let statement = typed(
+      "updatemany",
+      vec![
+        ("set", vec![argument("tags", "gift", "string", Some("push"))]),
+        ("where", vec![statuses, argument("note", "gift", "string", Some("contains"))]),
+      ],
+    );
+
+    let set = &statement.query.fields[0].arguments[0];
+    assert_eq!(
+      set.update,
+      Some(ParsedUpdate::Push(cast(ParsedValue::String("gift".to_string()), "text")))
+    );
+
+    let conditions = &statement.query.fields[1].arguments;
+    assert_eq!(
+      conditions[0].filter,
+      Some(ParsedFilter::In(vec![
+        cast(ParsedValue::Enum("open".to_string()), "shop.order_status"),
+        cast(ParsedValue::Enum("paid".to_string()), "shop.order_status"),
+      ]))
+    );
+    // Pattern filters bind strings of their own.
+    assert_eq!(
+      conditions[1].filter,
+      Some(ParsedFilter::Contains(ParsedValue::String("gift".to_string())))
+    );
+  }
+
+  #[test]
+  fn test_validate_and_cast_amounts_written_as_strings() {
+    let metadata = Metadata {
+      command: "insertone".to_string(),
+      table: "orders".to_string(),
+      schema: "shop".to_string(),
+    };
+    let fields = vec![Field {
+      name: "data".to_string(),
+      arguments: vec![
+        argument("total", "12345678.90", "string", None),
+        argument("shipping", "4.95", "string", None),
+      ],
+    }];
+
+    let mut statement = create_statement(metadata, fields, Options::default()).unwrap();
+    assert!(validate(&statement, &models()).is_ok());
+
+    apply_column_types(&mut statement, &models());
+    let values = statement.query.fields[0]
+      .arguments
+      .iter()
+      .map(|argument| argument.value.clone().unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      values,
+      vec![
+        ParsedValue::Decimal("12345678.90".to_string()),
+        cast(ParsedValue::String("4.95".to_string()), "money"),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_apply_column_type_to_time_bucket() {
+    let metadata = Metadata {
+      command: "groupby".to_string(),
+      table: "orders".to_string(),
+      schema: "shop".to_string(),
+    };
+    let options = Options {
+      time_bucket: Some(TimeBucket {
+        field: "shippedAt".to_string(),
+        unit: "day".to_string(),
+        timezone: Some("Europe/Berlin".to_string()),
+        from: None,
+        to: None,
+      }),
+      ..Default::default()
+    };
+
+    let mut statement = create_statement(metadata, vec![], options).unwrap();
+    apply_column_types(&mut statement, &models());
+
+    let bucket = statement.query.options.time_bucket.unwrap();
+    assert_eq!(bucket.column_type, Some("timestamp".to_string()));
+  }
+}
@@ -1 +1,3 @@
-pub mod statement;
\ No newline at end of file
+pub mod casting;
+pub mod statement;
+pub mod validation;
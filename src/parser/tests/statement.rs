@@ -1,13 +1,18 @@
 #[cfg(test)]
 pub mod tests {
   use parser::{
+    error::ParserError,
     parsed::{
-      FieldName, ParsedArgument, ParsedField, ParsedInclude, ParsedOperation, ParsedQuery, ParsedStatement, ParsedValue,
-      QueryOptions,
+      FieldName, ParsedAggregate, ParsedArgument, ParsedField, ParsedFilter, ParsedInclude, ParsedJoin,
+      ParsedOperation, ParsedOrderBy, ParsedQuantifier, ParsedQuery,
+      ParsedRelation, ParsedRelationFilter, ParsedRelationOrder, ParsedStatement, ParsedTimeBucket, ParsedUpdate, ParsedValue, QueryOptions,
+    },
+    statement::{
+      create_statement, Argument, ArgumentValue, Field, IncludeField, Join, Metadata, Options, Order, OrderBy, Relation,
+      RelationOrder, TimeBucket,
     },
-    statement::{create_statement, Argument, Field, IncludeField, Join, Metadata, Options, Order, OrderBy},
   };
-  use std::{collections::HashMap, str::FromStr};
+  use std::str::FromStr;
 
   use crate::parser;
 
@@ -19,21 +24,183 @@ pub mod tests {
         key: "Key1".to_string(),
         joining_table: "Table2".to_string(),
         joining_key: "Key2".to_string(),
+        ..Default::default()
       }],
+      relations: None,
     };
 
     let parsed_include = include_field.parse();
 
     let expected_parsed_include = ParsedInclude {
-      joins: vec![(
-        "Table1".to_string(),
-        "Key1".to_string(),
-        "Table2".to_string(),
-        "Key2".to_string(),
-      )],
+      joins: vec![ParsedJoin {
+        kind: "INNER".to_string(),
+        table: "Table1".to_string(),
+        joining_table: "Table2".to_string(),
+        alias: None,
+        keys: vec![("Key1".to_string(), "Key2".to_string())],
+      }],
+      relations: vec![],
+    };
+
+    assert_eq!(parsed_include, Ok(expected_parsed_include));
+  }
+
+  #[test]
+  fn test_join_parse() {
+    let join = Join {
+      table: "employees".to_string(),
+      key: "manager_id".to_string(),
+      joining_table: "employees".to_string(),
+      joining_key: "id".to_string(),
+      kind: Some("left".to_string()),
+      alias: Some("manager".to_string()),
+      keys: Some(vec!["tenant_id".to_string()]),
+      joining_keys: Some(vec!["tenant_id".to_string()]),
+    };
+
+    assert_eq!(
+      join.parse(),
+      Ok(ParsedJoin {
+        kind: "LEFT".to_string(),
+        table: "employees".to_string(),
+        joining_table: "employees".to_string(),
+        alias: Some("manager".to_string()),
+        keys: vec![
+          ("manager_id".to_string(), "id".to_string()),
+          ("tenant_id".to_string(), "tenant_id".to_string()),
+        ],
+      })
+    );
+
+    let invalid_kind = Join {
+      kind: Some("cross".to_string()),
+      ..join.clone()
+    };
+    assert_eq!(
+      invalid_kind.parse(),
+      Err(ParserError::ParseError("Join kind must be inner, left, right or full!".to_string()))
+    );
+
+    let invalid_alias = Join {
+      alias: Some("manager m".to_string()),
+      ..join.clone()
+    };
+    assert_eq!(invalid_alias.parse(), Err(ParserError::InputError("Invalid join alias!".to_string())));
+
+    let uneven_keys = Join { joining_keys: None, ..join };
+    assert_eq!(
+      uneven_keys.parse(),
+      Err(ParserError::InputError("Join keys and joining keys must have the same length!".to_string()))
+    );
+  }
+
+  #[test]
+  fn test_include_field_parse_relations() {
+    let relation = |name: &str, table: &str, key: &str| Relation {
+      name: name.to_string(),
+      table: table.to_string(),
+      key: key.to_string(),
+      references: None,
+      one: None,
+      include: None,
+      select: None,
+      filter: None,
+      order_by: None,
+      take: None,
+    };
+
+    let include_field = IncludeField {
+      joins: vec![],
+      relations: Some(vec![Relation {
+        include: Some(vec![Relation {
+          references: Some("categoryId".to_string()),
+          one: Some(true),
+          ..relation("category", "categories", "id")
+        }]),
+        select: Some(vec!["id".to_string(), "publishedAt".to_string()]),
+        filter: Some(vec![Argument {
+          name: "published".to_string(),
+          value: Some("true".to_string()),
+          value_type: Some("boolean".to_string()),
+          ..Default::default()
+        }]),
+        order_by: Some(vec![OrderBy {
+          field: "publishedAt".to_string(),
+          order: Order::Desc,
+          nulls: None,
+          table: None,
+          relation: None,
+          aggregate: None,
+        }]),
+        take: Some(5),
+        ..relation("recentPosts", "posts", "authorId")
+      }]),
+    };
+
+    let expected_parsed_include = ParsedInclude {
+      joins: vec![],
+      relations: vec![ParsedRelation {
+        name: "recent_posts".to_string(),
+        table: "posts".to_string(),
+        key: "author_id".to_string(),
+        references: "id".to_string(),
+        one: false,
+        include: vec![ParsedRelation {
+          name: "category".to_string(),
+          table: "categories".to_string(),
+          key: "id".to_string(),
+          references: "category_id".to_string(),
+          one: true,
+          include: vec![],
+          select: vec![],
+          filter: vec![],
+          order_by: None,
+          take: None,
+        }],
+        select: vec!["id".to_string(), "published_at".to_string()],
+        filter: vec![ParsedArgument {
+          name: "published".to_string(),
+          value: Some(ParsedValue::Boolean("true".to_string())),
+          filter: None,
+          update: None,
+          aggregate: None,
+        }],
+        order_by: Some(vec![ParsedOrderBy {
+          field: "publishedAt".to_string(),
+          order: "desc".to_string(),
+          nulls: None,
+          table: None,
+          relation: None,
+          aggregate: None,
+        }]),
+        take: Some(5),
+      }],
     };
 
-    assert_eq!(parsed_include, expected_parsed_include);
+    assert_eq!(include_field.parse(), Ok(expected_parsed_include));
+
+    let invalid_name = IncludeField {
+      joins: vec![],
+      relations: Some(vec![relation("posts\" FROM users --", "posts", "authorId")]),
+    };
+
+    assert_eq!(
+      invalid_name.parse(),
+      Err(ParserError::InputError("Invalid relation name!".to_string()))
+    );
+
+    let negative_take = IncludeField {
+      joins: vec![],
+      relations: Some(vec![Relation {
+        take: Some(-1),
+        ..relation("posts", "posts", "authorId")
+      }]),
+    };
+
+    assert_eq!(
+      negative_take.parse(),
+      Err(ParserError::InputError("Relation take can't be negative!".to_string()))
+    );
   }
 
   // Test Field::parse function
@@ -45,6 +212,7 @@ pub mod tests {
         name: "arg1".to_string(),
         value: Some("value1".to_string()),
         value_type: Some("string".to_string()),
+        ..Default::default()
       }],
     };
 
@@ -55,6 +223,7 @@ pub mod tests {
       arguments: vec![ParsedArgument {
         name: "arg1".to_string(),
         value: Some(ParsedValue::String("value1".to_string())),
+        ..Default::default()
       }],
     });
 
@@ -74,6 +243,7 @@ pub mod tests {
       name: "arg1".to_string(),
       value: Some("value1".to_string()),
       value_type: Some("string".to_string()),
+      ..Default::default()
     };
 
     let parsed_argument_result = argument.parse();
@@ -81,26 +251,514 @@ pub mod tests {
     let expected_parsed_argument = Ok(ParsedArgument {
       name: "arg1".to_string(),
       value: Some(ParsedValue::String("value1".to_string())),
+      ..Default::default()
     });
 
     assert_eq!(parsed_argument_result, expected_parsed_argument);
   }
 
+  // Test Argument::parse function with a filter operator
+  #[test]
+  fn test_argument_parse_filter() {
+    let argument = Argument {
+      name: "createdAt".to_string(),
+      value: None,
+      value_type: Some("date".to_string()),
+      operator: Some("between".to_string()),
+      values: Some(vec![
+        ArgumentValue {
+          value: Some("2023-01-01".to_string()),
+          value_type: None,
+        },
+        ArgumentValue {
+          value: Some("2023-12-31".to_string()),
+          value_type: None,
+        },
+      ]),
+      ..Default::default()
+    };
+
+    let expected_parsed_argument = Ok(ParsedArgument {
+      name: "created_at".to_string(),
+      value: None,
+      filter: Some(ParsedFilter::Between(
+        ParsedValue::Date("2023-01-01".to_string()),
+        ParsedValue::Date("2023-12-31".to_string()),
+      )),
+      ..Default::default()
+    });
+
+    assert_eq!(argument.parse(), expected_parsed_argument);
+
+    let argument = Argument {
+      name: "deletedAt".to_string(),
+      value: None,
+      value_type: Some("null".to_string()),
+      operator: Some("equals".to_string()),
+      ..Default::default()
+    };
+
+    assert_eq!(argument.parse().unwrap().filter, Some(ParsedFilter::Equals(ParsedValue::Null)));
+
+    let argument = Argument {
+      name: "age".to_string(),
+      value: Some("18".to_string()),
+      value_type: Some("number".to_string()),
+      operator: Some("like".to_string()),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      argument.parse(),
+      Err(ParserError::ParseError("Invalid filter operator!".to_string()))
+    );
+  }
+
+  // Test Argument::parse function with a typed list
+  #[test]
+  fn test_argument_parse_list() {
+    let argument = Argument {
+      name: "id".to_string(),
+      value: None,
+      value_type: Some("number".to_string()),
+      operator: Some("notIn".to_string()),
+      values: Some(vec![
+        ArgumentValue {
+          value: Some("1".to_string()),
+          value_type: None,
+        },
+        ArgumentValue {
+          value: Some("2.5".to_string()),
+          value_type: Some("decimal".to_string()),
+        },
+      ]),
+      ..Default::default()
+    };
+
+    let expected_filter = Some(ParsedFilter::NotIn(vec![
+      ParsedValue::Number("1".to_string()),
+      ParsedValue::Decimal("2.5".to_string()),
+    ]));
+
+    assert_eq!(argument.parse().unwrap().filter, expected_filter);
+
+    let argument = Argument {
+      name: "id".to_string(),
+      value: None,
+      value_type: Some("number".to_string()),
+      operator: Some("in".to_string()),
+      values: Some(vec![]),
+      ..Default::default()
+    };
+
+    assert_eq!(argument.parse().unwrap().filter, Some(ParsedFilter::In(vec![])));
+  }
+
+  #[test]
+  fn test_argument_parse_typed_values() {
+    let typed = |value: &str, value_type: &str| {
+      Argument {
+        name: "column".to_string(),
+        value: Some(value.to_string()),
+        value_type: Some(value_type.to_string()),
+        ..Default::default()
+      }
+      .parse()
+      .unwrap()
+      .value
+    };
+
+    assert_eq!(typed("1.5", "float"), Some(ParsedValue::Float("1.5".to_string())));
+    assert_eq!(typed("{\"a\":1}", "jsonb"), Some(ParsedValue::Jsonb("{\"a\":1}".to_string())));
+    assert_eq!(typed("3q2+7w==", "bytes"), Some(ParsedValue::Bytes("3q2+7w==".to_string())));
+    assert_eq!(typed("paid", "enum"), Some(ParsedValue::Enum("paid".to_string())));
+    assert_eq!(
+      typed("2023-01-01T10:00:00+02:00", "timestamptz"),
+      Some(ParsedValue::TimestampTz("2023-01-01T10:00:00+02:00".to_string()))
+    );
+
+    let argument = Argument {
+      name: "tags".to_string(),
+      value: None,
+      value_type: Some("array".to_string()),
+      values: Some(vec![
+        ArgumentValue {
+          value: Some("new".to_string()),
+          value_type: None,
+        },
+        ArgumentValue {
+          value: None,
+          value_type: Some("null".to_string()),
+        },
+      ]),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      argument.parse().unwrap().value,
+      Some(ParsedValue::Array(vec![
+        ParsedValue::String("new".to_string()),
+        ParsedValue::Null
+      ]))
+    );
+  }
+
+  // Test Field::parse function with update operators
+  #[test]
+  fn test_field_parse_update() {
+    let increment = || Argument {
+      name: "viewCount".to_string(),
+      value: Some("1".to_string()),
+      value_type: Some("number".to_string()),
+      operator: Some("increment".to_string()),
+      ..Default::default()
+    };
+
+    let field = Field {
+      name: "set".to_string(),
+      arguments: vec![increment()],
+    };
+
+    let expected_parsed_field = Ok(ParsedField {
+      name: FieldName::Set,
+      arguments: vec![ParsedArgument {
+        name: "view_count".to_string(),
+        value: None,
+        update: Some(ParsedUpdate::Increment(ParsedValue::Number("1".to_string()))),
+        ..Default::default()
+      }],
+    });
+
+    assert_eq!(field.parse(), expected_parsed_field);
+
+    let field = Field {
+      name: "where".to_string(),
+      arguments: vec![increment()],
+    };
+
+    assert_eq!(
+      field.parse(),
+      Err(ParserError::InputError("Update operators are only allowed in set!".to_string()))
+    );
+  }
+
+  // Test Field::parse function with aggregate operators
+  #[test]
+  fn test_field_parse_aggregate() {
+    let aggregate = |name: &str, operator: &str| Argument {
+      name: name.to_string(),
+      value: None,
+      value_type: None,
+      operator: Some(operator.to_string()),
+      ..Default::default()
+    };
+
+    let field = Field {
+      name: "aggs".to_string(),
+      arguments: vec![aggregate("totalAmount", "_sum"), aggregate("_all", "_count"), aggregate("customerId", "_countDistinct")],
+    };
+
+    let parsed_argument = |name: &str, aggregate: ParsedAggregate| ParsedArgument {
+      name: name.to_string(),
+      value: None,
+      aggregate: Some(aggregate),
+      ..Default::default()
+    };
+
+    let expected_parsed_field = Ok(ParsedField {
+      name: FieldName::Aggs,
+      arguments: vec![
+        parsed_argument("total_amount", ParsedAggregate::Sum),
+        parsed_argument("_all", ParsedAggregate::Count),
+        parsed_argument("customer_id", ParsedAggregate::CountDistinct),
+      ],
+    });
+
+    assert_eq!(field.parse(), expected_parsed_field);
+
+    let field = Field {
+      name: "select".to_string(),
+      arguments: vec![aggregate("totalAmount", "_max")],
+    };
+
+    assert_eq!(
+      field.parse(),
+      Err(ParserError::InputError("Aggregate operators are only allowed in aggs and having!".to_string()))
+    );
+  }
+
+  // Test Field::parse function with aggregate groups in having
+  #[test]
+  fn test_field_parse_having() {
+    let field = Field {
+      name: "having".to_string(),
+      arguments: vec![Argument {
+        name: "_sum".to_string(),
+        value: None,
+        value_type: None,
+        arguments: Some(vec![Argument {
+          name: "totalAmount".to_string(),
+          value: Some("100".to_string()),
+          value_type: Some("number".to_string()),
+          operator: Some("gt".to_string()),
+          values: None,
+          arguments: None,
+        }]),
+        ..Default::default()
+      }],
+    };
+
+    let expected_parsed_field = Ok(ParsedField {
+      name: FieldName::Having,
+      arguments: vec![ParsedArgument {
+        name: "_sum".to_string(),
+        value: None,
+        filter: Some(ParsedFilter::And(vec![ParsedArgument {
+          name: "total_amount".to_string(),
+          value: None,
+          filter: Some(ParsedFilter::Gt(ParsedValue::Number("100".to_string()))),
+          update: None,
+          aggregate: Some(ParsedAggregate::Sum),
+        }])),
+        aggregate: Some(ParsedAggregate::Sum),
+        ..Default::default()
+      }],
+    });
+
+    assert_eq!(field.parse(), expected_parsed_field);
+
+    let field = Field {
+      name: "where".to_string(),
+      ..field
+    };
+
+    assert_eq!(
+      field.parse(),
+      Err(ParserError::InputError("Aggregate operators are only allowed in aggs and having!".to_string()))
+    );
+  }
+
+  // Test Argument::parse function with nested groups
+  #[test]
+  fn test_argument_parse_group() {
+    let argument = Argument {
+      name: "OR".to_string(),
+      value: None,
+      value_type: None,
+      arguments: Some(vec![
+        Argument {
+          name: "ownerId".to_string(),
+          value: Some("1".to_string()),
+          value_type: Some("number".to_string()),
+          operator: None,
+          values: None,
+          arguments: None,
+        },
+        Argument {
+          name: "NOT".to_string(),
+          value: None,
+          value_type: None,
+          operator: None,
+          values: None,
+          arguments: Some(vec![Argument {
+            name: "shared".to_string(),
+            value: Some("false".to_string()),
+            value_type: Some("boolean".to_string()),
+            operator: None,
+            values: None,
+            arguments: None,
+          }]),
+        },
+      ]),
+      ..Default::default()
+    };
+
+    let expected_parsed_argument = Ok(ParsedArgument {
+      name: "or".to_string(),
+      value: None,
+      filter: Some(ParsedFilter::Or(vec![
+        ParsedArgument {
+          name: "owner_id".to_string(),
+          value: Some(ParsedValue::Number("1".to_string())),
+          filter: None,
+          update: None,
+          aggregate: None,
+        },
+        ParsedArgument {
+          name: "not".to_string(),
+          value: None,
+          filter: Some(ParsedFilter::Negate(vec![ParsedArgument {
+            name: "shared".to_string(),
+            value: Some(ParsedValue::Boolean("false".to_string())),
+            filter: None,
+            update: None,
+            aggregate: None,
+          }])),
+          update: None,
+          aggregate: None,
+        },
+      ])),
+      ..Default::default()
+    });
+
+    assert_eq!(argument.parse(), expected_parsed_argument);
+  }
+
   // Test OrderBy::parse function
   #[test]
   fn test_order_by_parse() {
-    let order_by = OrderBy {
+    let order_by_vec = vec![
+      OrderBy {
+        field: "field2".to_string(),
+        order: Order::Desc,
+        nulls: Some("last".to_string()),
+        table: None,
+        relation: None,
+        aggregate: None,
+      },
+      OrderBy {
+        field: "field1".to_string(),
+        order: Order::Asc,
+        nulls: None,
+        table: Some("table2".to_string()),
+        relation: None,
+        aggregate: None,
+      },
+      OrderBy {
+        field: "id".to_string(),
+        order: Order::Desc,
+        nulls: None,
+        table: None,
+        relation: Some(RelationOrder {
+          table: "comments".to_string(),
+          key: "postId".to_string(),
+          references: None,
+          function: None,
+        }),
+        aggregate: None,
+      },
+    ];
+    let parsed_order_by = OrderBy::parse(Some(order_by_vec));
+
+    // Entries keep the order they were given in.
+    let expected_order_by = vec![
+      ParsedOrderBy {
+        field: "field2".to_string(),
+        order: "desc".to_string(),
+        nulls: Some("last".to_string()),
+        ..Default::default()
+      },
+      ParsedOrderBy {
+        field: "field1".to_string(),
+        order: "asc".to_string(),
+        table: Some("table2".to_string()),
+        ..Default::default()
+      },
+      ParsedOrderBy {
+        field: "id".to_string(),
+        order: "desc".to_string(),
+        relation: Some(ParsedRelationOrder {
+          table: "comments".to_string(),
+          key: "post_id".to_string(),
+          references: "id".to_string(),
+          function: "count".to_string(),
+        }),
+        ..Default::default()
+      },
+    ];
+
+    assert_eq!(parsed_order_by, Ok(Some(expected_order_by)));
+
+    let invalid_nulls = OrderBy {
       field: "field1".to_string(),
       order: Order::Asc,
+      nulls: Some("middle".to_string()),
+      table: None,
+      relation: None,
+      aggregate: None,
     };
 
-    let order_by_vec = vec![order_by];
-    let parsed_order_by = OrderBy::parse(Some(order_by_vec));
+    assert_eq!(
+      OrderBy::parse(Some(vec![invalid_nulls])),
+      Err(ParserError::ParseError("Nulls must be first or last!".to_string()))
+    );
+  }
 
-    let mut expected_order_by_map = HashMap::new();
-    expected_order_by_map.insert("field1".to_string(), "asc".to_string());
+  // Test OrderBy::parse function with aggregates
+  #[test]
+  fn test_order_by_parse_aggregate() {
+    let order_by = |aggregate: &str| OrderBy {
+      field: "amount".to_string(),
+      order: Order::Desc,
+      nulls: None,
+      table: None,
+      relation: None,
+      aggregate: Some(aggregate.to_string()),
+    };
 
-    assert_eq!(parsed_order_by, Some(expected_order_by_map));
+    assert_eq!(
+      OrderBy::parse(Some(vec![order_by("_countDistinct")])),
+      Ok(Some(vec![ParsedOrderBy {
+        field: "amount".to_string(),
+        order: "desc".to_string(),
+        aggregate: Some(ParsedAggregate::CountDistinct),
+        ..Default::default()
+      }]))
+    );
+    assert_eq!(
+      OrderBy::parse(Some(vec![order_by("sum")])),
+      Err(ParserError::ParseError("Invalid aggregate!".to_string()))
+    );
+  }
+
+  // Test TimeBucket::parse function
+  #[test]
+  fn test_time_bucket_parse() {
+    let bucket = TimeBucket {
+      field: "createdAt".to_string(),
+      unit: "week".to_string(),
+      timezone: Some("America/New_York".to_string()),
+      from: Some("2023-01-01 00:00:00".to_string()),
+      to: Some("2023-03-01 00:00:00".to_string()),
+    };
+
+    assert_eq!(
+      bucket.parse(),
+      Ok(ParsedTimeBucket {
+        column: "created_at".to_string(),
+        unit: "week".to_string(),
+        timezone: Some("America/New_York".to_string()),
+        column_type: None,
+        fill: Some((
+          ParsedValue::DateTime("2023-01-01 00:00:00".to_string()),
+          ParsedValue::DateTime("2023-03-01 00:00:00".to_string()),
+        )),
+      })
+    );
+
+    let invalid_unit = TimeBucket {
+      unit: "fortnight".to_string(),
+      ..bucket.clone()
+    };
+    assert_eq!(
+      invalid_unit.parse(),
+      Err(ParserError::ParseError("Invalid time bucket unit!".to_string()))
+    );
+
+    let invalid_timezone = TimeBucket {
+      timezone: Some("UTC'; DROP TABLE events; --".to_string()),
+      ..bucket.clone()
+    };
+    assert_eq!(
+      invalid_timezone.parse(),
+      Err(ParserError::ParseError("Invalid time zone!".to_string()))
+    );
+
+    let missing_to = TimeBucket { to: None, ..bucket };
+    assert_eq!(
+      missing_to.parse(),
+      Err(ParserError::InputError("Filling time buckets needs both from and to!".to_string()))
+    );
   }
 
   // Test Order::parse function
@@ -117,26 +775,48 @@ pub mod tests {
       order_by: Some(vec![OrderBy {
         field: "field1".to_string(),
         order: Order::Asc,
+        nulls: None,
+        table: None,
+        relation: None,
+        aggregate: None,
       }]),
       limit: Some(10),
       offset: Some(5),
       num_of_rows: Some(20),
       include: None,
       group_by: Some(vec!["group1".to_string(), "group2".to_string()]),
+      ..Default::default()
     };
 
     let parsed_options_result = options.parse();
 
     let expected_parsed_options = Ok(QueryOptions {
-      order_by: Some(HashMap::from_iter(vec![("field1".to_string(), "asc".to_string())])),
+      order_by: Some(vec![ParsedOrderBy {
+        field: "field1".to_string(),
+        order: "asc".to_string(),
+        nulls: None,
+        table: None,
+        relation: None,
+        aggregate: None,
+      }]),
       limit: Some("10".to_string()),
       offset: Some("5".to_string()),
       num_of_rows: Some(20),
       include: None,
       group_by: Some(vec!["group1".to_string(), "group2".to_string()]),
+      ..Default::default()
     });
 
     assert_eq!(parsed_options_result, expected_parsed_options);
+
+    let negative_skip = Options {
+      skip: Some(-1),
+      ..Default::default()
+    };
+    assert_eq!(
+      negative_skip.parse(),
+      Err(ParserError::InputError("Skip can't be negative!".to_string()))
+    );
   }
 
   // Test create_statement function
@@ -154,6 +834,7 @@ pub mod tests {
         name: "arg1".to_string(),
         value: Some("value1".to_string()),
         value_type: Some("string".to_string()),
+        ..Default::default()
       }],
     }];
 
@@ -161,12 +842,17 @@ pub mod tests {
       order_by: Some(vec![OrderBy {
         field: "field1".to_string(),
         order: Order::Asc,
+        nulls: None,
+        table: None,
+        relation: None,
+        aggregate: None,
       }]),
       limit: Some(10),
       offset: Some(5),
       num_of_rows: Some(20),
       include: None,
       group_by: Some(vec!["group1".to_string(), "group2".to_string()]),
+      ..Default::default()
     };
 
     let parsed_statement_result = create_statement(metadata, fields, options);
@@ -180,15 +866,24 @@ pub mod tests {
           arguments: vec![ParsedArgument {
             name: "arg1".to_string(),
             value: Some(ParsedValue::String("value1".to_string())),
+            ..Default::default()
           }],
         }],
         options: QueryOptions {
-          order_by: Some(HashMap::from_iter(vec![("field1".to_string(), "asc".to_string())])),
+          order_by: Some(vec![ParsedOrderBy {
+        field: "field1".to_string(),
+        order: "asc".to_string(),
+        nulls: None,
+        table: None,
+        relation: None,
+        aggregate: None,
+      }]),
           limit: Some("10".to_string()),
           offset: Some("5".to_string()),
           num_of_rows: Some(20),
           include: None,
           group_by: Some(vec!["group1".to_string(), "group2".to_string()]),
+          ..Default::default()
         },
       },
       operation: ParsedOperation::FindFirst,
@@ -196,4 +891,123 @@ pub mod tests {
 
     assert_eq!(parsed_statement_result, expected_parsed_statement);
   }
+
+  #[test]
+  fn test_create_statement_relation_filters() {
+    let argument = |name: &str, operator: &str, arguments: Option<Vec<Argument>>| Argument {
+      name: name.to_string(),
+      value: None,
+      value_type: None,
+      operator: Some(operator.to_string()),
+      arguments,
+      ..Default::default()
+    };
+    let unpaid = Argument {
+      value: Some("unpaid".to_string()),
+      value_type: Some("string".to_string()),
+      ..argument("status", "equals", None)
+    };
+
+    let metadata = Metadata {
+      command: "findmany".to_string(),
+      table: "users".to_string(),
+      schema: "billing".to_string(),
+    };
+
+    let fields = vec![Field {
+      name: "where".to_string(),
+      arguments: vec![
+        argument(
+          "invoices",
+          "some",
+          Some(vec![unpaid.clone(), argument("lines", "none", None)]),
+        ),
+        argument("tasks", "every", Some(vec![argument("doneAt", "isNotNull", None)])),
+        argument("comments", "none", None),
+      ],
+    }];
+
+    let options = Options {
+      order_by: None,
+      limit: None,
+      offset: None,
+      num_of_rows: None,
+      include: Some(IncludeField {
+        joins: vec![Join {
+          table: "users".to_string(),
+          key: "id".to_string(),
+          joining_table: "tasks".to_string(),
+          joining_key: "owner_id".to_string(),
+          kind: Some("left".to_string()),
+          alias: None,
+          keys: None,
+          joining_keys: None,
+        }],
+        relations: Some(vec![Relation {
+          name: "invoices".to_string(),
+          table: "invoices".to_string(),
+          key: "userId".to_string(),
+          references: None,
+          one: None,
+          include: None,
+          select: None,
+          filter: None,
+          order_by: None,
+          take: None,
+        }]),
+      }),
+      group_by: None,
+      ..Default::default()
+    };
+
+    let statement = create_statement(metadata, fields, options).unwrap();
+    let relation = |index: usize| match &statement.query.fields[0].arguments[index].filter {
+      Some(ParsedFilter::Relation(relation)) => relation.clone(),
+      filter => panic!("Expected a relation filter, got {:?}", filter),
+    };
+
+    assert_eq!(
+      relation(0),
+      ParsedRelationFilter {
+        quantifier: ParsedQuantifier::Some,
+        table: Some("billing.invoices".to_string()),
+        keys: vec![("user_id".to_string(), "id".to_string())],
+        alias: "auro_invoices_1".to_string(),
+        parent: "billing.users".to_string(),
+        arguments: vec![
+          ParsedArgument {
+            name: "auro_invoices_1.status".to_string(),
+            value: None,
+            filter: Some(ParsedFilter::Equals(ParsedValue::String("unpaid".to_string()))),
+            ..Default::default()
+          },
+          ParsedArgument {
+            name: "lines".to_string(),
+            value: None,
+            filter: Some(ParsedFilter::Relation(ParsedRelationFilter {
+              quantifier: ParsedQuantifier::None,
+              table: None,
+              keys: vec![],
+              alias: "auro_lines_2".to_string(),
+              parent: "auro_invoices_1".to_string(),
+              arguments: vec![],
+            })),
+            ..Default::default()
+          },
+        ],
+      }
+    );
+
+    // Joins resolve relations by the joined table, or by their alias.
+    let tasks = relation(1);
+    assert_eq!(tasks.quantifier, ParsedQuantifier::Every);
+    assert_eq!(tasks.table, Some("billing.tasks".to_string()));
+    assert_eq!(tasks.keys, vec![("owner_id".to_string(), "id".to_string())]);
+    assert_eq!(tasks.arguments[0].name, "auro_tasks_1.done_at");
+
+    // Relations named in no include are left for the engine to resolve from the foreign keys.
+    let comments = relation(2);
+    assert_eq!(comments.table, None);
+    assert_eq!(comments.alias, "auro_comments_1");
+  }
 }
@@ -0,0 +1,244 @@
+#[cfg(test)]
+mod validation_tests {
+  use crate::{
+    engine::executor::introspection::schema::{ColumnSchema, DatabaseSchema, EnumSchema, TableSchema},
+    parser::{
+      error::ParserError,
+      models::ModelRegistry,
+      statement::{create_statement, Argument, Field, IncludeField, Metadata, Options, Order, OrderBy, Relation},
+      validation::validate,
+    },
+  };
+
+  fn column(name: &str, udt_name: &str, nullable: bool, default: Option<&str>) -> ColumnSchema {
+    ColumnSchema {
+      name: name.to_string(),
+      data_type: udt_name.to_string(),
+      udt_name: udt_name.to_string(),
+      udt_schema: "pg_catalog".to_string(),
+      nullable,
+      default: default.map(str::to_string),
+      ..Default::default()
+    }
+  }
+
+  fn models() -> ModelRegistry {
+    ModelRegistry::new(&DatabaseSchema {
+      schemas: vec!["shop".to_string()],
+      tables: vec![
+        TableSchema {
+          schema: "shop".to_string(),
+          name: "orders".to_string(),
+          columns: vec![
+            column("id", "int4", false, Some("nextval('shop.orders_id_seq'::regclass)")),
+            column("customer_id", "int4", false, None),
+            column("number", "text", false, None),
+            column("note", "text", true, None),
+            column("placed_at", "timestamptz", false, Some("now()")),
+            ColumnSchema {
+              udt_schema: "shop".to_string(),
+              ..column("status", "order_status", false, Some("'open'::shop.order_status"))
+            },
+          ],
+          ..Default::default()
+        },
+        TableSchema {
+          schema: "shop".to_string(),
+          name: "order_lines".to_string(),
+          columns: vec![
+            column("order_id", "int4", false, None),
+            column("quantity", "int4", false, None),
+          ],
+          ..Default::default()
+        },
+      ],
+      views: vec![],
+      enums: vec![EnumSchema {
+        schema: "shop".to_string(),
+        name: "order_status".to_string(),
+        values: vec!["open".to_string(), "paid".to_string()],
+      }],
+    })
+  }
+
+  fn argument(name: &str, value: Option<&str>, value_type: Option<&str>, operator: Option<&str>) -> Argument {
+    Argument {
+      name: name.to_string(),
+      value: value.map(str::to_string),
+      value_type: value_type.map(str::to_string),
+      operator: operator.map(str::to_string),
+      values: None,
+      arguments: None,
+    }
+  }
+
+  fn check(command: &str, table: &str, fields: Vec<(&str, Vec<Argument>)>, options: Options) -> Result<(), ParserError> {
+    let metadata = Metadata {
+      command: command.to_string(),
+      table: table.to_string(),
+      schema: "shop".to_string(),
+    };
+    let fields = fields
+      .into_iter()
+      .map(|(name, arguments)| Field {
+        name: name.to_string(),
+        arguments,
+      })
+      .collect();
+
+    validate(&create_statement(metadata, fields, options).unwrap(), &models())
+  }
+
+  fn error(message: &str) -> Result<(), ParserError> {
+    Err(ParserError::ValidationError(message.to_string()))
+  }
+
+  #[test]
+  fn test_validate_columns() {
+    let where_field = vec![
+      argument("customerId", Some("7"), Some("number"), Some("equals")),
+      argument("status", Some("paid"), Some("string"), None),
+    ];
+    assert_eq!(check("findmany", "orders", vec![("where", where_field)], Options::default()), Ok(()));
+
+    assert_eq!(
+      check("findmany", "customers", vec![], Options::default()),
+      error("Unknown table shop.customers.")
+    );
+    assert_eq!(
+      check(
+        "findmany",
+        "orders",
+        vec![(
+          "select",
+          vec![argument("id", None, None, None), argument("total", None, None, None)]
+        )],
+        Options::default()
+      ),
+      error("Invalid argument total in select: shop.orders has no column total.")
+    );
+
+    let ordered = Options {
+      order_by: Some(vec![OrderBy {
+        field: "placedOn".to_string(),
+        order: Order::Desc,
+        nulls: None,
+        table: None,
+        relation: None,
+        aggregate: None,
+      }]),
+      ..Default::default()
+    };
+    assert_eq!(
+      check("findmany", "orders", vec![], ordered),
+      error("Invalid argument placed_on in orderBy: shop.orders has no column placed_on.")
+    );
+  }
+
+  #[test]
+  fn test_validate_value_types() {
+    assert_eq!(
+      check(
+        "findmany",
+        "orders",
+        vec![(
+          "where",
+          vec![argument("customerId", Some("seven"), Some("string"), Some("gt"))]
+        )],
+        Options::default()
+      ),
+      error("Invalid argument customer_id in where: column customer_id is int4, got a string.")
+    );
+    assert_eq!(
+      check(
+        "updatemany",
+        "orders",
+        vec![
+          (
+            "set",
+            vec![
+              argument("note", None, Some("null"), None),
+              argument("number", None, Some("null"), None)
+            ]
+          ),
+          ("where", vec![argument("id", Some("1"), Some("number"), None)]),
+        ],
+        Options::default()
+      ),
+      error("Invalid argument number in set: column number can't be null.")
+    );
+    assert_eq!(
+      check(
+        "updatemany",
+        "orders",
+        vec![("set", vec![argument("status", Some("1"), Some("number"), None)])],
+        Options::default()
+      ),
+      error("Invalid argument status in set: column status is order_status, got a number.")
+    );
+  }
+
+  #[test]
+  fn test_validate_required_insert_columns() {
+    let data = vec![
+      argument("customerId", Some("7"), Some("number"), None),
+      argument("number", Some("A-1"), Some("string"), None),
+    ];
+    assert_eq!(check("insertone", "orders", vec![("data", data.clone())], Options::default()), Ok(()));
+
+    assert_eq!(
+      check("insertone", "orders", vec![("data", data[..1].to_vec())], Options::default()),
+      error("Missing argument number in data: shop.orders requires column number.")
+    );
+  }
+
+  #[test]
+  fn test_validate_relations() {
+    let included = |select: Option<Vec<String>>| Options {
+      include: Some(IncludeField {
+        joins: vec![],
+        relations: Some(vec![Relation {
+          name: "lines".to_string(),
+          table: "order_lines".to_string(),
+          key: "orderId".to_string(),
+          select,
+          ..Default::default()
+        }]),
+      }),
+      ..Options::default()
+    };
+    assert_eq!(
+      check(
+        "findmany",
+        "orders",
+        vec![],
+        included(Some(vec!["quantity".to_string(), "price".to_string()]))
+      ),
+      error("Invalid argument price in include: shop.order_lines has no column price.")
+    );
+
+    // Columns inside a relation filter belong to the related table.
+    let relation_filter = |column: &str| Argument {
+      arguments: Some(vec![argument(column, Some("2"), Some("number"), Some("gte"))]),
+      ..argument("lines", None, None, Some("some"))
+    };
+    assert_eq!(
+      check(
+        "findmany",
+        "orders",
+        vec![("where", vec![relation_filter("quantity")])],
+        included(None)
+      ),
+      Ok(())
+    );
+    assert_eq!(
+      check(
+        "findmany",
+        "orders",
+        vec![("where", vec![relation_filter("amount")])],
+        included(None)
+      ),
+      error("Invalid argument auro_lines_1.amount in where: shop.order_lines has no column amount.")
+    );
+  }
+}
@@ -0,0 +1,373 @@
+use super::{
+  error::ParserError,
+  models::ModelRegistry,
+  parsed::{
+    FieldName, ParsedArgument, ParsedField, ParsedFilter, ParsedOperation, ParsedRelation, ParsedStatement, ParsedUpdate,
+    ParsedValue, QueryOptions,
+  },
+  utils,
+};
+use crate::engine::executor::introspection::schema::ColumnSchema;
+
+/// Checks the table, columns and values of a statement against the registered models, so mistakes
+/// are reported with the field and argument they come from before any SQL reaches the database.
+pub fn validate(statement: &ParsedStatement, models: &ModelRegistry) -> Result<(), ParserError> {
+  let query = &statement.query;
+  let model = Model::find(models, &query.schema, &query.table)?;
+
+  for field in &query.fields {
+    let label = field_label(&field.name);
+
+    match field.name {
+      FieldName::Where | FieldName::Having => validate_conditions(&model, label, &field.arguments)?,
+      FieldName::Set | FieldName::Data => {
+        for argument in &field.arguments {
+          model.assign(label, argument)?;
+        }
+      }
+      FieldName::Select | FieldName::Return | FieldName::Conflict | FieldName::Aggs => {
+        for argument in &field.arguments {
+          model.column(label, &argument.name)?;
+        }
+      }
+      _ => {}
+    }
+  }
+
+  if matches!(
+    statement.operation,
+    ParsedOperation::InsertOne | ParsedOperation::InsertMany | ParsedOperation::UpsertOne | ParsedOperation::UpsertMany
+  ) {
+    model.require(query.fields.iter().find(|field| field.name == FieldName::Data))?;
+  }
+
+  validate_options(&model, &query.options)
+}
+
+/// A table or view of the registry, with the alias its columns are qualified by inside a relation
+/// filter.
+struct Model<'a> {
+  models: &'a ModelRegistry,
+  name: String,
+  alias: Option<String>,
+  columns: &'a [ColumnSchema],
+}
+
+impl<'a> Model<'a> {
+  fn find(models: &'a ModelRegistry, schema: &str, table: &str) -> Result<Self, ParserError> {
+    match models.columns(schema, table) {
+      Some(columns) => Ok(Model {
+        models,
+        name: format!("{}.{}", schema, table),
+        alias: None,
+        columns,
+      }),
+      None => Err(ParserError::ValidationError(format!("Unknown table {}.{}.", schema, table))),
+    }
+  }
+
+  fn related(&self, label: &str, argument: &str, schema: &str, table: &str) -> Result<Model<'a>, ParserError> {
+    Model::find(self.models, schema, table)
+      .map_err(|_| invalid(label, argument, format!("there is no table {}.{}", schema, table)))
+  }
+
+  /// Finds the column an argument names. Names that aren't plain column names, such as columns of
+  /// joined tables, `*`, `_all` or expressions, aren't checked.
+  fn column(&self, label: &str, name: &str) -> Result<Option<&'a ColumnSchema>, ParserError> {
+    let column = match self.alias.as_deref() {
+      Some(alias) => name
+        .strip_prefix(alias)
+        .and_then(|name| name.strip_prefix('.'))
+        .unwrap_or(name),
+      None => name,
+    };
+
+    if column == "_all" || !column.chars().all(|char| char.is_alphanumeric() || char == '_') {
+      return Ok(None);
+    }
+
+    match self.columns.iter().find(|schema| schema.name == column) {
+      Some(schema) => Ok(Some(schema)),
+      None => Err(invalid(label, name, format!("{} has no column {}", self.name, column))),
+    }
+  }
+
+  /// Checks that `value` can be bound to `column`. `NULL` and custom SQL fit every column.
+  fn value(&self, label: &str, argument: &str, column: &ColumnSchema, value: &ParsedValue) -> Result<(), ParserError> {
+    if self.accepts(column, value) {
+      return Ok(());
+    }
+
+    Err(invalid(
+      label,
+      argument,
+      format!("column {} is {}, got {}", column.name, column.data_type, value_kind(value)),
+    ))
+  }
+
+  fn accepts(&self, column: &ColumnSchema, value: &ParsedValue) -> bool {
+    if matches!(value, ParsedValue::Null | ParsedValue::Custom(_)) {
+      return true;
+    }
+    if let ParsedValue::Cast(value, _) = value {
+      return self.accepts(column, value);
+    }
+    if self.models.is_enum(column) {
+      return matches!(value, ParsedValue::String(_) | ParsedValue::Enum(_));
+    }
+    // Array columns also take array literals written as strings, e.g. `{a,b}`.
+    if column.udt_name.starts_with('_') {
+      return matches!(value, ParsedValue::Array(_) | ParsedValue::String(_));
+    }
+    if matches!(value, ParsedValue::Array(_)) {
+      return false;
+    }
+
+    match column.udt_name.as_str() {
+      "int2" | "int4" | "int8" | "float4" | "float8" | "oid" => {
+        matches!(
+          value,
+          ParsedValue::Number(_) | ParsedValue::Float(_) | ParsedValue::Decimal(_)
+        )
+      }
+      // Exact amounts are also written as strings, so no precision is lost on the way.
+      "numeric" | "money" => matches!(
+        value,
+        ParsedValue::Number(_) | ParsedValue::Float(_) | ParsedValue::Decimal(_) | ParsedValue::String(_)
+      ),
+      "bool" => matches!(value, ParsedValue::Boolean(_)),
+      "date" | "timestamp" | "timestamptz" | "time" | "timetz" => {
+        matches!(
+          value,
+          ParsedValue::Date(_) | ParsedValue::DateTime(_) | ParsedValue::TimestampTz(_) | ParsedValue::String(_)
+        )
+      }
+      "uuid" => matches!(value, ParsedValue::Uuid(_) | ParsedValue::String(_)),
+      "json" | "jsonb" => matches!(
+        value,
+        ParsedValue::Json(_) | ParsedValue::Jsonb(_) | ParsedValue::String(_)
+      ),
+      "bytea" => matches!(value, ParsedValue::Bytes(_)),
+      "text" | "varchar" | "bpchar" | "citext" | "name" => matches!(
+        value,
+        ParsedValue::String(_) | ParsedValue::Uuid(_) | ParsedValue::Json(_) | ParsedValue::Date(_) | ParsedValue::DateTime(_)
+      ),
+      // Types without a mapping are left for the database to check.
+      _ => true,
+    }
+  }
+
+  /// Checks a column written by a `set` or `data` argument and the value written to it.
+  fn assign(&self, label: &str, argument: &ParsedArgument) -> Result<(), ParserError> {
+    let column = match self.column(label, &argument.name)? {
+      Some(column) => column,
+      None => return Ok(()),
+    };
+
+    match &argument.update {
+      Some(ParsedUpdate::Increment(value))
+      | Some(ParsedUpdate::Decrement(value))
+      | Some(ParsedUpdate::Multiply(value))
+      | Some(ParsedUpdate::Divide(value)) => self.value(label, &argument.name, column, value),
+      Some(_) => Ok(()),
+      None => match &argument.value {
+        Some(ParsedValue::Null) | None if !column.nullable => Err(invalid(
+          label,
+          &argument.name,
+          format!("column {} can't be null", column.name),
+        )),
+        Some(value) => self.value(label, &argument.name, column, value),
+        None => Ok(()),
+      },
+    }
+  }
+
+  /// Checks that inserted rows set every column that has no default and can't be null.
+  fn require(&self, data: Option<&ParsedField>) -> Result<(), ParserError> {
+    let arguments = data.map(|data| data.arguments.as_slice()).unwrap_or(&[]);
+
+    let missing = self.columns.iter().find(|column| {
+      !column.nullable
+        && column.default.is_none()
+        && column.identity.is_none()
+        && !arguments.iter().any(|argument| argument.name == column.name)
+    });
+
+    match missing {
+      Some(column) => Err(ParserError::ValidationError(format!(
+        "Missing argument {} in data: {} requires column {}.",
+        column.name, self.name, column.name
+      ))),
+      None => Ok(()),
+    }
+  }
+}
+
+fn validate_conditions(model: &Model, label: &str, arguments: &[ParsedArgument]) -> Result<(), ParserError> {
+  for argument in arguments {
+    match &argument.filter {
+      Some(ParsedFilter::And(nested)) | Some(ParsedFilter::Or(nested)) | Some(ParsedFilter::Negate(nested)) => {
+        validate_conditions(model, label, nested)?
+      }
+      Some(ParsedFilter::Relation(relation)) => {
+        // Relations left for the foreign keys are resolved, and checked, by the database.
+        let (schema, table) = match relation.table.as_deref().and_then(|table| table.split_once('.')) {
+          Some(table) => table,
+          None => continue,
+        };
+
+        let related = Model {
+          alias: Some(relation.alias.clone()),
+          ..model.related(label, &argument.name, schema, table)?
+        };
+        validate_conditions(&related, label, &relation.arguments)?;
+      }
+      filter => {
+        let column = match model.column(label, &argument.name)? {
+          Some(column) => column,
+          None => continue,
+        };
+
+        // Aggregates compare counts and sums rather than the column's own values.
+        if argument.aggregate.is_some() {
+          continue;
+        }
+
+        for value in compared_values(filter, &argument.value) {
+          model.value(label, &argument.name, column, value)?;
+        }
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn validate_options(model: &Model, options: &QueryOptions) -> Result<(), ParserError> {
+  for order_by in options.order_by.iter().flatten() {
+    if order_by.relation.is_none() && order_by.table.is_none() {
+      model.column("orderBy", &utils::camel_to_snake_case(&order_by.field))?;
+    }
+  }
+
+  for column in options.group_by.iter().flatten() {
+    model.column("groupBy", column)?;
+  }
+
+  for argument in options.cursor.iter().flatten() {
+    model.column("cursor", &argument.name)?;
+  }
+
+  if let Some(bucket) = &options.time_bucket {
+    if let Some(column) = model.column("timeBucket", &bucket.column)? {
+      if !column.udt_name.starts_with("timestamp") && column.udt_name != "date" {
+        return Err(invalid(
+          "timeBucket",
+          &bucket.column,
+          format!("column {} is {}, not a date or timestamp", column.name, column.data_type),
+        ));
+      }
+    }
+  }
+
+  if let Some(include) = &options.include {
+    let schema = model.name.split('.').next().unwrap_or_default();
+
+    for join in &include.joins {
+      let joined = model.related("include", &join.joining_table, schema, &join.joining_table)?;
+
+      for (key, joining_key) in &join.keys {
+        if model.name == format!("{}.{}", schema, join.table) {
+          model.column("include", key)?;
+        }
+        joined.column("include", joining_key)?;
+      }
+    }
+
+    validate_relations(model, schema, &include.relations)?;
+  }
+
+  Ok(())
+}
+
+fn validate_relations(parent: &Model, schema: &str, relations: &[ParsedRelation]) -> Result<(), ParserError> {
+  for relation in relations {
+    let related = parent.related("include", &relation.name, schema, &relation.table)?;
+
+    related.column("include", &relation.key)?;
+    parent.column("include", &relation.references)?;
+
+    for column in &relation.select {
+      related.column("include", column)?;
+    }
+    for order_by in relation.order_by.iter().flatten() {
+      related.column("include", &utils::camel_to_snake_case(&order_by.field))?;
+    }
+
+    validate_conditions(&related, "include", &relation.filter)?;
+    validate_relations(&related, schema, &relation.include)?;
+  }
+
+  Ok(())
+}
+
+/// Values a filter compares its column to.
+fn compared_values<'a>(filter: &'a Option<ParsedFilter>, value: &'a Option<ParsedValue>) -> Vec<&'a ParsedValue> {
+  match filter {
+    Some(ParsedFilter::Equals(value))
+    | Some(ParsedFilter::Not(value))
+    | Some(ParsedFilter::Gt(value))
+    | Some(ParsedFilter::Gte(value))
+    | Some(ParsedFilter::Lt(value))
+    | Some(ParsedFilter::Lte(value))
+    | Some(ParsedFilter::Contains(value))
+    | Some(ParsedFilter::StartsWith(value))
+    | Some(ParsedFilter::EndsWith(value)) => vec![value],
+    Some(ParsedFilter::In(values)) | Some(ParsedFilter::NotIn(values)) => values.iter().collect(),
+    Some(ParsedFilter::Between(from, to)) => vec![from, to],
+    Some(_) => vec![],
+    None => value.iter().collect(),
+  }
+}
+
+fn invalid(label: &str, argument: &str, reason: String) -> ParserError {
+  ParserError::ValidationError(format!("Invalid argument {} in {}: {}.", argument, label, reason))
+}
+
+fn field_label(name: &FieldName) -> &'static str {
+  match name {
+    FieldName::Where => "where",
+    FieldName::Select => "select",
+    FieldName::Include => "include",
+    FieldName::Data => "data",
+    FieldName::Set => "set",
+    FieldName::Return => "return",
+    FieldName::NoReturn => "no_return",
+    FieldName::Aggs => "aggs",
+    FieldName::From => "from",
+    FieldName::Conflict => "conflict",
+    FieldName::Having => "having",
+  }
+}
+
+fn value_kind(value: &ParsedValue) -> &'static str {
+  match value {
+    ParsedValue::String(_) => "a string",
+    ParsedValue::Number(_) => "a number",
+    ParsedValue::Boolean(_) => "a boolean",
+    ParsedValue::Float(_) => "a float",
+    ParsedValue::Date(_) => "a date",
+    ParsedValue::DateTime(_) => "a datetime",
+    ParsedValue::TimestampTz(_) => "a timestamp with time zone",
+    ParsedValue::Uuid(_) => "a uuid",
+    ParsedValue::Json(_) => "json",
+    ParsedValue::Jsonb(_) => "jsonb",
+    ParsedValue::Decimal(_) => "a decimal",
+    ParsedValue::Bytes(_) => "bytes",
+    ParsedValue::Enum(_) => "an enum label",
+    ParsedValue::Array(_) => "an array",
+    ParsedValue::Cast(value, _) => value_kind(value),
+    ParsedValue::Custom(_) => "custom SQL",
+    ParsedValue::Null => "null",
+  }
+}